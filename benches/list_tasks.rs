@@ -0,0 +1,66 @@
+//! Mesure la latence de `list` sur une base de 10 000 tâches.
+//!
+//! ```bash
+//! cargo bench --bench list_tasks
+//! ```
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use std::hint::black_box;
+use std::path::PathBuf;
+use task_cli::storage::{ConflictPolicy, TaskQuery, TaskStorage};
+use task_cli::task::{Priority, Status, Task};
+
+const TASK_COUNT: usize = 10_000;
+
+/// Crée une base temporaire remplie de `TASK_COUNT` tâches, avec deux tags chacune
+fn populated_storage() -> (TaskStorage, PathBuf) {
+    let db_path = std::env::temp_dir().join(format!("task_cli_bench_{}.db", uuid::Uuid::new_v4()));
+    let mut storage = TaskStorage::new(db_path.clone()).expect("Impossible de créer la base");
+
+    let priorities = [Priority::High, Priority::Medium, Priority::Low];
+    let statuses = [Status::NotStarted, Status::InProgress, Status::Completed];
+    let tasks: Vec<Task> = (0..TASK_COUNT)
+        .map(|i| {
+            Task::new(
+                format!("Tâche de test numéro {}", i),
+                Some(vec![format!("projet-{}", i % 50), "bench".to_string()]),
+                Some(statuses[i % statuses.len()].clone()),
+                Some(priorities[i % priorities.len()].clone()),
+                None,
+            )
+        })
+        .collect();
+    storage
+        .import_tasks(&tasks, ConflictPolicy::Skip)
+        .expect("Impossible d'insérer les tâches");
+
+    (storage, db_path)
+}
+
+fn bench_list(c: &mut Criterion) {
+    let (storage, db_path) = populated_storage();
+
+    c.bench_function("list 10k tâches", |b| {
+        b.iter(|| black_box(storage.get_all_tasks().unwrap()))
+    });
+
+    c.bench_function("list 10k tâches --status inprogress", |b| {
+        let query = TaskQuery::new().status(Status::InProgress);
+        b.iter(|| black_box(storage.query_tasks(&query).unwrap()))
+    });
+
+    c.bench_function("list 10k tâches, tag projet-7", |b| {
+        let query = TaskQuery::new().tag("projet-7");
+        b.iter(|| black_box(storage.query_tasks(&query).unwrap()))
+    });
+
+    drop(storage);
+    let _ = std::fs::remove_file(&db_path);
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().sample_size(20);
+    targets = bench_list
+}
+criterion_main!(benches);
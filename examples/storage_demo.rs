@@ -26,7 +26,9 @@ fn main() {
     println!("   Priorité: {:?}", task.priority);
     println!(
         "   Date de fin: {}",
-        task.due_date.format("%Y-%m-%d %H:%M:%S UTC")
+        task.due_date
+            .map(|d| d.format("%Y-%m-%d %H:%M:%S UTC").to_string())
+            .unwrap_or_else(|| "aucune".to_string())
     );
 
     // Ajouter la task à la base de données
@@ -1,20 +1,12 @@
+use crate::commands::common::{db_path, find_active_project, find_task};
+use crate::dates;
+use crate::display::{OutputFormat, format_due, format_estimate, print_json, short_id_label};
+use crate::estimate::Estimate;
+use crate::recurrence::Recurrence;
 use crate::storage::TaskStorage;
 use crate::task::{Priority, Task};
-use chrono::DateTime;
 use clap::ArgMatches;
 use colored::Colorize;
-use std::path::PathBuf;
-
-/// Obtient le chemin de la base de données
-fn get_db_path() -> PathBuf {
-    if let Some(proj_dirs) = directories::ProjectDirs::from("", "", "task-cli") {
-        let data_dir = proj_dirs.data_dir();
-        std::fs::create_dir_all(data_dir).ok();
-        data_dir.join("tasks.db")
-    } else {
-        PathBuf::from("tasks.db")
-    }
-}
 
 /// Convertit une chaîne de priorité en enum Priority
 fn parse_priority(priority_str: &str) -> Result<Priority, String> {
@@ -29,30 +21,9 @@ fn parse_priority(priority_str: &str) -> Result<Priority, String> {
     }
 }
 
-/// Convertit une chaîne de date en DateTime<Utc>
-fn parse_due_date(date_str: &str) -> Result<chrono::DateTime<chrono::Utc>, String> {
-    // Essayer le format RFC3339
-    if let Ok(dt) = DateTime::parse_from_rfc3339(date_str) {
-        return Ok(dt.with_timezone(&chrono::Utc));
-    }
-
-    // Essayer le format YYYY-MM-DD
-    if let Ok(naive_date) = chrono::NaiveDate::parse_from_str(date_str, "%Y-%m-%d") {
-        let naive_datetime = naive_date.and_hms_opt(0, 0, 0).unwrap();
-        return Ok(chrono::DateTime::<chrono::Utc>::from_naive_utc_and_offset(
-            naive_datetime,
-            chrono::Utc,
-        ));
-    }
-
-    Err(format!(
-        "Format de date invalide '{}'. Utilisez: YYYY-MM-DD ou RFC3339",
-        date_str
-    ))
-}
-
 pub fn handle_add(matches: &ArgMatches) {
     let description = matches.get_one::<String>("description").unwrap();
+    let format = OutputFormat::from_matches(matches);
 
     // Parser les tags
     let tags = matches.get_one::<String>("tags").map(|tags_str| {
@@ -78,8 +49,8 @@ pub fn handle_add(matches: &ArgMatches) {
 
     // Parser la date de fin
     let due_date = if let Some(due_str) = matches.get_one::<String>("due") {
-        match parse_due_date(due_str) {
-            Ok(d) => Some(d),
+        match dates::parse_due(due_str) {
+            Ok(d) => d,
             Err(e) => {
                 eprintln!("{} {}", "Erreur:".red(), e);
                 return;
@@ -89,35 +60,112 @@ pub fn handle_add(matches: &ArgMatches) {
         None
     };
 
+    // Parser la règle de récurrence
+    let recurrence = match matches
+        .get_one::<String>("recur")
+        .map(|r| Recurrence::parse(r))
+    {
+        Some(Ok(r)) => Some(r),
+        Some(Err(e)) => {
+            eprintln!("{} {}", "Erreur:".red(), e);
+            return;
+        }
+        None => None,
+    };
+
+    // Parser l'estimation d'effort
+    let estimate = match matches
+        .get_one::<String>("estimate")
+        .map(|e| Estimate::parse(e))
+    {
+        Some(Ok(e)) => Some(e),
+        Some(Err(e)) => {
+            eprintln!("{} {}", "Erreur:".red(), e);
+            return;
+        }
+        None => None,
+    };
+
     // Créer la task
-    let task = Task::new(description, tags.clone(), None, priority.clone(), due_date);
+    let mut task = Task::new(description, tags.clone(), None, priority.clone(), due_date);
+    task.recurrence = recurrence;
+    task.estimate = estimate;
 
     // Ouvrir la base de données et ajouter la task
-    let db_path = get_db_path();
+    let db_path = db_path(matches);
     match TaskStorage::new(db_path) {
-        Ok(mut storage) => match storage.add_task(&task) {
-            Ok(_) => {
-                println!("{}", "✓ Tâche ajoutée avec succès!".green());
-                println!("  {}: {}", "ID".cyan(), task.id);
-                println!("  {}: {}", "Description".cyan(), description);
-                if let Some(t) = &tags {
-                    if !t.is_empty() {
+        Ok(mut storage) => {
+            // Résoudre la tâche parente
+            let parent = match matches
+                .get_one::<String>("parent")
+                .map(|parent_str| find_task(&storage, parent_str))
+            {
+                Some(Ok(parent)) => Some(parent),
+                Some(Err(e)) => {
+                    eprintln!("{} {}", "Erreur:".red(), e);
+                    return;
+                }
+                None => None,
+            };
+            task.parent_id = parent.as_ref().map(|p| p.id);
+
+            // Rattacher au projet, qui doit exister et ne pas être archivé
+            if let Some(project_str) = matches.get_one::<String>("project") {
+                match find_active_project(&storage, project_str) {
+                    Ok(project) => task.project = Some(project.name),
+                    Err(e) => {
+                        eprintln!("{} {}", "Erreur:".red(), e);
+                        return;
+                    }
+                }
+            }
+
+            match storage.add_task(&task) {
+                Ok(short_id) => {
+                    task.short_id = Some(short_id);
+                    if format == OutputFormat::Json {
+                        print_json(&task);
+                        return;
+                    }
+
+                    println!("{}", "✓ Tâche ajoutée avec succès!".green());
+                    println!("  {}: {}", "ID".cyan(), short_id);
+                    println!("  {}: {}", "UUID".cyan(), task.id);
+                    println!("  {}: {}", "Description".cyan(), description);
+                    if let Some(t) = &tags
+                        && !t.is_empty()
+                    {
                         println!("  {}: {}", "Tags".cyan(), t.join(", "));
                     }
+                    if let Some(p) = &priority {
+                        println!("  {}: {:?}", "Priorité".cyan(), p);
+                    }
+                    if let Some(due_date) = &task.due_date {
+                        println!("  {}: {}", "Date de fin".cyan(), format_due(due_date));
+                    }
+                    if let Some(recurrence) = &task.recurrence {
+                        println!("  {}: {}", "Récurrence".cyan(), recurrence);
+                    }
+                    if let Some(parent) = &parent {
+                        println!(
+                            "  {}: [{}] {}",
+                            "Parente".cyan(),
+                            short_id_label(parent),
+                            parent.description
+                        );
+                    }
+                    if let Some(project) = &task.project {
+                        println!("  {}: {}", "Projet".cyan(), project);
+                    }
+                    if let Some(estimate) = &task.estimate {
+                        println!("  {}: {}", "Estimation".cyan(), format_estimate(estimate));
+                    }
                 }
-                if let Some(p) = &priority {
-                    println!("  {}: {:?}", "Priorité".cyan(), p);
+                Err(e) => {
+                    eprintln!("{} Impossible d'ajouter la tâche: {}", "Erreur:".red(), e);
                 }
-                println!(
-                    "  {}: {}",
-                    "Date de fin".cyan(),
-                    task.due_date.format("%Y-%m-%d")
-                );
-            }
-            Err(e) => {
-                eprintln!("{} Impossible d'ajouter la tâche: {}", "Erreur:".red(), e);
             }
-        },
+        }
         Err(e) => {
             eprintln!(
                 "{} Impossible d'ouvrir la base de données: {}",
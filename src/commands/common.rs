@@ -0,0 +1,190 @@
+use crate::dates;
+use crate::display::short_id_label;
+use crate::storage::{Project, TaskStorage};
+use crate::task::{Task, TaskRef};
+use crate::urgency::UrgencyCoefficients;
+use clap::ArgMatches;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+/// Variable d'environnement définissant le chemin de la base de données
+pub const DB_ENV_VAR: &str = "TASK_CLI_DB";
+
+/// Obtient le chemin de la base de données, par ordre de priorité :
+/// option globale `--db`, variable `TASK_CLI_DB`, puis répertoire de données utilisateur.
+/// Le répertoire parent est créé s'il n'existe pas.
+pub fn db_path(matches: &ArgMatches) -> PathBuf {
+    let path = matches
+        .get_one::<String>("db")
+        .map(PathBuf::from)
+        .or_else(|| {
+            std::env::var_os(DB_ENV_VAR)
+                .filter(|v| !v.is_empty())
+                .map(PathBuf::from)
+        })
+        .unwrap_or_else(default_db_path);
+
+    if let Some(parent) = path.parent()
+        && !parent.as_os_str().is_empty()
+    {
+        std::fs::create_dir_all(parent).ok();
+    }
+    path
+}
+
+/// Variable d'environnement définissant le fuseau horaire des dates
+pub const TZ_ENV_VAR: &str = "TASK_CLI_TZ";
+
+/// Définit le fuseau horaire des dates, par ordre de priorité : option globale `--tz`,
+/// variable `TASK_CLI_TZ`, puis fuseau du système
+pub fn configure_timezone(matches: &ArgMatches) -> Result<(), String> {
+    let name = matches
+        .get_one::<String>("tz")
+        .cloned()
+        .or_else(|| std::env::var(TZ_ENV_VAR).ok().filter(|v| !v.is_empty()));
+
+    if let Some(name) = name {
+        dates::set_timezone(dates::parse_timezone(&name)?);
+    }
+    Ok(())
+}
+
+/// Pagination demandée par `--limit` et `--offset`
+pub fn pagination(matches: &ArgMatches) -> Result<(Option<u32>, Option<u32>), String> {
+    let number = |name: &str| -> Result<Option<u32>, String> {
+        match matches.get_one::<String>(name) {
+            Some(value) => value.trim().parse::<u32>().map(Some).map_err(|_| {
+                format!(
+                    "Nombre invalide '{}' pour --{}. Utilisez un entier positif ou nul",
+                    value, name
+                )
+            }),
+            None => Ok(None),
+        }
+    };
+    Ok((number("limit")?, number("offset")?))
+}
+
+/// Variable d'environnement redéfinissant les coefficients du score d'urgence
+pub const URGENCY_ENV_VAR: &str = "TASK_CLI_URGENCY";
+
+/// Coefficients du score d'urgence : valeurs par défaut, redéfinies par la variable
+/// `TASK_CLI_URGENCY` (ex: `due=15,blocked=-8`)
+pub fn urgency_coefficients() -> Result<UrgencyCoefficients, String> {
+    match std::env::var(URGENCY_ENV_VAR) {
+        Ok(value) => UrgencyCoefficients::parse(&value),
+        Err(_) => Ok(UrgencyCoefficients::default()),
+    }
+}
+
+/// Chemin par défaut : `tasks.db` dans le répertoire de données de l'application
+fn default_db_path() -> PathBuf {
+    if let Some(proj_dirs) = directories::ProjectDirs::from("", "", "task-cli") {
+        proj_dirs.data_dir().join("tasks.db")
+    } else {
+        PathBuf::from("tasks.db")
+    }
+}
+
+/// Analyse l'identifiant saisi par l'utilisateur (ID court, UUID complet ou préfixe d'UUID)
+pub fn parse_task_ref(id_str: &str) -> Result<TaskRef, String> {
+    TaskRef::parse(id_str).ok_or_else(|| {
+        format!(
+            "UUID invalide: '{}'. Utilisez un ID court, un UUID ou un préfixe d'UUID",
+            id_str
+        )
+    })
+}
+
+/// Résout une référence vers l'UUID d'une task unique, hors corbeille
+pub fn resolve_task_id(
+    storage: &TaskStorage,
+    task_ref: &TaskRef,
+    id_str: &str,
+) -> Result<Uuid, String> {
+    single_task_id(storage.resolve_task_ref(task_ref), id_str)
+}
+
+/// Analyse et résout une référence saisie par l'utilisateur, puis récupère la task, hors corbeille
+pub fn find_task(storage: &TaskStorage, id_str: &str) -> Result<Task, String> {
+    let task_ref = parse_task_ref(id_str)?;
+    let task_id = resolve_task_id(storage, &task_ref, id_str)?;
+    match storage.get_task(&task_id) {
+        Ok(Some(task)) => Ok(task),
+        Ok(None) => Err(format!("Tâche introuvable avec l'ID: {}", id_str)),
+        Err(e) => Err(format!("Impossible de récupérer la tâche: {}", e)),
+    }
+}
+
+/// Résout une référence vers l'UUID d'une task unique de la corbeille
+pub fn resolve_trashed_task_id(
+    storage: &TaskStorage,
+    task_ref: &TaskRef,
+    id_str: &str,
+) -> Result<Uuid, String> {
+    single_task_id(storage.resolve_trashed_task_ref(task_ref), id_str)
+}
+
+fn single_task_id(ids: rusqlite::Result<Vec<Uuid>>, id_str: &str) -> Result<Uuid, String> {
+    let ids = ids.map_err(|e| format!("Impossible de récupérer la tâche: {}", e))?;
+
+    match ids.as_slice() {
+        [id] => Ok(*id),
+        [] => Err(format!("Tâche introuvable avec l'ID: {}", id_str)),
+        _ => Err(format!(
+            "ID ambigu '{}': {} tâches correspondent, précisez davantage",
+            id_str,
+            ids.len()
+        )),
+    }
+}
+
+/// Vérifie qu'une task peut être complétée (`complete`, `edit --status completed`) :
+/// sans `force`, aucune de ses sous-tâches directes ni de ses dépendances ne doit rester ouverte.
+pub fn check_can_complete(storage: &TaskStorage, task: &Task, force: bool) -> Result<(), String> {
+    if force {
+        return Ok(());
+    }
+
+    let subtasks = storage
+        .get_subtasks(&task.id)
+        .map_err(|e| format!("Impossible de récupérer les sous-tâches: {}", e))?;
+    let open = subtasks.iter().filter(|t| t.is_open()).count();
+    if open > 0 {
+        return Err(format!(
+            "La tâche a {} sous-tâche(s) non terminée(s). Utilisez --force pour la compléter quand même",
+            open
+        ));
+    }
+
+    let blockers = storage
+        .get_blockers(&task.id)
+        .map_err(|e| format!("Impossible de récupérer les dépendances: {}", e))?;
+    if !blockers.is_empty() {
+        let names: Vec<String> = blockers
+            .iter()
+            .map(|t| format!("[{}] {}", short_id_label(t), t.description))
+            .collect();
+        return Err(format!(
+            "La tâche est bloquée par: {}. Utilisez --force pour la compléter quand même",
+            names.join(", ")
+        ));
+    }
+    Ok(())
+}
+
+/// Récupère un projet actif par son nom, pour y rattacher une task
+pub fn find_active_project(storage: &TaskStorage, name: &str) -> Result<Project, String> {
+    match storage.get_project(name) {
+        Ok(Some(project)) if project.is_archived() => Err(format!(
+            "Le projet '{}' est archivé et n'accepte plus de tâches",
+            project.name
+        )),
+        Ok(Some(project)) => Ok(project),
+        Ok(None) => Err(format!(
+            "Projet introuvable: {}. Créez-le avec 'project add'",
+            name.trim()
+        )),
+        Err(e) => Err(format!("Impossible de récupérer le projet: {}", e)),
+    }
+}
@@ -1,65 +1,94 @@
+use crate::commands::common::{check_can_complete, db_path, parse_task_ref, resolve_task_id};
+use crate::display::{OutputFormat, format_due, print_json, short_id_label};
 use crate::storage::TaskStorage;
 use crate::task::Status;
 use clap::ArgMatches;
 use colored::Colorize;
-use std::path::PathBuf;
-use uuid::Uuid;
-
-/// Obtient le chemin de la base de données
-fn get_db_path() -> PathBuf {
-    if let Some(proj_dirs) = directories::ProjectDirs::from("", "", "task-cli") {
-        let data_dir = proj_dirs.data_dir();
-        data_dir.join("tasks.db")
-    } else {
-        PathBuf::from("tasks.db")
-    }
-}
 
 pub fn handle_complete(matches: &ArgMatches) {
     let id_str = matches.get_one::<String>("id").unwrap();
+    let format = OutputFormat::from_matches(matches);
 
-    // Parser l'UUID
-    let task_id = match Uuid::parse_str(id_str) {
-        Ok(id) => id,
-        Err(_) => {
-            eprintln!("{} UUID invalide: '{}'", "Erreur:".red(), id_str);
+    // Parser l'ID (court, UUID ou préfixe)
+    let task_ref = match parse_task_ref(id_str) {
+        Ok(task_ref) => task_ref,
+        Err(e) => {
+            eprintln!("{} {}", "Erreur:".red(), e);
             return;
         }
     };
 
     // Ouvrir la base de données
-    let db_path = get_db_path();
+    let db_path = db_path(matches);
     match TaskStorage::new(db_path) {
         Ok(mut storage) => {
+            // Résoudre l'ID vers une tâche unique
+            let task_id = match resolve_task_id(&storage, &task_ref, id_str) {
+                Ok(id) => id,
+                Err(e) => {
+                    eprintln!("{} {}", "Erreur:".red(), e);
+                    return;
+                }
+            };
+
             // Récupérer la tâche existante
             match storage.get_task(&task_id) {
                 Ok(Some(mut task)) => {
                     // Vérifier si déjà complétée
                     if let Some(Status::Completed) = task.status {
+                        if format == OutputFormat::Json {
+                            print_json(&task);
+                            return;
+                        }
+
                         println!("{}", "⚠️  Cette tâche est déjà complétée!".yellow());
                         println!("  {}: {}", "Description".cyan(), task.description);
-                        println!("  {}: {}", "ID".cyan(), task.id);
+                        println!("  {}: {}", "ID".cyan(), short_id_label(&task));
+                        println!("  {}: {}", "UUID".cyan(), task.id);
+                        return;
+                    }
+
+                    // Refuser tant que des sous-tâches ou des dépendances restent ouvertes,
+                    // sauf avec --force
+                    if let Err(e) = check_can_complete(&storage, &task, matches.get_flag("force")) {
+                        eprintln!("{} {}", "Erreur:".red(), e);
                         return;
                     }
 
                     // Marquer comme complétée
                     task.set_status(Some(Status::Completed));
 
-                    // Sauvegarder les modifications
-                    match storage.update_task(&task) {
-                        Ok(_) => {
+                    // Sauvegarder, avec l'occurrence suivante si la tâche est récurrente
+                    match storage.complete_task(&task) {
+                        Ok(next) => {
+                            if format == OutputFormat::Json {
+                                print_json(&task);
+                                return;
+                            }
+
                             println!("{}", "✓ Tâche marquée comme complétée!".green());
                             println!("  {}: {}", "Description".cyan(), task.description);
-                            println!("  {}: {}", "ID".cyan(), task.id);
+                            println!("  {}: {}", "ID".cyan(), short_id_label(&task));
+                            println!("  {}: {}", "UUID".cyan(), task.id);
                             if let Some(tags) = &task.tags {
                                 println!("  {}: {}", "Tags".cyan(), tags.join(", "));
                             }
                             println!("  {}: {:?}", "Priorité".cyan(), task.priority);
-                            println!(
-                                "  {}: {}",
-                                "Échéance".cyan(),
-                                task.due_date.format("%Y-%m-%d")
-                            );
+                            if let Some(due_date) = &task.due_date {
+                                println!("  {}: {}", "Échéance".cyan(), format_due(due_date));
+                            }
+
+                            if let Some(next) = &next {
+                                println!(
+                                    "{} {} {}",
+                                    "↻ Prochaine occurrence créée:".green(),
+                                    format!("[{}]", short_id_label(next)).cyan(),
+                                    next.description
+                                );
+                                if let Some(due_date) = &next.due_date {
+                                    println!("  {}: {}", "Échéance".cyan(), format_due(due_date));
+                                }
+                            }
                         }
                         Err(e) => {
                             eprintln!(
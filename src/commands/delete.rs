@@ -1,49 +1,59 @@
+use crate::commands::common::{db_path, parse_task_ref, resolve_task_id};
+use crate::display::{OutputFormat, print_json, short_id_label};
 use crate::storage::TaskStorage;
 use clap::ArgMatches;
 use colored::Colorize;
 use std::io::{self, Write};
-use std::path::PathBuf;
-use uuid::Uuid;
-
-/// Obtient le chemin de la base de données
-fn get_db_path() -> PathBuf {
-    if let Some(proj_dirs) = directories::ProjectDirs::from("", "", "task-cli") {
-        let data_dir = proj_dirs.data_dir();
-        data_dir.join("tasks.db")
-    } else {
-        PathBuf::from("tasks.db")
-    }
-}
 
 pub fn handle_delete(matches: &ArgMatches) {
     let id_str = matches.get_one::<String>("id").unwrap();
+    let format = OutputFormat::from_matches(matches);
 
-    // Parser l'UUID
-    let task_id = match Uuid::parse_str(id_str) {
-        Ok(id) => id,
-        Err(_) => {
-            eprintln!("{} UUID invalide: '{}'", "Erreur:".red(), id_str);
+    // Parser l'ID (court, UUID ou préfixe)
+    let task_ref = match parse_task_ref(id_str) {
+        Ok(task_ref) => task_ref,
+        Err(e) => {
+            eprintln!("{} {}", "Erreur:".red(), e);
             return;
         }
     };
 
     // Ouvrir la base de données
-    let db_path = get_db_path();
+    let db_path = db_path(matches);
     match TaskStorage::new(db_path) {
         Ok(mut storage) => {
+            // Résoudre l'ID vers une tâche unique
+            let task_id = match resolve_task_id(&storage, &task_ref, id_str) {
+                Ok(id) => id,
+                Err(e) => {
+                    eprintln!("{} {}", "Erreur:".red(), e);
+                    return;
+                }
+            };
+
             // Récupérer la tâche avant suppression pour affichage
             match storage.get_task(&task_id) {
                 Ok(Some(task)) => {
                     // Demander confirmation si pas de flag --force
                     let force = matches.get_flag("force");
 
+                    // La confirmation interactive n'est pas compatible avec la sortie JSON
+                    if !force && format == OutputFormat::Json {
+                        eprintln!(
+                            "{} Confirmation impossible en sortie JSON, utilisez --force",
+                            "Erreur:".red()
+                        );
+                        return;
+                    }
+
                     if !force {
                         println!(
                             "{}",
                             "⚠️  Êtes-vous sûr de vouloir supprimer cette tâche?".yellow()
                         );
                         println!("  {}: {}", "Description".cyan(), task.description);
-                        println!("  {}: {}", "ID".cyan(), task.id);
+                        println!("  {}: {}", "ID".cyan(), short_id_label(&task));
+                        println!("  {}: {}", "UUID".cyan(), task.id);
                         print!("\n{} ", "Taper 'yes' pour confirmer:".yellow());
                         io::stdout().flush().ok();
 
@@ -57,9 +67,22 @@ pub fn handle_delete(matches: &ArgMatches) {
                     // Supprimer la tâche
                     match storage.delete_task(&task_id) {
                         Ok(_) => {
-                            println!("{}", "✓ Tâche supprimée avec succès!".green());
+                            if format == OutputFormat::Json {
+                                // Relire la tâche pour exposer `deleted_at`
+                                let trashed = storage.get_task(&task_id).ok().flatten();
+                                print_json(trashed.as_ref().unwrap_or(&task));
+                                return;
+                            }
+
+                            println!("{}", "✓ Tâche placée dans la corbeille!".green());
                             println!("  {}: {}", "Description".cyan(), task.description);
-                            println!("  {}: {}", "ID".cyan(), task.id);
+                            println!("  {}: {}", "ID".cyan(), short_id_label(&task));
+                            println!("  {}: {}", "UUID".cyan(), task.id);
+                            println!(
+                                "  {}",
+                                format!("Restaurer avec: task restore {}", short_id_label(&task))
+                                    .dimmed()
+                            );
                         }
                         Err(e) => {
                             eprintln!(
@@ -1,20 +1,14 @@
+use crate::commands::common::{
+    check_can_complete, db_path, find_active_project, find_task, parse_task_ref, resolve_task_id,
+};
+use crate::dates;
+use crate::display::{OutputFormat, format_due, format_estimate, print_json, short_id_label};
+use crate::estimate::Estimate;
+use crate::recurrence::Recurrence;
 use crate::storage::TaskStorage;
 use crate::task::{Priority, Status};
-use chrono::DateTime;
 use clap::ArgMatches;
 use colored::Colorize;
-use std::path::PathBuf;
-use uuid::Uuid;
-
-/// Obtient le chemin de la base de données
-fn get_db_path() -> PathBuf {
-    if let Some(proj_dirs) = directories::ProjectDirs::from("", "", "task-cli") {
-        let data_dir = proj_dirs.data_dir();
-        data_dir.join("tasks.db")
-    } else {
-        PathBuf::from("tasks.db")
-    }
-}
 
 /// Convertit une chaîne de priorité en enum Priority
 fn parse_priority(priority_str: &str) -> Result<Priority, String> {
@@ -43,47 +37,37 @@ fn parse_status(status_str: &str) -> Result<Status, String> {
     }
 }
 
-/// Convertit une chaîne de date en DateTime<Utc>
-fn parse_due_date(date_str: &str) -> Result<chrono::DateTime<chrono::Utc>, String> {
-    // Essayer le format RFC3339
-    if let Ok(dt) = DateTime::parse_from_rfc3339(date_str) {
-        return Ok(dt.with_timezone(&chrono::Utc));
-    }
-
-    // Essayer le format YYYY-MM-DD
-    if let Ok(naive_date) = chrono::NaiveDate::parse_from_str(date_str, "%Y-%m-%d") {
-        let naive_datetime = naive_date.and_hms_opt(0, 0, 0).unwrap();
-        return Ok(chrono::DateTime::<chrono::Utc>::from_naive_utc_and_offset(
-            naive_datetime,
-            chrono::Utc,
-        ));
-    }
-
-    Err(format!(
-        "Format de date invalide '{}'. Utilisez: YYYY-MM-DD ou RFC3339",
-        date_str
-    ))
-}
-
 pub fn handle_edit(matches: &ArgMatches) {
     let id_str = matches.get_one::<String>("id").unwrap();
+    let format = OutputFormat::from_matches(matches);
 
-    // Parser l'UUID
-    let task_id = match Uuid::parse_str(id_str) {
-        Ok(id) => id,
-        Err(_) => {
-            eprintln!("{} UUID invalide: '{}'", "Erreur:".red(), id_str);
+    // Parser l'ID (court, UUID ou préfixe)
+    let task_ref = match parse_task_ref(id_str) {
+        Ok(task_ref) => task_ref,
+        Err(e) => {
+            eprintln!("{} {}", "Erreur:".red(), e);
             return;
         }
     };
 
     // Ouvrir la base de données
-    let db_path = get_db_path();
+    let db_path = db_path(matches);
     match TaskStorage::new(db_path) {
         Ok(mut storage) => {
+            // Résoudre l'ID vers une tâche unique
+            let task_id = match resolve_task_id(&storage, &task_ref, id_str) {
+                Ok(id) => id,
+                Err(e) => {
+                    eprintln!("{} {}", "Erreur:".red(), e);
+                    return;
+                }
+            };
+
             // Récupérer la tâche existante
             match storage.get_task(&task_id) {
                 Ok(Some(mut task)) => {
+                    let was_completed = task.status == Some(Status::Completed);
+
                     // Mettre à jour la description si fournie
                     if let Some(description) = matches.get_one::<String>("description") {
                         task.set_description(description);
@@ -106,6 +90,18 @@ pub fn handle_edit(matches: &ArgMatches) {
                     if let Some(status_str) = matches.get_one::<String>("status") {
                         match parse_status(status_str) {
                             Ok(status) => {
+                                // Terminer la tâche suit les mêmes règles que `complete`
+                                if status == Status::Completed
+                                    && !was_completed
+                                    && let Err(e) = check_can_complete(
+                                        &storage,
+                                        &task,
+                                        matches.get_flag("force"),
+                                    )
+                                {
+                                    eprintln!("{} {}", "Erreur:".red(), e);
+                                    return;
+                                }
                                 task.set_status(Some(status));
                             }
                             Err(e) => {
@@ -133,7 +129,7 @@ pub fn handle_edit(matches: &ArgMatches) {
 
                     // Mettre à jour la date limite si fournie
                     if let Some(due_str) = matches.get_one::<String>("due") {
-                        match parse_due_date(due_str) {
+                        match dates::parse_due(due_str) {
                             Ok(due_date) => {
                                 task.set_due_date(due_date);
                             }
@@ -144,11 +140,89 @@ pub fn handle_edit(matches: &ArgMatches) {
                         }
                     }
 
-                    // Sauvegarder les modifications
-                    match storage.update_task(&task) {
-                        Ok(_) => {
+                    // Mettre à jour la récurrence si fournie ('none' la supprime)
+                    if let Some(recur_str) = matches.get_one::<String>("recur") {
+                        if recur_str.trim().eq_ignore_ascii_case("none") {
+                            task.set_recurrence(None);
+                        } else {
+                            match Recurrence::parse(recur_str) {
+                                Ok(recurrence) => task.set_recurrence(Some(recurrence)),
+                                Err(e) => {
+                                    eprintln!("{} {}", "Erreur:".red(), e);
+                                    return;
+                                }
+                            }
+                        }
+                    }
+
+                    // Rattacher à une tâche parente si fournie ('none' détache la tâche)
+                    if let Some(parent_str) = matches.get_one::<String>("parent") {
+                        if parent_str.trim().eq_ignore_ascii_case("none") {
+                            task.set_parent(None);
+                        } else {
+                            let parent_id = match find_task(&storage, parent_str) {
+                                Ok(parent) => parent.id,
+                                Err(e) => {
+                                    eprintln!("{} {}", "Erreur:".red(), e);
+                                    return;
+                                }
+                            };
+                            if let Err(e) = storage.check_parent(&task.id, &parent_id) {
+                                eprintln!("{} {}", "Erreur:".red(), e);
+                                return;
+                            }
+                            task.set_parent(Some(parent_id));
+                        }
+                    }
+
+                    // Changer de projet si fourni ('none' retire la tâche de son projet)
+                    if let Some(project_str) = matches.get_one::<String>("project") {
+                        if project_str.trim().eq_ignore_ascii_case("none") {
+                            task.set_project(None);
+                        } else {
+                            match find_active_project(&storage, project_str) {
+                                Ok(project) => task.set_project(Some(project.name)),
+                                Err(e) => {
+                                    eprintln!("{} {}", "Erreur:".red(), e);
+                                    return;
+                                }
+                            }
+                        }
+                    }
+
+                    // Mettre à jour l'estimation si fournie ('none' la supprime)
+                    if let Some(estimate_str) = matches.get_one::<String>("estimate") {
+                        if estimate_str.trim().eq_ignore_ascii_case("none") {
+                            task.set_estimate(None);
+                        } else {
+                            match Estimate::parse(estimate_str) {
+                                Ok(estimate) => task.set_estimate(Some(estimate)),
+                                Err(e) => {
+                                    eprintln!("{} {}", "Erreur:".red(), e);
+                                    return;
+                                }
+                            }
+                        }
+                    }
+
+                    // Sauvegarder les modifications ; terminer la tâche passe par `complete_task`
+                    // (occurrence suivante, arrêt du suivi du temps)
+                    let completing = task.status == Some(Status::Completed) && !was_completed;
+                    let result = if completing {
+                        storage.complete_task(&task)
+                    } else {
+                        storage.update_task(&task).map(|_| None)
+                    };
+                    match result {
+                        Ok(next) => {
+                            if format == OutputFormat::Json {
+                                print_json(&task);
+                                return;
+                            }
+
                             println!("{}", "✓ Tâche mise à jour avec succès!".green());
-                            println!("  {}: {}", "ID".cyan(), task.id);
+                            println!("  {}: {}", "ID".cyan(), short_id_label(&task));
+                            println!("  {}: {}", "UUID".cyan(), task.id);
                             println!("  {}: {}", "Description".cyan(), task.description);
                             if let Some(tags) = &task.tags {
                                 println!("  {}: {}", "Tags".cyan(), tags.join(", "));
@@ -160,8 +234,46 @@ pub fn handle_edit(matches: &ArgMatches) {
                             println!(
                                 "  {}: {}",
                                 "Échéance".cyan(),
-                                task.due_date.format("%Y-%m-%d")
+                                task.due_date
+                                    .as_ref()
+                                    .map(format_due)
+                                    .unwrap_or_else(|| "aucune".to_string())
                             );
+                            if let Some(recurrence) = &task.recurrence {
+                                println!("  {}: {}", "Récurrence".cyan(), recurrence);
+                            }
+                            if let Some(Ok(Some(parent))) =
+                                task.parent_id.map(|id| storage.get_task(&id))
+                            {
+                                println!(
+                                    "  {}: [{}] {}",
+                                    "Parente".cyan(),
+                                    short_id_label(&parent),
+                                    parent.description
+                                );
+                            }
+                            if let Some(project) = &task.project {
+                                println!("  {}: {}", "Projet".cyan(), project);
+                            }
+                            if let Some(estimate) = &task.estimate {
+                                println!(
+                                    "  {}: {}",
+                                    "Estimation".cyan(),
+                                    format_estimate(estimate)
+                                );
+                            }
+
+                            if let Some(next) = &next {
+                                println!(
+                                    "{} {} {}",
+                                    "↻ Prochaine occurrence créée:".green(),
+                                    format!("[{}]", short_id_label(next)).cyan(),
+                                    next.description
+                                );
+                                if let Some(due_date) = &next.due_date {
+                                    println!("  {}: {}", "Échéance".cyan(), format_due(due_date));
+                                }
+                            }
                         }
                         Err(e) => {
                             eprintln!(
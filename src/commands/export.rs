@@ -0,0 +1,81 @@
+use crate::commands::common::db_path;
+use crate::display::{OutputFormat, print_json};
+use crate::exchange::{FileFormat, write_tasks};
+use crate::storage::TaskStorage;
+use clap::ArgMatches;
+use colored::Colorize;
+use std::fs::File;
+use std::io::{self, BufWriter};
+use std::path::Path;
+
+pub fn handle_export(matches: &ArgMatches) {
+    let output_format = OutputFormat::from_matches(matches);
+    let file = matches.get_one::<String>("file");
+
+    // Format explicite ou déduit de l'extension du fichier
+    let file_format = match matches.get_one::<String>("type") {
+        Some(type_str) => match FileFormat::parse(type_str) {
+            Ok(f) => f,
+            Err(e) => {
+                eprintln!("{} {}", "Erreur:".red(), e);
+                return;
+            }
+        },
+        None => file
+            .map(|f| FileFormat::from_path(Path::new(f)))
+            .unwrap_or(FileFormat::Json),
+    };
+
+    // Ouvrir la base de données
+    let db_path = db_path(matches);
+    match TaskStorage::new(db_path) {
+        Ok(storage) => {
+            let tasks = match storage.export_tasks() {
+                Ok(tasks) => tasks,
+                Err(e) => {
+                    eprintln!(
+                        "{} Impossible de récupérer les tâches: {}",
+                        "Erreur:".red(),
+                        e
+                    );
+                    return;
+                }
+            };
+
+            // Sans fichier, l'export est écrit sur la sortie standard
+            let Some(file) = file else {
+                if let Err(e) = write_tasks(io::stdout().lock(), &tasks, file_format) {
+                    eprintln!("{} {}", "Erreur:".red(), e);
+                }
+                return;
+            };
+
+            let result = File::create(file)
+                .map_err(|e| format!("Impossible de créer le fichier '{}': {}", file, e))
+                .and_then(|f| write_tasks(BufWriter::new(f), &tasks, file_format));
+            match result {
+                Ok(_) => {
+                    if output_format == OutputFormat::Json {
+                        print_json(&serde_json::json!({ "exported": tasks.len(), "file": file }));
+                        return;
+                    }
+
+                    println!(
+                        "{}",
+                        format!("✓ {} tâche(s) exportée(s) vers {}", tasks.len(), file).green()
+                    );
+                }
+                Err(e) => {
+                    eprintln!("{} {}", "Erreur:".red(), e);
+                }
+            }
+        }
+        Err(e) => {
+            eprintln!(
+                "{} Impossible d'ouvrir la base de données: {}",
+                "Erreur:".red(),
+                e
+            );
+        }
+    }
+}
@@ -0,0 +1,150 @@
+use crate::commands::common::{db_path, parse_task_ref, resolve_task_id};
+use crate::display::{OutputFormat, format_due, print_json, short_id_label};
+use crate::storage::{TaskEvent, TaskField, TaskStorage};
+use chrono::DateTime;
+use clap::ArgMatches;
+use colored::Colorize;
+
+/// Libellé d'un champ suivi
+fn field_label(field: TaskField) -> &'static str {
+    match field {
+        TaskField::Description => "Description",
+        TaskField::Priority => "Priorité",
+        TaskField::Status => "Statut",
+        TaskField::DueDate => "Échéance",
+        TaskField::Tags => "Tags",
+        TaskField::Recurrence => "Récurrence",
+        TaskField::Parent => "Parente",
+        TaskField::Dependencies => "Dépendances",
+        TaskField::Project => "Projet",
+        TaskField::Estimate => "Estimation",
+    }
+}
+
+/// Formate une valeur de l'historique (`-` si absente, date seule pour l'échéance)
+fn format_value(field: TaskField, value: Option<&String>) -> String {
+    match value {
+        None => "-".to_string(),
+        Some(v) if field == TaskField::DueDate => DateTime::parse_from_rfc3339(v)
+            .map(|dt| format_due(&dt.to_utc()))
+            .unwrap_or_else(|_| v.clone()),
+        Some(v) => v.clone(),
+    }
+}
+
+/// Affiche des modifications, une par ligne, précédées de `indent`
+pub fn print_events(events: &[TaskEvent], indent: &str) {
+    for event in events {
+        let author = event
+            .author
+            .as_ref()
+            .map(|a| format!("({})", a).dimmed().to_string())
+            .unwrap_or_default();
+        println!(
+            "{}{}  {}: {} → {} {}",
+            indent,
+            event
+                .changed_at
+                .format("%Y-%m-%d %H:%M")
+                .to_string()
+                .dimmed(),
+            field_label(event.field).cyan(),
+            format_value(event.field, event.old_value.as_ref()),
+            format_value(event.field, event.new_value.as_ref()),
+            author
+        );
+    }
+}
+
+pub fn handle_history(matches: &ArgMatches) {
+    let id_str = matches.get_one::<String>("id").unwrap();
+    let format = OutputFormat::from_matches(matches);
+
+    // Parser l'ID (court, UUID ou préfixe)
+    let task_ref = match parse_task_ref(id_str) {
+        Ok(task_ref) => task_ref,
+        Err(e) => {
+            eprintln!("{} {}", "Erreur:".red(), e);
+            return;
+        }
+    };
+
+    // Ouvrir la base de données
+    let db_path = db_path(matches);
+    match TaskStorage::new(db_path) {
+        Ok(storage) => {
+            // Résoudre l'ID vers une tâche unique
+            let task_id = match resolve_task_id(&storage, &task_ref, id_str) {
+                Ok(id) => id,
+                Err(e) => {
+                    eprintln!("{} {}", "Erreur:".red(), e);
+                    return;
+                }
+            };
+
+            let task = match storage.get_task(&task_id) {
+                Ok(Some(task)) => task,
+                Ok(None) => {
+                    eprintln!(
+                        "{} Tâche introuvable avec l'ID: {}",
+                        "Erreur:".red(),
+                        id_str
+                    );
+                    return;
+                }
+                Err(e) => {
+                    eprintln!(
+                        "{} Impossible de récupérer la tâche: {}",
+                        "Erreur:".red(),
+                        e
+                    );
+                    return;
+                }
+            };
+
+            let events = match storage.get_task_events(&task_id) {
+                Ok(events) => events,
+                Err(e) => {
+                    eprintln!(
+                        "{} Impossible de récupérer l'historique: {}",
+                        "Erreur:".red(),
+                        e
+                    );
+                    return;
+                }
+            };
+
+            if format == OutputFormat::Json {
+                print_json(&events);
+                return;
+            }
+
+            println!(
+                "{}",
+                format!(
+                    "\nHistorique de la tâche [{}] {}\n",
+                    short_id_label(&task),
+                    task.description
+                )
+                .bold()
+            );
+            println!(
+                "  {}  {}",
+                task.created_at
+                    .format("%Y-%m-%d %H:%M")
+                    .to_string()
+                    .dimmed(),
+                "Création".green()
+            );
+
+            print_events(&events, "  ");
+        }
+        Err(e) => {
+            eprintln!(
+                "{} Impossible d'ouvrir la base de données: {}",
+                "Erreur:".red(),
+                e
+            );
+        }
+    }
+}
@@ -0,0 +1,98 @@
+use crate::commands::common::db_path;
+use crate::display::{OutputFormat, print_json};
+use crate::exchange::{FileFormat, read_tasks};
+use crate::storage::{ConflictPolicy, TaskStorage};
+use clap::ArgMatches;
+use colored::Colorize;
+use std::fs::File;
+use std::io::{self, BufReader};
+use std::path::Path;
+
+/// Convertit une chaîne en politique de conflit
+fn parse_conflict_policy(policy_str: &str) -> Result<ConflictPolicy, String> {
+    match policy_str.to_lowercase().as_str() {
+        "skip" => Ok(ConflictPolicy::Skip),
+        "overwrite" => Ok(ConflictPolicy::Overwrite),
+        "newer" => Ok(ConflictPolicy::Newer),
+        _ => Err(format!(
+            "Politique de conflit invalide '{}'. Utilisez: skip, overwrite ou newer",
+            policy_str
+        )),
+    }
+}
+
+pub fn handle_import(matches: &ArgMatches) {
+    let output_format = OutputFormat::from_matches(matches);
+    let file = matches.get_one::<String>("file").unwrap();
+
+    let policy = match matches.get_one::<String>("on-conflict") {
+        Some(policy_str) => match parse_conflict_policy(policy_str) {
+            Ok(p) => p,
+            Err(e) => {
+                eprintln!("{} {}", "Erreur:".red(), e);
+                return;
+            }
+        },
+        None => ConflictPolicy::Skip,
+    };
+
+    // Format explicite ou déduit de l'extension du fichier
+    let file_format = match matches.get_one::<String>("type") {
+        Some(type_str) => match FileFormat::parse(type_str) {
+            Ok(f) => f,
+            Err(e) => {
+                eprintln!("{} {}", "Erreur:".red(), e);
+                return;
+            }
+        },
+        None => FileFormat::from_path(Path::new(file)),
+    };
+
+    // Lire le fichier (`-` pour l'entrée standard)
+    let tasks = if file == "-" {
+        read_tasks(io::stdin().lock(), file_format)
+    } else {
+        File::open(file)
+            .map_err(|e| format!("Impossible d'ouvrir le fichier '{}': {}", file, e))
+            .and_then(|f| read_tasks(BufReader::new(f), file_format))
+    };
+    let tasks = match tasks {
+        Ok(tasks) => tasks,
+        Err(e) => {
+            eprintln!("{} {}", "Erreur:".red(), e);
+            return;
+        }
+    };
+
+    // Ouvrir la base de données et importer
+    let db_path = db_path(matches);
+    match TaskStorage::new(db_path) {
+        Ok(mut storage) => match storage.import_tasks(&tasks, policy) {
+            Ok(report) => {
+                if output_format == OutputFormat::Json {
+                    print_json(&report);
+                    return;
+                }
+
+                println!("{}", "✓ Import terminé!".green());
+                println!("  {}: {}", "Ajoutées".cyan(), report.added);
+                println!("  {}: {}", "Mises à jour".cyan(), report.updated);
+                println!("  {}: {}", "Ignorées".cyan(), report.skipped);
+            }
+            Err(e) => {
+                eprintln!(
+                    "{} Import annulé, aucune tâche importée: {}",
+                    "Erreur:".red(),
+                    e
+                );
+            }
+        },
+        Err(e) => {
+            eprintln!(
+                "{} Impossible d'ouvrir la base de données: {}",
+                "Erreur:".red(),
+                e
+            );
+        }
+    }
+}
@@ -1,17 +1,26 @@
-use crate::storage::TaskStorage;
-use crate::task::Status;
+use crate::commands::common::{db_path, pagination, urgency_coefficients};
+use crate::display::{
+    OutputFormat, format_due, print_filter_error, print_json, short_id_label, status_symbol,
+};
+use crate::filter;
+use crate::storage::{SortKey, TaskQuery, TaskStorage, tree_order};
+use crate::task::{Status, Task};
+use chrono::Utc;
 use clap::ArgMatches;
 use colored::Colorize;
-use std::path::PathBuf;
+use serde::Serialize;
+use std::collections::HashMap;
+use uuid::Uuid;
 
-/// Obtient le chemin de la base de données
-fn get_db_path() -> PathBuf {
-    if let Some(proj_dirs) = directories::ProjectDirs::from("", "", "task-cli") {
-        let data_dir = proj_dirs.data_dir();
-        data_dir.join("tasks.db")
-    } else {
-        PathBuf::from("tasks.db")
-    }
+/// Tâche de la sortie JSON, accompagnée des IDs courts de ses dépendances ouvertes et,
+/// avec `--sort urgency`, de son score d'urgence
+#[derive(Serialize)]
+struct ListedTask {
+    #[serde(flatten)]
+    task: Task,
+    blocked_by: Vec<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    urgency: Option<f64>,
 }
 
 /// Convertit une chaîne de statut en enum Status
@@ -29,93 +38,142 @@ fn parse_status(status_str: &str) -> Result<Status, String> {
 }
 
 pub fn handle_list(matches: &ArgMatches) {
-    let db_path = get_db_path();
+    let format = OutputFormat::from_matches(matches);
+
+    // Analyser l'expression de filtre avant d'ouvrir la base
+    let filter = match matches.get_one::<String>("filter") {
+        Some(expr) => match filter::parse(expr) {
+            Ok(filter) => Some(filter),
+            Err(e) => {
+                print_filter_error(expr, &e);
+                return;
+            }
+        },
+        None => None,
+    };
+
+    // Tri et pagination analysés avant d'ouvrir la base. Le tri par urgence est calculé
+    // après la requête et ne se combine pas avec les autres critères.
+    let sort = matches.get_one::<String>("sort");
+    let by_urgency = sort.is_some_and(|s| s.trim().eq_ignore_ascii_case("urgency"));
+    let sort_keys = match sort {
+        Some(keys) if !by_urgency => {
+            let parsed = if keys
+                .split(',')
+                .any(|key| key.trim().eq_ignore_ascii_case("urgency"))
+            {
+                Err("Le tri par urgence ne se combine pas avec d'autres critères".to_string())
+            } else {
+                SortKey::parse_list(keys)
+            };
+            match parsed {
+                Ok(keys) => keys,
+                Err(e) => {
+                    eprintln!("{} {}", "Erreur:".red(), e);
+                    return;
+                }
+            }
+        }
+        _ => Vec::new(),
+    };
+    let (limit, offset) = match pagination(matches) {
+        Ok(page) => page,
+        Err(e) => {
+            eprintln!("{} {}", "Erreur:".red(), e);
+            return;
+        }
+    };
+
+    // Coefficients lus avant d'ouvrir la base, pour signaler une configuration invalide
+    let coefficients = match urgency_coefficients() {
+        Ok(coefficients) => coefficients,
+        Err(e) if by_urgency => {
+            eprintln!("{} {}", "Erreur:".red(), e);
+            return;
+        }
+        Err(_) => Default::default(),
+    };
+
+    let db_path = db_path(matches);
 
     match TaskStorage::new(db_path) {
         Ok(storage) => {
-            // Déterminer si on filtre par statut
-            let status_filters: Vec<Status> =
-                if let Some(statuses) = matches.get_many::<String>("status") {
-                    statuses.filter_map(|s| parse_status(s).ok()).collect()
-                } else {
-                    Vec::new()
-                };
+            // Filtrer par statut si fourni
+            let mut query = TaskQuery::new();
+            if let Some(statuses) = matches.get_many::<String>("status") {
+                query.statuses = statuses.filter_map(|s| parse_status(s).ok()).collect();
+            }
+            query.filter = filter;
+            query.project = matches.get_one::<String>("project").cloned();
+            query.sort = sort_keys;
+            if !by_urgency {
+                query.limit = limit;
+                query.offset = offset;
+            }
 
             // Récupérer les tâches
-            let tasks = match status_filters.is_empty() {
-                true => match storage.get_all_tasks() {
-                    Ok(tasks) => tasks,
-                    Err(e) => {
-                        eprintln!(
-                            "{} Impossible de récupérer les tâches: {}",
-                            "Erreur:".red(),
-                            e
-                        );
-                        return;
-                    }
-                },
-                false => {
-                    // Récupérer les tâches pour chaque statut
-                    let mut all_tasks = Vec::new();
-                    for status in &status_filters {
-                        match storage.get_tasks_by_status(status) {
-                            Ok(mut tasks) => all_tasks.append(&mut tasks),
-                            Err(e) => {
-                                eprintln!(
-                                    "{} Impossible de récupérer les tâches: {}",
-                                    "Erreur:".red(),
-                                    e
-                                );
-                                return;
-                            }
-                        }
-                    }
-                    all_tasks
+            let tasks = match storage.query_tasks(&query) {
+                Ok(tasks) => tasks,
+                Err(e) => {
+                    eprintln!(
+                        "{} Impossible de récupérer les tâches: {}",
+                        "Erreur:".red(),
+                        e
+                    );
+                    return;
                 }
             };
 
-            // Afficher les tâches
-            if tasks.is_empty() {
-                println!("{}", "Aucune tâche trouvée.".yellow());
-            } else {
-                println!(
-                    "{}",
-                    format!("\n{} tâche(s) trouvée(s):\n", tasks.len()).bold()
-                );
-
-                for (i, task) in tasks.iter().enumerate() {
-                    let status_str = match &task.status {
-                        Some(Status::Completed) => "✓".green(),
-                        Some(Status::InProgress) => "⚙".yellow(),
-                        Some(Status::Canceled) => "✗".red(),
-                        _ => "○".white(),
-                    };
-
-                    println!(
-                        "{}  {} {} {} ({})",
-                        status_str,
-                        format!("[{}]", i + 1).cyan(),
-                        task.description,
-                        format!("(ID: {})", task.id).dimmed(),
-                        match &task.priority {
-                            crate::task::Priority::High => "HIGH".red(),
-                            crate::task::Priority::Medium => "MEDIUM".yellow(),
-                            crate::task::Priority::Low => "LOW".green(),
-                        }
-                    );
-
-                    if let Some(tags) = &task.tags {
-                        println!("     {}: {}", "Tags".cyan(), tags.join(", "));
-                    }
+            if !by_urgency && format != OutputFormat::Json {
+                print_tasks(&storage, &tasks, sort.is_some(), None);
+                return;
+            }
 
-                    println!(
-                        "     {}: {}",
-                        "Échéance".cyan(),
-                        task.due_date.format("%Y-%m-%d")
+            // Dépendances ouvertes : exposées en JSON et pénalisant le score d'urgence
+            let blockers = match storage.open_blockers() {
+                Ok(blockers) => blockers,
+                Err(e) => {
+                    eprintln!(
+                        "{} Impossible de récupérer les dépendances: {}",
+                        "Erreur:".red(),
+                        e
                     );
-                    println!();
+                    return;
                 }
+            };
+            let listed = |task: Task, urgency: Option<f64>| ListedTask {
+                blocked_by: blockers.get(&task.id).cloned().unwrap_or_default(),
+                task,
+                urgency,
+            };
+
+            if !by_urgency {
+                let tasks: Vec<ListedTask> =
+                    tasks.into_iter().map(|task| listed(task, None)).collect();
+                print_json(&tasks);
+                return;
+            }
+
+            // Classement par urgence : les dépendances ouvertes pénalisent le score
+            let ranked: Vec<(Task, f64)> = coefficients
+                .rank(tasks, |t| blockers.contains_key(&t.id), Utc::now())
+                .into_iter()
+                .skip(offset.map_or(0, |n| n as usize))
+                .take(limit.map_or(usize::MAX, |n| n as usize))
+                .collect();
+
+            if format == OutputFormat::Json {
+                let ranked: Vec<ListedTask> = ranked
+                    .into_iter()
+                    .map(|(task, score)| listed(task, Some((score * 100.0).round() / 100.0)))
+                    .collect();
+                print_json(&ranked);
+                return;
             }
+
+            let scores: HashMap<Uuid, f64> = ranked.iter().map(|(t, s)| (t.id, *s)).collect();
+            let tasks: Vec<Task> = ranked.into_iter().map(|(task, _)| task).collect();
+            print_tasks(&storage, &tasks, true, Some(&scores));
         }
         Err(e) => {
             eprintln!(
@@ -126,3 +184,115 @@ pub fn handle_list(matches: &ArgMatches) {
         }
     }
 }
+
+/// Affiche des tasks en arbre, avec l'avancement des sous-tâches et les dépendances ouvertes.
+/// Avec `flat`, les tasks sont affichées à plat dans l'ordre donné ; les scores d'urgence
+/// éventuels sont affichés sous chaque task.
+pub fn print_tasks(
+    storage: &TaskStorage,
+    tasks: &[Task],
+    flat: bool,
+    urgency: Option<&HashMap<Uuid, f64>>,
+) {
+    if tasks.is_empty() {
+        println!("{}", "Aucune tâche trouvée.".yellow());
+        return;
+    }
+
+    // Avancement des sous-tâches et dépendances ouvertes, affichés sous chaque tâche
+    let (progress, blockers) = match storage
+        .subtask_progress()
+        .and_then(|progress| Ok((progress, storage.open_blockers()?)))
+    {
+        Ok(relations) => relations,
+        Err(e) => {
+            eprintln!(
+                "{} Impossible de récupérer les sous-tâches et dépendances: {}",
+                "Erreur:".red(),
+                e
+            );
+            return;
+        }
+    };
+
+    println!(
+        "{}",
+        format!("\n{} tâche(s) trouvée(s):\n", tasks.len()).bold()
+    );
+
+    // Les sous-tâches sont indentées sous leur parente, sauf dans un ordre demandé
+    let ordered: Vec<(usize, &Task)> = if flat {
+        tasks.iter().map(|task| (0, task)).collect()
+    } else {
+        tree_order(tasks)
+    };
+    for (depth, task) in ordered {
+        let indent = "    ".repeat(depth);
+        let status_str = status_symbol(task.status.as_ref());
+        let blocked = blockers.get(&task.id);
+
+        println!(
+            "{}{}  {} {} {} ({}){}",
+            indent,
+            status_str,
+            format!("[{}]", short_id_label(task)).cyan(),
+            task.description,
+            format!("(ID: {})", task.id).dimmed(),
+            match &task.priority {
+                crate::task::Priority::High => "HIGH".red(),
+                crate::task::Priority::Medium => "MEDIUM".yellow(),
+                crate::task::Priority::Low => "LOW".green(),
+            },
+            if blocked.is_some() {
+                format!(" {}", "⛔ BLOQUÉE".red())
+            } else {
+                String::new()
+            }
+        );
+
+        if let Some(score) = urgency.and_then(|scores| scores.get(&task.id)) {
+            println!("{}     {}: {:.1}", indent, "Urgence".cyan(), score);
+        }
+
+        if let Some(tags) = &task.tags {
+            println!("{}     {}: {}", indent, "Tags".cyan(), tags.join(", "));
+        }
+
+        if let Some(due_date) = &task.due_date {
+            println!(
+                "{}     {}: {}",
+                indent,
+                "Échéance".cyan(),
+                format_due(due_date)
+            );
+        }
+
+        if let Some(recurrence) = &task.recurrence {
+            println!("{}     {}: ↻ {}", indent, "Récurrence".cyan(), recurrence);
+        }
+
+        if let Some(project) = &task.project {
+            println!("{}     {}: {}", indent, "Projet".cyan(), project);
+        }
+
+        if let Some(progress) = progress.get(&task.id) {
+            println!(
+                "{}     {}: {} terminée(s)",
+                indent,
+                "Sous-tâches".cyan(),
+                progress
+            );
+        }
+
+        if let Some(short_ids) = blocked {
+            let ids: Vec<String> = short_ids.iter().map(|id| format!("[{}]", id)).collect();
+            println!(
+                "{}     {}: {}",
+                indent,
+                "Bloquée par".cyan(),
+                ids.join(", ")
+            );
+        }
+        println!();
+    }
+}
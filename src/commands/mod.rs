@@ -1,6 +1,24 @@
 pub mod add;
+pub mod common;
 pub mod complete;
 pub mod delete;
+pub mod depend;
 pub mod edit;
+pub mod export;
+pub mod history;
+pub mod import;
 pub mod list;
+pub mod note;
+pub mod project;
+pub mod purge;
+pub mod ready;
+pub mod redo;
+pub mod report;
+pub mod restore;
 pub mod search;
+pub mod show;
+pub mod start;
+pub mod stop;
+pub mod trash;
+pub mod undepend;
+pub mod undo;
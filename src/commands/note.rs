@@ -0,0 +1,182 @@
+use crate::commands::common::{db_path, find_task};
+use crate::display::{OutputFormat, print_json, short_id_label};
+use crate::storage::TaskStorage;
+use clap::ArgMatches;
+use colored::Colorize;
+use std::process::Command;
+
+/// Ajoute une note à une tâche, ou modifie une note existante avec `--edit <N>`.
+/// Sans texte, la note est saisie dans `$VISUAL` / `$EDITOR`.
+pub fn handle_note(matches: &ArgMatches) {
+    let id_str = matches.get_one::<String>("id").unwrap();
+    let text = matches.get_one::<String>("text");
+    let format = OutputFormat::from_matches(matches);
+
+    // Numéro de la note à modifier (1 pour la plus ancienne)
+    let edit_index = match matches
+        .get_one::<String>("edit")
+        .map(|n| n.parse::<usize>())
+    {
+        Some(Ok(n)) if n > 0 => Some(n),
+        Some(_) => {
+            eprintln!(
+                "{} Numéro de note invalide '{}'. Utilisez un entier positif",
+                "Erreur:".red(),
+                matches.get_one::<String>("edit").unwrap()
+            );
+            return;
+        }
+        None => None,
+    };
+
+    // Ouvrir la base de données
+    let db_path = db_path(matches);
+    match TaskStorage::new(db_path) {
+        Ok(storage) => {
+            let task = match find_task(&storage, id_str) {
+                Ok(task) => task,
+                Err(e) => {
+                    eprintln!("{} {}", "Erreur:".red(), e);
+                    return;
+                }
+            };
+
+            // Note existante à modifier
+            let existing = match edit_index {
+                Some(n) => match storage.get_notes(&task.id) {
+                    Ok(notes) => match notes.into_iter().nth(n - 1) {
+                        Some(note) => Some(note),
+                        None => {
+                            eprintln!(
+                                "{} La tâche [{}] n'a pas de note n°{}",
+                                "Erreur:".red(),
+                                short_id_label(&task),
+                                n
+                            );
+                            return;
+                        }
+                    },
+                    Err(e) => {
+                        eprintln!(
+                            "{} Impossible de récupérer les notes: {}",
+                            "Erreur:".red(),
+                            e
+                        );
+                        return;
+                    }
+                },
+                None => None,
+            };
+
+            // Texte fourni en argument, sinon saisi dans l'éditeur
+            let text = match text {
+                Some(text) => text.trim().to_string(),
+                None => {
+                    let initial = existing.as_ref().map(|n| n.text.as_str()).unwrap_or("");
+                    match edit_in_editor(initial) {
+                        Ok(text) => text,
+                        Err(e) => {
+                            eprintln!("{} {}", "Erreur:".red(), e);
+                            return;
+                        }
+                    }
+                }
+            };
+            if text.is_empty() {
+                eprintln!(
+                    "{} Note vide, aucune modification enregistrée",
+                    "Erreur:".red()
+                );
+                return;
+            }
+
+            let result = match &existing {
+                Some(note) => storage.update_note(note.id, &text),
+                None => storage.add_note(&task.id, &text).map(Some),
+            };
+            let note = match result {
+                Ok(Some(note)) => note,
+                Ok(None) => {
+                    eprintln!("{} Note introuvable", "Erreur:".red());
+                    return;
+                }
+                Err(e) => {
+                    eprintln!(
+                        "{} Impossible d'enregistrer la note: {}",
+                        "Erreur:".red(),
+                        e
+                    );
+                    return;
+                }
+            };
+
+            if format == OutputFormat::Json {
+                print_json(&note);
+                return;
+            }
+
+            let message = if existing.is_some() {
+                "✓ Note modifiée!"
+            } else {
+                "✓ Note ajoutée!"
+            };
+            println!("{}", message.green());
+            println!(
+                "  {}: [{}] {}",
+                "Tâche".cyan(),
+                short_id_label(&task),
+                task.description
+            );
+            for line in note.text.lines() {
+                println!("  {}", line);
+            }
+        }
+        Err(e) => {
+            eprintln!(
+                "{} Impossible d'ouvrir la base de données: {}",
+                "Erreur:".red(),
+                e
+            );
+        }
+    }
+}
+
+/// Ouvre `$VISUAL` ou `$EDITOR` (`vi` par défaut) sur un fichier temporaire contenant
+/// `initial`, et retourne le texte enregistré sans les espaces de début et de fin
+fn edit_in_editor(initial: &str) -> Result<String, String> {
+    let editor = ["VISUAL", "EDITOR"]
+        .into_iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|value| !value.trim().is_empty())
+        .unwrap_or_else(|| "vi".to_string());
+
+    let path = std::env::temp_dir().join(format!("task-cli-note-{}.txt", uuid::Uuid::new_v4()));
+    let content = if initial.is_empty() {
+        String::new()
+    } else {
+        format!("{}\n", initial)
+    };
+    std::fs::write(&path, content)
+        .map_err(|e| format!("Impossible de créer le fichier temporaire: {}", e))?;
+
+    // L'éditeur peut contenir des arguments (ex: `code --wait`)
+    let mut parts = editor.split_whitespace();
+    let program = parts.next().unwrap_or("vi");
+    let status = Command::new(program).args(parts).arg(&path).status();
+
+    let text = std::fs::read_to_string(&path);
+    std::fs::remove_file(&path).ok();
+
+    match status {
+        Ok(status) if status.success() => {}
+        Ok(status) => return Err(format!("L'éditeur '{}' a échoué ({})", editor, status)),
+        Err(e) => {
+            return Err(format!(
+                "Impossible de lancer l'éditeur '{}': {}",
+                editor, e
+            ));
+        }
+    }
+    text.map(|t| t.trim().to_string())
+        .map_err(|e| format!("Impossible de lire le fichier temporaire: {}", e))
+}
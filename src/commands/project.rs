@@ -0,0 +1,107 @@
+use crate::commands::common::db_path;
+use crate::display::{OutputFormat, print_json};
+use crate::storage::{ProjectSummary, TaskStorage};
+use clap::ArgMatches;
+use colored::Colorize;
+
+/// Gère les projets : création, liste avec avancement, archivage et renommage
+pub fn handle_project(matches: &ArgMatches) {
+    let Some((action, sub_m)) = matches.subcommand() else {
+        return;
+    };
+    let format = OutputFormat::from_matches(sub_m);
+
+    // Ouvrir la base de données
+    let db_path = db_path(sub_m);
+    let storage = match TaskStorage::new(db_path) {
+        Ok(storage) => storage,
+        Err(e) => {
+            eprintln!(
+                "{} Impossible d'ouvrir la base de données: {}",
+                "Erreur:".red(),
+                e
+            );
+            return;
+        }
+    };
+
+    match action {
+        "add" => {
+            let name = sub_m.get_one::<String>("name").unwrap();
+            match storage.add_project(name) {
+                Ok(project) if format == OutputFormat::Json => print_json(&project),
+                Ok(project) => {
+                    println!("{}", "✓ Projet créé!".green());
+                    println!("  {}: {}", "Nom".cyan(), project.name);
+                }
+                Err(e) => eprintln!("{} {}", "Erreur:".red(), e),
+            }
+        }
+        "list" => {
+            let include_archived = sub_m.get_flag("all");
+            match storage.list_projects(include_archived) {
+                Ok(projects) if format == OutputFormat::Json => print_json(&projects),
+                Ok(projects) => print_projects(&projects),
+                Err(e) => eprintln!(
+                    "{} Impossible de récupérer les projets: {}",
+                    "Erreur:".red(),
+                    e
+                ),
+            }
+        }
+        "archive" => {
+            let name = sub_m.get_one::<String>("name").unwrap();
+            match storage.archive_project(name) {
+                Ok(project) if format == OutputFormat::Json => print_json(&project),
+                Ok(project) => println!(
+                    "{}",
+                    format!("✓ Projet '{}' archivé.", project.name).green()
+                ),
+                Err(e) => eprintln!("{} {}", "Erreur:".red(), e),
+            }
+        }
+        "rename" => {
+            let name = sub_m.get_one::<String>("name").unwrap();
+            let new_name = sub_m.get_one::<String>("new_name").unwrap();
+            match storage.rename_project(name, new_name) {
+                Ok(project) if format == OutputFormat::Json => print_json(&project),
+                Ok(project) => println!(
+                    "{}",
+                    format!("✓ Projet '{}' renommé en '{}'.", name.trim(), project.name).green()
+                ),
+                Err(e) => eprintln!("{} {}", "Erreur:".red(), e),
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Affiche les projets avec le nombre de tâches terminées
+fn print_projects(projects: &[ProjectSummary]) {
+    if projects.is_empty() {
+        println!("{}", "Aucun projet trouvé.".yellow());
+        return;
+    }
+
+    println!("{}", format!("\n{} projet(s):\n", projects.len()).bold());
+
+    for summary in projects {
+        let progress = summary.progress;
+        let percent = (progress.done * 100)
+            .checked_div(progress.total)
+            .unwrap_or(0);
+        println!(
+            "  {} {} — {} tâche(s) terminée(s) ({}%){}",
+            "▸".cyan(),
+            summary.project.name.bold(),
+            progress,
+            percent,
+            if summary.project.is_archived() {
+                format!(" {}", "[archivé]".dimmed())
+            } else {
+                String::new()
+            }
+        );
+    }
+    println!();
+}
@@ -0,0 +1,111 @@
+use crate::commands::common::db_path;
+use crate::display::{OutputFormat, print_json};
+use crate::storage::TaskStorage;
+use chrono::{Duration, Utc};
+use clap::ArgMatches;
+use colored::Colorize;
+use serde::Serialize;
+use std::io::{self, Write};
+
+/// Résultat d'une purge, pour la sortie JSON
+#[derive(Serialize)]
+struct PurgeReport {
+    purged: usize,
+}
+
+/// Convertit une durée de la forme `30d`, `2w` ou `12h`
+fn parse_age(age_str: &str) -> Result<Duration, String> {
+    let error = || {
+        format!(
+            "Durée invalide '{}'. Utilisez un nombre suivi de h, d ou w (ex: 30d)",
+            age_str
+        )
+    };
+
+    let (value, unit) = match age_str.char_indices().last() {
+        Some((i, _)) => age_str.split_at(i),
+        None => return Err(error()),
+    };
+    let value = i64::from(value.parse::<u32>().map_err(|_| error())?);
+    match unit {
+        "h" => Ok(Duration::hours(value)),
+        "d" => Ok(Duration::days(value)),
+        "w" => Ok(Duration::weeks(value)),
+        _ => Err(error()),
+    }
+}
+
+/// Supprime définitivement les tâches de la corbeille
+pub fn handle_purge(matches: &ArgMatches) {
+    let format = OutputFormat::from_matches(matches);
+    let force = matches.get_flag("force");
+
+    let older_than = match matches.get_one::<String>("older-than") {
+        Some(age_str) => match parse_age(age_str) {
+            Ok(age) => Some(Utc::now() - age),
+            Err(e) => {
+                eprintln!("{} {}", "Erreur:".red(), e);
+                return;
+            }
+        },
+        None => None,
+    };
+
+    // La confirmation interactive n'est pas compatible avec la sortie JSON
+    if !force && format == OutputFormat::Json {
+        eprintln!(
+            "{} Confirmation impossible en sortie JSON, utilisez --force",
+            "Erreur:".red()
+        );
+        return;
+    }
+
+    // Ouvrir la base de données
+    let db_path = db_path(matches);
+    match TaskStorage::new(db_path) {
+        Ok(mut storage) => {
+            if !force {
+                println!(
+                    "{}",
+                    "⚠️  Les tâches de la corbeille seront supprimées définitivement.".yellow()
+                );
+                print!("\n{} ", "Taper 'yes' pour confirmer:".yellow());
+                io::stdout().flush().ok();
+
+                let mut input = String::new();
+                if io::stdin().read_line(&mut input).is_err() || input.trim() != "yes" {
+                    println!("{}", "Purge annulée.".yellow());
+                    return;
+                }
+            }
+
+            match storage.purge_tasks(older_than) {
+                Ok(purged) => {
+                    if format == OutputFormat::Json {
+                        print_json(&PurgeReport { purged });
+                        return;
+                    }
+
+                    println!(
+                        "{}",
+                        format!("✓ {} tâche(s) supprimée(s) définitivement", purged).green()
+                    );
+                }
+                Err(e) => {
+                    eprintln!(
+                        "{} Impossible de purger la corbeille: {}",
+                        "Erreur:".red(),
+                        e
+                    );
+                }
+            }
+        }
+        Err(e) => {
+            eprintln!(
+                "{} Impossible d'ouvrir la base de données: {}",
+                "Erreur:".red(),
+                e
+            );
+        }
+    }
+}
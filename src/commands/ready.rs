@@ -0,0 +1,62 @@
+use crate::commands::common::db_path;
+use crate::commands::list::print_tasks;
+use crate::display::{OutputFormat, print_filter_error, print_json};
+use crate::filter;
+use crate::storage::{SortField, SortKey, TaskQuery, TaskStorage};
+use clap::ArgMatches;
+use colored::Colorize;
+
+/// Liste les tâches à faire dont aucune dépendance n'est ouverte,
+/// par échéance puis par priorité décroissante
+pub fn handle_ready(matches: &ArgMatches) {
+    let format = OutputFormat::from_matches(matches);
+
+    // Analyser l'expression de filtre avant d'ouvrir la base
+    let filter = match matches.get_one::<String>("filter") {
+        Some(expr) => match filter::parse(expr) {
+            Ok(filter) => Some(filter),
+            Err(e) => {
+                print_filter_error(expr, &e);
+                return;
+            }
+        },
+        None => None,
+    };
+
+    let db_path = db_path(matches);
+    match TaskStorage::new(db_path) {
+        Ok(storage) => {
+            let mut query = TaskQuery::new()
+                .ready()
+                .sort_by(SortKey::asc(SortField::DueDate))
+                .sort_by(SortKey::desc(SortField::Priority));
+            query.filter = filter;
+
+            let tasks = match storage.query_tasks(&query) {
+                Ok(tasks) => tasks,
+                Err(e) => {
+                    eprintln!(
+                        "{} Impossible de récupérer les tâches: {}",
+                        "Erreur:".red(),
+                        e
+                    );
+                    return;
+                }
+            };
+
+            if format == OutputFormat::Json {
+                print_json(&tasks);
+                return;
+            }
+
+            print_tasks(&storage, &tasks, false, None);
+        }
+        Err(e) => {
+            eprintln!(
+                "{} Impossible d'ouvrir la base de données: {}",
+                "Erreur:".red(),
+                e
+            );
+        }
+    }
+}
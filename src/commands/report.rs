@@ -0,0 +1,395 @@
+use crate::commands::common::db_path;
+use crate::dates;
+use crate::display::{OutputFormat, format_estimate, format_worked, print_json, short_id_label};
+use crate::storage::{
+    EffortGroup, EffortLine, EffortReport, EstimateComparison, ReportGroup, SortField, SortKey,
+    TaskQuery, TaskStorage, TimeReport,
+};
+use chrono::{Days, Utc};
+use clap::ArgMatches;
+use colored::Colorize;
+
+/// Produit les rapports d'activité
+pub fn handle_report(matches: &ArgMatches) {
+    let Some((report, sub_m)) = matches.subcommand() else {
+        return;
+    };
+    match report {
+        "time" => handle_time_report(sub_m),
+        "estimate" => handle_estimate_report(sub_m),
+        "load" => handle_load_report(sub_m),
+        _ => {}
+    }
+}
+
+/// Temps passé sur une période, par tâche, tag ou projet
+fn handle_time_report(matches: &ArgMatches) {
+    let format = OutputFormat::from_matches(matches);
+
+    let group = match ReportGroup::parse(matches.get_one::<String>("by").unwrap()) {
+        Ok(group) => group,
+        Err(e) => {
+            eprintln!("{} {}", "Erreur:".red(), e);
+            return;
+        }
+    };
+
+    // Bornes au jour près : `--until` inclut toute la journée indiquée
+    let since = match matches
+        .get_one::<String>("since")
+        .map(|s| dates::parse_day(s))
+    {
+        Some(Ok(day)) => Some(dates::local_day_start(day)),
+        Some(Err(e)) => {
+            eprintln!("{} {}", "Erreur:".red(), e);
+            return;
+        }
+        None => None,
+    };
+    let until = match matches
+        .get_one::<String>("until")
+        .map(|s| dates::parse_day(s))
+    {
+        Some(Ok(day)) => Some(dates::local_day_start(
+            day.checked_add_days(Days::new(1)).unwrap_or(day),
+        )),
+        Some(Err(e)) => {
+            eprintln!("{} {}", "Erreur:".red(), e);
+            return;
+        }
+        None => None,
+    };
+    if let (Some(since), Some(until)) = (since, until)
+        && since >= until
+    {
+        eprintln!(
+            "{} La date de début doit précéder la date de fin",
+            "Erreur:".red()
+        );
+        return;
+    }
+
+    // Ouvrir la base de données
+    let db_path = db_path(matches);
+    match TaskStorage::new(db_path) {
+        Ok(storage) => match storage.time_report(since, until, group, Utc::now()) {
+            Ok(report) if format == OutputFormat::Json => print_json(&report),
+            Ok(report) => print_time_report(&report, group),
+            Err(e) => eprintln!(
+                "{} Impossible de calculer le temps passé: {}",
+                "Erreur:".red(),
+                e
+            ),
+        },
+        Err(e) => {
+            eprintln!(
+                "{} Impossible d'ouvrir la base de données: {}",
+                "Erreur:".red(),
+                e
+            );
+        }
+    }
+}
+
+fn print_time_report(report: &TimeReport, group: ReportGroup) {
+    if report.lines.is_empty() {
+        println!("{}", "Aucun temps enregistré sur la période.".yellow());
+        return;
+    }
+
+    let period = match report.since {
+        Some(since) => format!(
+            "du {} au {}",
+            dates::to_local(&since).format("%Y-%m-%d %H:%M"),
+            dates::to_local(&report.until).format("%Y-%m-%d %H:%M")
+        ),
+        None => format!(
+            "jusqu'au {}",
+            dates::to_local(&report.until).format("%Y-%m-%d %H:%M")
+        ),
+    };
+    println!("{}", format!("\nTemps passé {}:\n", period).bold());
+
+    let missing = match group {
+        ReportGroup::Task => "(tâche purgée)",
+        ReportGroup::Tag => "(sans tag)",
+        ReportGroup::Project => "(sans projet)",
+    };
+    let labels: Vec<&str> = report
+        .lines
+        .iter()
+        .map(|line| line.label.as_deref().unwrap_or(missing))
+        .collect();
+    let width = labels
+        .iter()
+        .map(|label| label.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max("Total".len());
+
+    for (label, line) in labels.iter().zip(&report.lines) {
+        println!(
+            "  {:<width$}  {:>12}",
+            label,
+            format_worked(line.seconds),
+            width = width
+        );
+    }
+    println!(
+        "  {:<width$}  {:>12}\n",
+        "Total".bold(),
+        format_worked(report.total_seconds).bold(),
+        width = width
+    );
+}
+
+/// Requête des tâches d'un rapport d'estimation, filtrée par `--tag` et `--project`
+fn estimate_query(matches: &ArgMatches) -> TaskQuery {
+    let mut query = TaskQuery::new().sort_by(SortKey::asc(SortField::ShortId));
+    if let Some(tag) = matches.get_one::<String>("tag") {
+        query = query.tag(tag);
+    }
+    if let Some(project) = matches.get_one::<String>("project") {
+        query = query.project(project);
+    }
+    query
+}
+
+/// Compare l'estimation des tâches au temps passé
+fn handle_estimate_report(matches: &ArgMatches) {
+    let format = OutputFormat::from_matches(matches);
+
+    // Ouvrir la base de données
+    let db_path = db_path(matches);
+    match TaskStorage::new(db_path) {
+        Ok(storage) => match storage.estimate_comparison(&estimate_query(matches), Utc::now()) {
+            Ok(lines) if format == OutputFormat::Json => print_json(&lines),
+            Ok(lines) => print_estimate_comparison(&lines),
+            Err(e) => eprintln!(
+                "{} Impossible de récupérer les estimations: {}",
+                "Erreur:".red(),
+                e
+            ),
+        },
+        Err(e) => {
+            eprintln!(
+                "{} Impossible d'ouvrir la base de données: {}",
+                "Erreur:".red(),
+                e
+            );
+        }
+    }
+}
+
+/// Somme l'effort estimé des tâches à faire par tag, statut ou semaine d'échéance
+fn handle_load_report(matches: &ArgMatches) {
+    let format = OutputFormat::from_matches(matches);
+
+    let group = match EffortGroup::parse(matches.get_one::<String>("by").unwrap()) {
+        Ok(group) => group,
+        Err(e) => {
+            eprintln!("{} {}", "Erreur:".red(), e);
+            return;
+        }
+    };
+
+    // Ouvrir la base de données
+    let db_path = db_path(matches);
+    match TaskStorage::new(db_path) {
+        Ok(storage) => match storage.effort_report(&estimate_query(matches), group) {
+            Ok(report) if format == OutputFormat::Json => print_json(&report),
+            Ok(report) => print_effort_report(&report, group),
+            Err(e) => eprintln!(
+                "{} Impossible de calculer la charge estimée: {}",
+                "Erreur:".red(),
+                e
+            ),
+        },
+        Err(e) => {
+            eprintln!(
+                "{} Impossible d'ouvrir la base de données: {}",
+                "Erreur:".red(),
+                e
+            );
+        }
+    }
+}
+
+/// Écart entre le temps passé et l'estimation : `+30 min (125%)`
+fn format_variance(estimated_seconds: i64, tracked_seconds: i64) -> String {
+    let variance = tracked_seconds - estimated_seconds;
+    let sign = if variance < 0 { "-" } else { "+" };
+    let percent = (tracked_seconds * 100)
+        .checked_div(estimated_seconds)
+        .unwrap_or(0);
+    format!("{}{} ({}%)", sign, format_worked(variance.abs()), percent)
+}
+
+fn print_estimate_comparison(lines: &[EstimateComparison]) {
+    if lines.is_empty() {
+        println!("{}", "Aucune tâche estimée trouvée.".yellow());
+        return;
+    }
+
+    println!(
+        "{}",
+        format!("\nEstimations de {} tâche(s):\n", lines.len()).bold()
+    );
+
+    let mut rows: Vec<[String; 4]> = lines
+        .iter()
+        .map(|line| {
+            let estimate = line
+                .task
+                .estimate
+                .as_ref()
+                .map(format_estimate)
+                .unwrap_or_default();
+            let variance = match line.task.estimate.and_then(|e| e.minutes()) {
+                Some(minutes) => format_variance(i64::from(minutes) * 60, line.tracked_seconds),
+                None => "—".to_string(),
+            };
+            [
+                format!("[{}] {}", short_id_label(&line.task), line.task.description),
+                estimate,
+                format_worked(line.tracked_seconds),
+                variance,
+            ]
+        })
+        .collect();
+
+    // Le total ne porte que sur les estimations en durée, seules comparables au temps passé
+    let timed: Vec<&EstimateComparison> = lines
+        .iter()
+        .filter(|line| line.variance_seconds().is_some())
+        .collect();
+    if !timed.is_empty() {
+        let estimated: i64 = timed
+            .iter()
+            .filter_map(|line| line.task.estimate.and_then(|e| e.minutes()))
+            .map(|minutes| i64::from(minutes) * 60)
+            .sum();
+        let tracked: i64 = timed.iter().map(|line| line.tracked_seconds).sum();
+        rows.push([
+            "Total (estimations en durée)".to_string(),
+            format_worked(estimated),
+            format_worked(tracked),
+            format_variance(estimated, tracked),
+        ]);
+    }
+
+    let header = ["Tâche", "Estimé", "Passé", "Écart"];
+    let widths: Vec<usize> = (0..header.len())
+        .map(|i| {
+            rows.iter()
+                .map(|row| row[i].chars().count())
+                .chain([header[i].chars().count()])
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    let format_row = |row: [&str; 4]| {
+        format!(
+            "  {:<w0$}  {:>w1$}  {:>w2$}  {:>w3$}",
+            row[0],
+            row[1],
+            row[2],
+            row[3],
+            w0 = widths[0],
+            w1 = widths[1],
+            w2 = widths[2],
+            w3 = widths[3]
+        )
+    };
+    println!("{}", format_row(header).cyan());
+    let total_index = lines.len();
+    for (index, row) in rows.iter().enumerate() {
+        let line = format_row([&row[0], &row[1], &row[2], &row[3]]);
+        let over = index < total_index && lines[index].variance_seconds().is_some_and(|v| v > 0);
+        if index == total_index {
+            println!("{}", line.bold());
+        } else if over {
+            println!("{}", line.red());
+        } else {
+            println!("{}", line);
+        }
+    }
+    println!();
+}
+
+/// Effort d'une ligne de charge : `6 h 30 min + 5 pt`
+fn format_effort(line: &EffortLine) -> String {
+    let mut parts = Vec::new();
+    if line.minutes > 0 {
+        parts.push(format_worked(line.minutes as i64 * 60));
+    }
+    if line.points > 0 {
+        parts.push(format!("{} pt", line.points));
+    }
+    if parts.is_empty() {
+        "—".to_string()
+    } else {
+        parts.join(" + ")
+    }
+}
+
+fn print_effort_report(report: &EffortReport, group: EffortGroup) {
+    if report.lines.is_empty() {
+        println!("{}", "Aucune tâche à faire trouvée.".yellow());
+        return;
+    }
+
+    let title = match group {
+        EffortGroup::Tag => "par tag",
+        EffortGroup::Status => "par statut",
+        EffortGroup::Week => "par semaine d'échéance",
+    };
+    println!(
+        "{}",
+        format!("\nCharge estimée des tâches à faire, {}:\n", title).bold()
+    );
+
+    let label = |line: &EffortLine| match (&line.label, group) {
+        (Some(monday), EffortGroup::Week) => format!("Semaine du {}", monday),
+        (Some(label), _) => label.clone(),
+        (None, EffortGroup::Tag) => "(sans tag)".to_string(),
+        (None, _) => "(sans échéance)".to_string(),
+    };
+    let rows: Vec<(String, &EffortLine)> = report
+        .lines
+        .iter()
+        .map(|line| (label(line), line))
+        .chain([("Total".to_string(), &report.total)])
+        .collect();
+    let width = rows
+        .iter()
+        .map(|(label, _)| label.chars().count())
+        .max()
+        .unwrap_or(0);
+    let effort_width = rows
+        .iter()
+        .map(|(_, line)| format_effort(line).chars().count())
+        .max()
+        .unwrap_or(0);
+
+    for (index, (label, line)) in rows.iter().enumerate() {
+        let mut text = format!(
+            "  {:<width$}  {:>3} tâche(s)  {:>effort_width$}",
+            label,
+            line.tasks,
+            format_effort(line),
+            width = width,
+            effort_width = effort_width
+        );
+        if line.unestimated > 0 {
+            text.push_str(&format!("  ({} non estimée(s))", line.unestimated));
+        }
+        if index == rows.len() - 1 {
+            println!("{}", text.bold());
+        } else {
+            println!("{}", text);
+        }
+    }
+    println!();
+}
@@ -0,0 +1,79 @@
+use crate::commands::common::{db_path, parse_task_ref, resolve_trashed_task_id};
+use crate::display::{OutputFormat, print_json, short_id_label};
+use crate::storage::TaskStorage;
+use clap::ArgMatches;
+use colored::Colorize;
+
+/// Sort une tâche de la corbeille
+pub fn handle_restore(matches: &ArgMatches) {
+    let id_str = matches.get_one::<String>("id").unwrap();
+    let format = OutputFormat::from_matches(matches);
+
+    // Parser l'ID (court, UUID ou préfixe)
+    let task_ref = match parse_task_ref(id_str) {
+        Ok(task_ref) => task_ref,
+        Err(e) => {
+            eprintln!("{} {}", "Erreur:".red(), e);
+            return;
+        }
+    };
+
+    // Ouvrir la base de données
+    let db_path = db_path(matches);
+    match TaskStorage::new(db_path) {
+        Ok(mut storage) => {
+            // Résoudre l'ID parmi les tâches de la corbeille
+            let task_id = match resolve_trashed_task_id(&storage, &task_ref, id_str) {
+                Ok(id) => id,
+                Err(e) => {
+                    eprintln!("{} {}", "Erreur:".red(), e);
+                    return;
+                }
+            };
+
+            if let Err(e) = storage.restore_task(&task_id) {
+                eprintln!(
+                    "{} Impossible de restaurer la tâche: {}",
+                    "Erreur:".red(),
+                    e
+                );
+                return;
+            }
+
+            match storage.get_task(&task_id) {
+                Ok(Some(task)) => {
+                    if format == OutputFormat::Json {
+                        print_json(&task);
+                        return;
+                    }
+
+                    println!("{}", "✓ Tâche restaurée avec succès!".green());
+                    println!("  {}: {}", "Description".cyan(), task.description);
+                    println!("  {}: {}", "ID".cyan(), short_id_label(&task));
+                    println!("  {}: {}", "UUID".cyan(), task.id);
+                }
+                Ok(None) => {
+                    eprintln!(
+                        "{} Tâche introuvable avec l'ID: {}",
+                        "Erreur:".red(),
+                        id_str
+                    );
+                }
+                Err(e) => {
+                    eprintln!(
+                        "{} Impossible de récupérer la tâche: {}",
+                        "Erreur:".red(),
+                        e
+                    );
+                }
+            }
+        }
+        Err(e) => {
+            eprintln!(
+                "{} Impossible d'ouvrir la base de données: {}",
+                "Erreur:".red(),
+                e
+            );
+        }
+    }
+}
@@ -1,18 +1,14 @@
-use crate::storage::TaskStorage;
-use crate::task::{Priority, Status};
+use crate::commands::common::{db_path, pagination};
+use crate::dates;
+use crate::display::{
+    OutputFormat, format_due, highlight_ranges, print_filter_error, print_json, print_search_error,
+    short_id_label, status_symbol,
+};
+use crate::filter::{self, CompareOp, Condition, Filter};
+use crate::storage::{SortKey, TaskQuery, TaskStorage, check_search};
+use crate::task::{Priority, Status, Task};
 use clap::ArgMatches;
 use colored::Colorize;
-use std::path::PathBuf;
-
-/// Obtient le chemin de la base de données
-fn get_db_path() -> PathBuf {
-    if let Some(proj_dirs) = directories::ProjectDirs::from("", "", "task-cli") {
-        let data_dir = proj_dirs.data_dir();
-        data_dir.join("tasks.db")
-    } else {
-        PathBuf::from("tasks.db")
-    }
-}
 
 /// Convertit une chaîne de priorité en enum Priority
 fn parse_priority(priority_str: &str) -> Result<Priority, String> {
@@ -43,46 +39,74 @@ fn parse_status(status_str: &str) -> Result<Status, String> {
 
 pub fn handle_search(matches: &ArgMatches) {
     let pattern = matches.get_one::<String>("pattern").unwrap();
+    let format = OutputFormat::from_matches(matches);
+
+    // Vérifier la place des opérateurs de recherche avant d'ouvrir la base
+    if let Err(e) = check_search(pattern) {
+        print_search_error(pattern, &e);
+        return;
+    }
+
+    // Analyser l'expression de filtre avant d'ouvrir la base
+    let filter = match matches.get_one::<String>("filter") {
+        Some(expr) => match filter::parse(expr) {
+            Ok(filter) => Some(filter),
+            Err(e) => {
+                print_filter_error(expr, &e);
+                return;
+            }
+        },
+        None => None,
+    };
+
+    // Filtrer par échéance si fournie (équivalent à `due:<date>`)
+    let due_filter = match matches.get_one::<String>("due") {
+        Some(due_str) => match dates::parse_day(due_str) {
+            Ok(day) => Some(Filter::Condition(Condition::Due(CompareOp::Eq, Some(day)))),
+            Err(e) => {
+                eprintln!("{} {}", "Erreur:".red(), e);
+                return;
+            }
+        },
+        None => None,
+    };
+
+    // Critères de tri, après lesquels la pertinence départage les résultats
+    let sort_keys = match matches
+        .get_one::<String>("sort")
+        .map(|s| SortKey::parse_list(s))
+    {
+        Some(Ok(keys)) => keys,
+        Some(Err(e)) => {
+            eprintln!("{} {}", "Erreur:".red(), e);
+            return;
+        }
+        None => Vec::new(),
+    };
+    let (limit, offset) = match pagination(matches) {
+        Ok(page) => page,
+        Err(e) => {
+            eprintln!("{} {}", "Erreur:".red(), e);
+            return;
+        }
+    };
 
     // Ouvrir la base de données
-    let db_path = get_db_path();
+    let db_path = db_path(matches);
     match TaskStorage::new(db_path) {
         Ok(storage) => {
-            // Récupérer toutes les tâches
-            let mut tasks = match storage.get_all_tasks() {
-                Ok(tasks) => tasks,
-                Err(e) => {
-                    eprintln!(
-                        "{} Impossible de récupérer les tâches: {}",
-                        "Erreur:".red(),
-                        e
-                    );
-                    return;
-                }
-            };
-
-            // Filtrer par pattern dans la description (case-insensitive)
-            let pattern_lower = pattern.to_lowercase();
-            tasks.retain(|task| task.description.to_lowercase().contains(&pattern_lower));
+            // Recherche plein texte dans la description, classée par pertinence
+            let mut query = TaskQuery::new().text(pattern);
 
             // Filtrer par tag si fourni
             if let Some(tag) = matches.get_one::<String>("tag") {
-                let tag_lower = tag.to_lowercase();
-                tasks.retain(|task| {
-                    if let Some(tags) = &task.tags {
-                        tags.iter().any(|t| t.to_lowercase() == tag_lower)
-                    } else {
-                        false
-                    }
-                });
+                query = query.tag(tag);
             }
 
             // Filtrer par priorité si fournie
             if let Some(priority_str) = matches.get_one::<String>("priority") {
                 match parse_priority(priority_str) {
-                    Ok(priority) => {
-                        tasks.retain(|task| task.priority == priority);
-                    }
+                    Ok(priority) => query = query.priority(priority),
                     Err(e) => {
                         eprintln!("{} {}", "Erreur:".red(), e);
                         return;
@@ -92,22 +116,42 @@ pub fn handle_search(matches: &ArgMatches) {
 
             // Filtrer par statut si fourni
             if let Some(statuses) = matches.get_many::<String>("status") {
-                let status_filters: Vec<Status> =
-                    statuses.filter_map(|s| parse_status(s).ok()).collect();
-
-                if !status_filters.is_empty() {
-                    tasks.retain(|task| {
-                        if let Some(status) = &task.status {
-                            status_filters.contains(status)
-                        } else {
-                            false
-                        }
-                    });
+                query.statuses = statuses.filter_map(|s| parse_status(s).ok()).collect();
+            }
+
+            for filter in [filter, due_filter].into_iter().flatten() {
+                query = query.filter(filter);
+            }
+
+            // Filtrer par projet si fourni
+            if let Some(project) = matches.get_one::<String>("project") {
+                query = query.project(project);
+            }
+
+            query.sort = sort_keys;
+            query.limit = limit;
+            query.offset = offset;
+
+            let hits = match storage.search_tasks(&query) {
+                Ok(hits) => hits,
+                Err(e) => {
+                    eprintln!(
+                        "{} Impossible de récupérer les tâches: {}",
+                        "Erreur:".red(),
+                        e
+                    );
+                    return;
                 }
+            };
+
+            if format == OutputFormat::Json {
+                let tasks: Vec<&Task> = hits.iter().map(|hit| &hit.task).collect();
+                print_json(&tasks);
+                return;
             }
 
             // Afficher les résultats
-            if tasks.is_empty() {
+            if hits.is_empty() {
                 println!(
                     "{}",
                     "Aucune tâche trouvée correspondant aux critères.".yellow()
@@ -115,22 +159,18 @@ pub fn handle_search(matches: &ArgMatches) {
             } else {
                 println!(
                     "{}",
-                    format!("\n{} tâche(s) trouvée(s):\n", tasks.len()).bold()
+                    format!("\n{} tâche(s) trouvée(s):\n", hits.len()).bold()
                 );
 
-                for (i, task) in tasks.iter().enumerate() {
-                    let status_str = match &task.status {
-                        Some(Status::Completed) => "✓".green(),
-                        Some(Status::InProgress) => "⚙".yellow(),
-                        Some(Status::Canceled) => "✗".red(),
-                        _ => "○".white(),
-                    };
+                for hit in &hits {
+                    let task = &hit.task;
+                    let status_str = status_symbol(task.status.as_ref());
 
                     println!(
                         "{}  {} {} {} ({})",
                         status_str,
-                        format!("[{}]", i + 1).cyan(),
-                        task.description,
+                        format!("[{}]", short_id_label(task)).cyan(),
+                        highlight_ranges(&task.description, &hit.highlights),
                         format!("(ID: {})", task.id).dimmed(),
                         match &task.priority {
                             Priority::High => "HIGH".red(),
@@ -147,11 +187,25 @@ pub fn handle_search(matches: &ArgMatches) {
                         println!("     {}: {:?}", "Statut".cyan(), status);
                     }
 
-                    println!(
-                        "     {}: {}",
-                        "Échéance".cyan(),
-                        task.due_date.format("%Y-%m-%d")
-                    );
+                    if let Some(due_date) = &task.due_date {
+                        println!("     {}: {}", "Échéance".cyan(), format_due(due_date));
+                    }
+
+                    if let Some(recurrence) = &task.recurrence {
+                        println!("     {}: ↻ {}", "Récurrence".cyan(), recurrence);
+                    }
+
+                    if let Some(project) = &task.project {
+                        println!("     {}: {}", "Projet".cyan(), project);
+                    }
+
+                    if let Some(excerpt) = &hit.note_excerpt {
+                        println!(
+                            "     {}: {}",
+                            "Notes".cyan(),
+                            highlight_ranges(&excerpt.replace('\n', " "), &hit.note_highlights)
+                        );
+                    }
                     println!();
                 }
             }
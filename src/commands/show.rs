@@ -0,0 +1,291 @@
+use crate::commands::common::{db_path, find_task, urgency_coefficients};
+use crate::commands::history::print_events;
+use crate::dates;
+use crate::display::{
+    OutputFormat, format_due, format_estimate, format_worked, print_json, short_id_label,
+    status_symbol,
+};
+use crate::storage::{Note, Progress, TaskEvent, TaskStorage, TimeEntry};
+use crate::task::{Status, Task};
+use crate::urgency::UrgencyCoefficients;
+use chrono::{DateTime, Utc};
+use clap::ArgMatches;
+use colored::Colorize;
+use rusqlite::Result as SqlResult;
+use serde::Serialize;
+
+/// Tâche et tout ce qui s'y rattache, pour l'affichage détaillé
+#[derive(Serialize)]
+struct TaskDetails {
+    #[serde(flatten)]
+    task: Task,
+    parent: Option<Task>,
+    subtasks: Vec<Task>,
+    /// Dépendances encore présentes (hors tâches purgées)
+    dependencies: Vec<Task>,
+    /// Vrai si une dépendance n'est ni complétée ni annulée
+    blocked: bool,
+    /// Score d'urgence (voir le module `urgency`)
+    urgency: f64,
+    notes: Vec<Note>,
+    /// Intervalles de suivi du temps, dans l'ordre chronologique
+    time_entries: Vec<TimeEntry>,
+    history: Vec<TaskEvent>,
+}
+
+impl TaskDetails {
+    /// Charge la tâche, sa parente, ses sous-tâches, ses dépendances, ses notes, son temps
+    /// passé et son historique, et calcule son urgence à `now`
+    fn load(
+        storage: &TaskStorage,
+        task: Task,
+        coefficients: &UrgencyCoefficients,
+        now: DateTime<Utc>,
+    ) -> SqlResult<Self> {
+        let parent = match task.parent_id {
+            Some(id) => storage.get_task(&id)?,
+            None => None,
+        };
+        let mut dependencies = Vec::new();
+        for id in &task.depends_on {
+            dependencies.extend(storage.get_task(id)?);
+        }
+        let blocked = !storage.get_blockers(&task.id)?.is_empty();
+        let urgency = coefficients.score(&task, blocked, now);
+        Ok(TaskDetails {
+            parent,
+            subtasks: storage.get_subtasks(&task.id)?,
+            blocked,
+            urgency: (urgency * 100.0).round() / 100.0,
+            dependencies,
+            notes: storage.get_notes(&task.id)?,
+            time_entries: storage.get_time_entries(&task.id)?,
+            history: storage.get_task_events(&task.id)?,
+            task,
+        })
+    }
+}
+
+/// Affiche tous les champs d'une tâche, avec sa parente, ses sous-tâches, ses dépendances,
+/// ses notes et son historique
+pub fn handle_show(matches: &ArgMatches) {
+    let id_str = matches.get_one::<String>("id").unwrap();
+    let format = OutputFormat::from_matches(matches);
+    let coefficients = match urgency_coefficients() {
+        Ok(coefficients) => coefficients,
+        Err(e) => {
+            eprintln!("{} {}", "Erreur:".red(), e);
+            return;
+        }
+    };
+
+    // Ouvrir la base de données
+    let db_path = db_path(matches);
+    match TaskStorage::new(db_path) {
+        Ok(storage) => {
+            let task = match find_task(&storage, id_str) {
+                Ok(task) => task,
+                Err(e) => {
+                    eprintln!("{} {}", "Erreur:".red(), e);
+                    return;
+                }
+            };
+
+            let now = Utc::now();
+            let details = match TaskDetails::load(&storage, task, &coefficients, now) {
+                Ok(details) => details,
+                Err(e) => {
+                    eprintln!(
+                        "{} Impossible de récupérer la tâche: {}",
+                        "Erreur:".red(),
+                        e
+                    );
+                    return;
+                }
+            };
+
+            if format == OutputFormat::Json {
+                print_json(&details);
+                return;
+            }
+
+            print_details(&details, &now);
+        }
+        Err(e) => {
+            eprintln!(
+                "{} Impossible d'ouvrir la base de données: {}",
+                "Erreur:".red(),
+                e
+            );
+        }
+    }
+}
+
+/// Formate une date dans le fuseau configuré, suivie de son ancienneté
+fn format_timestamp(date: &DateTime<Utc>, now: &DateTime<Utc>) -> String {
+    format!(
+        "{} ({})",
+        dates::to_local(date).format("%Y-%m-%d %H:%M"),
+        dates::describe_ago(date, now)
+    )
+}
+
+/// Ligne résumant une tâche liée : `✓ [3] Description`
+fn task_line(task: &Task) -> String {
+    format!(
+        "{} {} {}",
+        status_symbol(task.status.as_ref()),
+        format!("[{}]", short_id_label(task)).cyan(),
+        task.description
+    )
+}
+
+fn print_details(details: &TaskDetails, now: &DateTime<Utc>) {
+    let task = &details.task;
+
+    println!(
+        "\n{} {}{}\n",
+        format!("[{}]", short_id_label(task)).cyan().bold(),
+        task.description.bold(),
+        if details.blocked {
+            format!(" {}", "⛔ BLOQUÉE".red())
+        } else {
+            String::new()
+        }
+    );
+
+    println!("  {}: {}", "UUID".cyan(), task.id);
+    println!(
+        "  {}: {} {:?}",
+        "Statut".cyan(),
+        status_symbol(task.status.as_ref()),
+        task.status.as_ref().unwrap_or(&Status::NotStarted)
+    );
+    println!("  {}: {:?}", "Priorité".cyan(), task.priority);
+    println!("  {}: {:.1}", "Urgence".cyan(), details.urgency);
+
+    // L'échéance n'est située dans le temps que pour une tâche encore à faire
+    match &task.due_date {
+        Some(due_date) if task.is_open() => {
+            let relative = dates::describe_due(due_date, now);
+            let relative = if due_date < now {
+                relative.red()
+            } else {
+                relative.normal()
+            };
+            println!(
+                "  {}: {} ({})",
+                "Échéance".cyan(),
+                format_due(due_date),
+                relative
+            );
+        }
+        Some(due_date) => println!("  {}: {}", "Échéance".cyan(), format_due(due_date)),
+        None => println!("  {}: {}", "Échéance".cyan(), "aucune".dimmed()),
+    }
+
+    println!(
+        "  {}: {}",
+        "Créée".cyan(),
+        format_timestamp(&task.created_at, now)
+    );
+    println!(
+        "  {}: {}",
+        "Modifiée".cyan(),
+        format_timestamp(&task.updated_at, now)
+    );
+
+    if let Some(tags) = &task.tags {
+        println!("  {}: {}", "Tags".cyan(), tags.join(", "));
+    }
+    if let Some(recurrence) = &task.recurrence {
+        println!("  {}: ↻ {}", "Récurrence".cyan(), recurrence);
+    }
+    if let Some(project) = &task.project {
+        println!("  {}: {}", "Projet".cyan(), project);
+    }
+    if let Some(estimate) = &task.estimate {
+        println!("  {}: {}", "Estimation".cyan(), format_estimate(estimate));
+    }
+    if !details.time_entries.is_empty() {
+        let seconds: i64 = details
+            .time_entries
+            .iter()
+            .map(|entry| entry.duration(*now).num_seconds())
+            .sum();
+        let running = details.time_entries.iter().any(TimeEntry::is_running);
+        println!(
+            "  {}: {}{}",
+            "Temps passé".cyan(),
+            format_worked(seconds),
+            if running {
+                format!(" {}", "(en cours)".green())
+            } else {
+                String::new()
+            }
+        );
+    }
+    if let Some(parent) = &details.parent {
+        println!("  {}: {}", "Parente".cyan(), task_line(parent));
+    }
+
+    if !details.subtasks.is_empty() {
+        let progress = Progress {
+            done: details
+                .subtasks
+                .iter()
+                .filter(|t| t.status == Some(Status::Completed))
+                .count(),
+            total: details
+                .subtasks
+                .iter()
+                .filter(|t| t.status != Some(Status::Canceled))
+                .count(),
+        };
+        println!("\n  {} ({} terminée(s)):", "Sous-tâches".cyan(), progress);
+        for subtask in &details.subtasks {
+            println!("    {}", task_line(subtask));
+        }
+    }
+
+    if !details.dependencies.is_empty() {
+        println!("\n  {}:", "Dépend de".cyan());
+        for dependency in &details.dependencies {
+            println!("    {}", task_line(dependency));
+        }
+    }
+
+    if !details.notes.is_empty() {
+        println!("\n  {} ({}):", "Notes".cyan(), details.notes.len());
+        print_notes(&details.notes);
+    }
+
+    if !details.history.is_empty() {
+        println!("\n  {}:", "Historique".cyan());
+        print_events(&details.history, "    ");
+    }
+    println!();
+}
+
+/// Affiche des notes numérotées avec leur date, le texte indenté sous l'en-tête
+fn print_notes(notes: &[Note]) {
+    for (index, note) in notes.iter().enumerate() {
+        let mut header = dates::to_local(&note.created_at)
+            .format("%Y-%m-%d %H:%M")
+            .to_string();
+        if note.updated_at != note.created_at {
+            header.push_str(&format!(
+                " (modifiée le {})",
+                dates::to_local(&note.updated_at).format("%Y-%m-%d %H:%M")
+            ));
+        }
+        println!(
+            "    {} {}",
+            format!("{}.", index + 1).cyan(),
+            header.dimmed()
+        );
+        for line in note.text.lines() {
+            println!("       {}", line);
+        }
+    }
+}
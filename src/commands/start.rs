@@ -0,0 +1,96 @@
+use crate::commands::common::{db_path, find_task};
+use crate::display::{OutputFormat, format_worked, print_json, short_id_label};
+use crate::storage::TaskStorage;
+use chrono::Utc;
+use clap::ArgMatches;
+use colored::Colorize;
+
+/// Démarre le suivi du temps d'une tâche, en arrêtant celui de la tâche suivie
+pub fn handle_start(matches: &ArgMatches) {
+    let id_str = matches.get_one::<String>("id").unwrap();
+    let format = OutputFormat::from_matches(matches);
+
+    // Ouvrir la base de données
+    let db_path = db_path(matches);
+    match TaskStorage::new(db_path) {
+        Ok(mut storage) => {
+            let task = match find_task(&storage, id_str) {
+                Ok(task) => task,
+                Err(e) => {
+                    eprintln!("{} {}", "Erreur:".red(), e);
+                    return;
+                }
+            };
+
+            // Une tâche terminée doit être rouverte avant d'y passer du temps
+            if !task.is_open() {
+                eprintln!(
+                    "{} La tâche [{}] est {:?}. Changez son statut avec 'edit --status' pour la démarrer",
+                    "Erreur:".red(),
+                    short_id_label(&task),
+                    task.status.as_ref().unwrap()
+                );
+                return;
+            }
+
+            match storage.running_entry() {
+                Ok(Some(entry)) if entry.task_id == task.id => {
+                    eprintln!(
+                        "{} La tâche [{}] est déjà démarrée",
+                        "Erreur:".red(),
+                        short_id_label(&task)
+                    );
+                    return;
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    eprintln!(
+                        "{} Impossible de récupérer le suivi en cours: {}",
+                        "Erreur:".red(),
+                        e
+                    );
+                    return;
+                }
+            }
+
+            let stopped = match storage.start_task(&task) {
+                Ok(stopped) => stopped,
+                Err(e) => {
+                    eprintln!("{} Impossible de démarrer la tâche: {}", "Erreur:".red(), e);
+                    return;
+                }
+            };
+
+            let task = storage.get_task(&task.id).ok().flatten().unwrap_or(task);
+            if format == OutputFormat::Json {
+                print_json(&task);
+                return;
+            }
+
+            if let Some(entry) = stopped
+                && let Ok(Some(previous)) = storage.get_task(&entry.task_id)
+            {
+                println!(
+                    "{} [{}] {} ({})",
+                    "⏹ Suivi arrêté:".yellow(),
+                    short_id_label(&previous),
+                    previous.description,
+                    format_worked(entry.duration(Utc::now()).num_seconds())
+                );
+            }
+            println!(
+                "{} [{}] {}",
+                "▶ Suivi démarré:".green(),
+                short_id_label(&task),
+                task.description
+            );
+        }
+        Err(e) => {
+            eprintln!(
+                "{} Impossible d'ouvrir la base de données: {}",
+                "Erreur:".red(),
+                e
+            );
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use crate::commands::common::{db_path, find_task};
+use crate::display::{OutputFormat, format_worked, print_json, short_id_label};
+use crate::storage::TaskStorage;
+use chrono::Utc;
+use clap::ArgMatches;
+use colored::Colorize;
+
+/// Arrête le suivi du temps de la tâche en cours
+pub fn handle_stop(matches: &ArgMatches) {
+    let format = OutputFormat::from_matches(matches);
+
+    // Ouvrir la base de données
+    let db_path = db_path(matches);
+    match TaskStorage::new(db_path) {
+        Ok(mut storage) => {
+            let running = match storage.running_entry() {
+                Ok(Some(entry)) => entry,
+                Ok(None) => {
+                    eprintln!("{} Aucune tâche n'est démarrée", "Erreur:".red());
+                    return;
+                }
+                Err(e) => {
+                    eprintln!(
+                        "{} Impossible de récupérer le suivi en cours: {}",
+                        "Erreur:".red(),
+                        e
+                    );
+                    return;
+                }
+            };
+
+            // L'ID est facultatif ; s'il est fourni, il doit désigner la tâche suivie
+            if let Some(id_str) = matches.get_one::<String>("id") {
+                match find_task(&storage, id_str) {
+                    Ok(task) if task.id == running.task_id => {}
+                    Ok(task) => {
+                        eprintln!(
+                            "{} La tâche [{}] n'est pas démarrée",
+                            "Erreur:".red(),
+                            short_id_label(&task)
+                        );
+                        return;
+                    }
+                    Err(e) => {
+                        eprintln!("{} {}", "Erreur:".red(), e);
+                        return;
+                    }
+                }
+            }
+
+            let entry = match storage.stop_task() {
+                Ok(Some(entry)) => entry,
+                Ok(None) => {
+                    eprintln!("{} Aucune tâche n'est démarrée", "Erreur:".red());
+                    return;
+                }
+                Err(e) => {
+                    eprintln!("{} Impossible d'arrêter la tâche: {}", "Erreur:".red(), e);
+                    return;
+                }
+            };
+
+            if format == OutputFormat::Json {
+                print_json(&entry);
+                return;
+            }
+
+            let duration = format_worked(entry.duration(Utc::now()).num_seconds());
+            match storage.get_task(&entry.task_id) {
+                Ok(Some(task)) => println!(
+                    "{} [{}] {} ({})",
+                    "⏹ Suivi arrêté:".yellow(),
+                    short_id_label(&task),
+                    task.description,
+                    duration
+                ),
+                _ => println!("{} ({})", "⏹ Suivi arrêté".yellow(), duration),
+            }
+        }
+        Err(e) => {
+            eprintln!(
+                "{} Impossible d'ouvrir la base de données: {}",
+                "Erreur:".red(),
+                e
+            );
+        }
+    }
+}
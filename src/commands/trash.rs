@@ -0,0 +1,68 @@
+use crate::commands::common::db_path;
+use crate::display::{OutputFormat, print_json, short_id_label};
+use crate::storage::TaskStorage;
+use clap::ArgMatches;
+use colored::Colorize;
+
+/// Affiche les tâches de la corbeille
+pub fn handle_trash(matches: &ArgMatches) {
+    let format = OutputFormat::from_matches(matches);
+    let db_path = db_path(matches);
+
+    match TaskStorage::new(db_path) {
+        Ok(storage) => {
+            let tasks = match storage.get_trashed_tasks() {
+                Ok(tasks) => tasks,
+                Err(e) => {
+                    eprintln!(
+                        "{} Impossible de récupérer la corbeille: {}",
+                        "Erreur:".red(),
+                        e
+                    );
+                    return;
+                }
+            };
+
+            if format == OutputFormat::Json {
+                print_json(&tasks);
+                return;
+            }
+
+            if tasks.is_empty() {
+                println!("{}", "La corbeille est vide.".yellow());
+                return;
+            }
+
+            println!(
+                "{}",
+                format!("\n{} tâche(s) dans la corbeille:\n", tasks.len()).bold()
+            );
+
+            for task in &tasks {
+                println!(
+                    "{}  {} {} {}",
+                    "🗑".dimmed(),
+                    format!("[{}]", short_id_label(task)).cyan(),
+                    task.description,
+                    format!("(ID: {})", task.id).dimmed(),
+                );
+
+                if let Some(deleted_at) = task.deleted_at {
+                    println!(
+                        "     {}: {}",
+                        "Supprimée le".cyan(),
+                        deleted_at.format("%Y-%m-%d %H:%M")
+                    );
+                }
+                println!();
+            }
+        }
+        Err(e) => {
+            eprintln!(
+                "{} Impossible d'ouvrir la base de données: {}",
+                "Erreur:".red(),
+                e
+            );
+        }
+    }
+}
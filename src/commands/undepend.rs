@@ -0,0 +1,70 @@
+use crate::commands::common::{db_path, find_task};
+use crate::display::{OutputFormat, print_json, short_id_label};
+use crate::storage::TaskStorage;
+use clap::ArgMatches;
+use colored::Colorize;
+
+/// Retire une dépendance entre deux tâches
+pub fn handle_undepend(matches: &ArgMatches) {
+    let id_str = matches.get_one::<String>("id").unwrap();
+    let on_str = matches.get_one::<String>("on").unwrap();
+    let format = OutputFormat::from_matches(matches);
+
+    // Ouvrir la base de données
+    let db_path = db_path(matches);
+    match TaskStorage::new(db_path) {
+        Ok(mut storage) => {
+            // Résoudre les deux tâches
+            let (mut task, blocker) = match find_task(&storage, id_str)
+                .and_then(|t| Ok((t, find_task(&storage, on_str)?)))
+            {
+                Ok(tasks) => tasks,
+                Err(e) => {
+                    eprintln!("{} {}", "Erreur:".red(), e);
+                    return;
+                }
+            };
+
+            if !task.remove_dependency(&blocker.id) {
+                eprintln!(
+                    "{} La tâche [{}] ne dépend pas de la tâche [{}]",
+                    "Erreur:".red(),
+                    short_id_label(&task),
+                    short_id_label(&blocker)
+                );
+                return;
+            }
+
+            if let Err(e) = storage.update_task(&task) {
+                eprintln!(
+                    "{} Impossible de mettre à jour la tâche: {}",
+                    "Erreur:".red(),
+                    e
+                );
+                return;
+            }
+
+            if format == OutputFormat::Json {
+                print_json(&task);
+                return;
+            }
+
+            println!("{}", "✓ Dépendance retirée!".green());
+            println!(
+                "  [{}] {} {} [{}] {}",
+                short_id_label(&task),
+                task.description,
+                "ne dépend plus de".cyan(),
+                short_id_label(&blocker),
+                blocker.description
+            );
+        }
+        Err(e) => {
+            eprintln!(
+                "{} Impossible d'ouvrir la base de données: {}",
+                "Erreur:".red(),
+                e
+            );
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use crate::commands::common::db_path;
+use crate::display::{OutputFormat, describe_operation, print_json};
+use crate::storage::TaskStorage;
+use clap::ArgMatches;
+use colored::Colorize;
+
+/// Annule les derniers lots d'opérations
+pub fn handle_undo(matches: &ArgMatches) {
+    let format = OutputFormat::from_matches(matches);
+    let count = match matches.get_one::<String>("count") {
+        Some(count_str) => match count_str.parse::<usize>() {
+            Ok(n) if n > 0 => n,
+            _ => {
+                eprintln!(
+                    "{} Nombre invalide '{}'. Utilisez un entier positif",
+                    "Erreur:".red(),
+                    count_str
+                );
+                return;
+            }
+        },
+        None => 1,
+    };
+
+    // Ouvrir la base de données
+    let db_path = db_path(matches);
+    match TaskStorage::new(db_path) {
+        Ok(mut storage) => {
+            let mut operations = Vec::new();
+            for _ in 0..count {
+                match storage.undo() {
+                    Ok(batch) if batch.is_empty() => break,
+                    Ok(mut batch) => operations.append(&mut batch),
+                    Err(e) => {
+                        eprintln!("{} Impossible d'annuler: {}", "Erreur:".red(), e);
+                        return;
+                    }
+                }
+            }
+
+            if format == OutputFormat::Json {
+                print_json(&operations);
+                return;
+            }
+
+            if operations.is_empty() {
+                println!("{}", "Rien à annuler.".yellow());
+                return;
+            }
+
+            for operation in &operations {
+                println!("{} {}", "✓ Annulé:".green(), describe_operation(operation));
+            }
+        }
+        Err(e) => {
+            eprintln!(
+                "{} Impossible d'ouvrir la base de données: {}",
+                "Erreur:".red(),
+                e
+            );
+        }
+    }
+}
@@ -0,0 +1,426 @@
+//! Analyse et fuseau horaire des dates saisies par l'utilisateur (`--due`, filtre `due`).
+//!
+//! Les dates sont résolues dans le fuseau horaire configuré (option `--tz`, variable
+//! `TASK_CLI_TZ`) ou, à défaut, celui du système, puis converties en UTC pour le
+//! stockage. Formats acceptés (sans tenir compte de la casse) :
+//!
+//! | Saisie | Résultat |
+//! |--------|----------|
+//! | `today`, `tomorrow`, `yesterday` | Le jour correspondant |
+//! | `monday` ... `sunday` (ou `mon` ... `sun`) | La prochaine occurrence de ce jour, aujourd'hui exclu |
+//! | `next monday` ... | Idem |
+//! | `+3d`, `+2w`, `+1m` | Dans 3 jours, 2 semaines, 1 mois |
+//! | `next week`, `next month` | Lundi prochain, le 1er du mois prochain |
+//! | `eow`, `eom`, `eoy` | Dimanche, dernier jour du mois, 31 décembre |
+//! | `2026-11-02` | Le jour indiqué |
+//! | RFC3339 (`2026-11-02T14:00:00Z`) | L'instant indiqué |
+//!
+//! Une heure `HH:MM` peut suivre une date ou un jour relatif (`2026-11-02 14:00`,
+//! `friday 09:30`) ; sans heure, l'échéance est fixée au début du jour.
+
+use chrono::{
+    DateTime, Datelike, Days, Duration, Months, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc,
+    Weekday,
+};
+use chrono_tz::Tz;
+use std::sync::OnceLock;
+
+const FORMATS: &str = "YYYY-MM-DD [HH:MM], today, tomorrow, friday, +3d, next week, eom ou RFC3339";
+
+/// Fuseau horaire configuré pour le processus
+static TIMEZONE: OnceLock<Tz> = OnceLock::new();
+
+/// Date résolue, avant conversion dans le fuseau horaire
+enum Resolved {
+    /// Jour sans heure
+    Day(NaiveDate),
+    /// Date et heure locales
+    Local(NaiveDateTime),
+    /// Instant avec décalage explicite (RFC3339)
+    Instant(DateTime<Utc>),
+}
+
+/// Analyse un nom de fuseau horaire IANA (ex: `Europe/Paris`, `UTC`)
+pub fn parse_timezone(name: &str) -> Result<Tz, String> {
+    name.trim().parse().map_err(|_| {
+        format!(
+            "Fuseau horaire inconnu '{}'. Utilisez un nom IANA (ex: Europe/Paris, America/New_York, UTC)",
+            name.trim()
+        )
+    })
+}
+
+/// Définit le fuseau horaire utilisé pour saisir et afficher les dates.
+/// Sans effet si un fuseau a déjà été défini ou utilisé.
+pub fn set_timezone(tz: Tz) {
+    let _ = TIMEZONE.set(tz);
+}
+
+/// Fuseau horaire utilisé pour saisir et afficher les dates : celui défini par
+/// [`set_timezone`], sinon celui du système (variable `TZ`, puis configuration du
+/// système), sinon UTC
+pub fn timezone() -> Tz {
+    *TIMEZONE.get_or_init(system_timezone)
+}
+
+fn system_timezone() -> Tz {
+    std::env::var("TZ")
+        .ok()
+        .and_then(|tz| tz.trim_start_matches(':').parse().ok())
+        .or_else(|| {
+            iana_time_zone::get_timezone()
+                .ok()
+                .and_then(|tz| tz.parse().ok())
+        })
+        .unwrap_or(Tz::UTC)
+}
+
+/// Convertit un instant dans le fuseau horaire configuré
+pub fn to_local(date: &DateTime<Utc>) -> DateTime<Tz> {
+    date.with_timezone(&timezone())
+}
+
+/// Analyse une échéance saisie par l'utilisateur, dans le fuseau configuré.
+/// Retourne `None` pour `none` (pas d'échéance).
+pub fn parse_due(input: &str) -> Result<Option<DateTime<Utc>>, String> {
+    parse_due_at(input, &to_local(&Utc::now()))
+}
+
+/// Comme [`parse_due`], par rapport à l'instant `now` et dans son fuseau horaire.
+///
+/// Aux changements d'heure, une heure répétée désigne sa première occurrence et une
+/// heure sautée est décalée d'une heure (`02:30` devient `03:30`).
+pub fn parse_due_at<Z: TimeZone>(
+    input: &str,
+    now: &DateTime<Z>,
+) -> Result<Option<DateTime<Utc>>, String> {
+    if input.trim().eq_ignore_ascii_case("none") {
+        return Ok(None);
+    }
+
+    let tz = now.timezone();
+    match resolve(input, now.date_naive())? {
+        Resolved::Instant(instant) => Ok(Some(instant)),
+        Resolved::Day(day) => Ok(Some(day_start_in(day, &tz))),
+        Resolved::Local(datetime) => local_to_utc(datetime, &tz)
+            .map(Some)
+            .ok_or_else(|| format!("L'heure '{}' n'existe pas dans le fuseau local", input)),
+    }
+}
+
+/// Convertit une date et heure locales du fuseau `tz` en UTC : une heure répétée désigne
+/// sa première occurrence, une heure sautée est décalée d'une heure
+pub fn local_to_utc<Z: TimeZone>(datetime: NaiveDateTime, tz: &Z) -> Option<DateTime<Utc>> {
+    tz.from_local_datetime(&datetime)
+        .earliest()
+        .or_else(|| {
+            tz.from_local_datetime(&(datetime + Duration::hours(1)))
+                .earliest()
+        })
+        .map(|dt| dt.with_timezone(&Utc))
+}
+
+/// Analyse un jour saisi par l'utilisateur (filtres au jour près), dans le fuseau configuré
+pub fn parse_day(input: &str) -> Result<NaiveDate, String> {
+    parse_day_at(input, &to_local(&Utc::now()))
+}
+
+/// Comme [`parse_day`], par rapport à l'instant `now` et dans son fuseau horaire
+pub fn parse_day_at<Z: TimeZone>(input: &str, now: &DateTime<Z>) -> Result<NaiveDate, String> {
+    match resolve(input, now.date_naive())? {
+        Resolved::Day(day) => Ok(day),
+        Resolved::Local(datetime) => Ok(datetime.date()),
+        Resolved::Instant(instant) => Ok(instant.with_timezone(&now.timezone()).date_naive()),
+    }
+}
+
+/// Début du jour `date` dans le fuseau configuré, en UTC
+pub fn local_day_start(date: NaiveDate) -> DateTime<Utc> {
+    day_start_in(date, &timezone())
+}
+
+/// Début du jour `date` dans le fuseau `tz`, en UTC.
+/// Si minuit n'existe pas (changement d'heure), le jour commence à la première heure valide.
+pub fn day_start_in<Z: TimeZone>(date: NaiveDate, tz: &Z) -> DateTime<Utc> {
+    let midnight = date.and_time(NaiveTime::MIN);
+    (0..=2)
+        .find_map(|hours| {
+            tz.from_local_datetime(&(midnight + Duration::hours(hours)))
+                .earliest()
+        })
+        .map(|dt| dt.with_timezone(&Utc))
+        .unwrap_or_else(|| midnight.and_utc())
+}
+
+/// Durée approximative, arrondie à l'unité inférieure (`moins d'une minute`, `15 min`,
+/// `3 h`, `2 jours`, `3 semaines`, `4 mois`). Les durées négatives sont prises en valeur absolue.
+pub fn format_duration(duration: Duration) -> String {
+    let duration = duration.abs();
+    let plural = |n: i64, singular: &str, plural: &str| {
+        format!("{} {}", n, if n > 1 { plural } else { singular })
+    };
+
+    if duration < Duration::minutes(1) {
+        "moins d'une minute".to_string()
+    } else if duration < Duration::hours(1) {
+        format!("{} min", duration.num_minutes())
+    } else if duration < Duration::days(2) {
+        format!("{} h", duration.num_hours())
+    } else if duration < Duration::weeks(2) {
+        plural(duration.num_days(), "jour", "jours")
+    } else if duration < Duration::days(60) {
+        plural(duration.num_weeks(), "semaine", "semaines")
+    } else {
+        format!("{} mois", duration.num_days() / 30)
+    }
+}
+
+/// Situe une échéance par rapport à `now` : `dans 2 jours` ou `en retard de 3 h`
+pub fn describe_due(due: &DateTime<Utc>, now: &DateTime<Utc>) -> String {
+    let remaining = *due - *now;
+    if remaining < Duration::zero() {
+        format!("en retard de {}", format_duration(remaining))
+    } else {
+        format!("dans {}", format_duration(remaining))
+    }
+}
+
+/// Ancienneté d'une date par rapport à `now` (`il y a 5 jours`)
+pub fn describe_ago(date: &DateTime<Utc>, now: &DateTime<Utc>) -> String {
+    format!(
+        "il y a {}",
+        format_duration((*now - *date).max(Duration::zero()))
+    )
+}
+
+fn invalid(input: &str) -> String {
+    format!(
+        "Format de date invalide '{}'. Utilisez: {}",
+        input.trim(),
+        FORMATS
+    )
+}
+
+fn resolve(input: &str, today: NaiveDate) -> Result<Resolved, String> {
+    let trimmed = input.trim();
+
+    if let Ok(dt) = DateTime::parse_from_rfc3339(trimmed) {
+        return Ok(Resolved::Instant(dt.with_timezone(&Utc)));
+    }
+    if let Ok(datetime) = NaiveDateTime::parse_from_str(trimmed, "%Y-%m-%dT%H:%M") {
+        return Ok(Resolved::Local(datetime));
+    }
+
+    let lower = trimmed.to_lowercase();
+    let words: Vec<&str> = lower.split_whitespace().collect();
+
+    // Heure optionnelle en fin de saisie
+    let (words, time) = match words.split_last() {
+        Some((last, rest)) if !rest.is_empty() => match NaiveTime::parse_from_str(last, "%H:%M") {
+            Ok(time) => (rest, Some(time)),
+            Err(_) => (&words[..], None),
+        },
+        _ => (&words[..], None),
+    };
+
+    let day = match words {
+        [word] => resolve_word(word, today),
+        ["next", "week"] => today.checked_add_days(Days::new(
+            7 - u64::from(today.weekday().num_days_from_monday()),
+        )),
+        ["next", "month"] => today
+            .with_day(1)
+            .and_then(|first| first.checked_add_months(Months::new(1))),
+        ["next", word] => parse_weekday(word).and_then(|weekday| next_weekday(today, weekday)),
+        _ => None,
+    };
+
+    match (day, time) {
+        (Some(day), Some(time)) => Ok(Resolved::Local(day.and_time(time))),
+        (Some(day), None) => Ok(Resolved::Day(day)),
+        (None, _) => Err(invalid(input)),
+    }
+}
+
+fn resolve_word(word: &str, today: NaiveDate) -> Option<NaiveDate> {
+    match word {
+        "today" => Some(today),
+        "tomorrow" => today.succ_opt(),
+        "yesterday" => today.pred_opt(),
+        "eow" => today.checked_add_days(Days::new(
+            6 - u64::from(today.weekday().num_days_from_monday()),
+        )),
+        "eom" => today
+            .with_day(1)?
+            .checked_add_months(Months::new(1))?
+            .pred_opt(),
+        "eoy" => NaiveDate::from_ymd_opt(today.year(), 12, 31),
+        _ => {
+            if let Some(offset) = word.strip_prefix('+') {
+                return add_offset(today, offset);
+            }
+            if let Some(weekday) = parse_weekday(word) {
+                return next_weekday(today, weekday);
+            }
+            NaiveDate::parse_from_str(word, "%Y-%m-%d").ok()
+        }
+    }
+}
+
+/// Décalage relatif `3d`, `2w` ou `1m`
+fn add_offset(today: NaiveDate, offset: &str) -> Option<NaiveDate> {
+    let unit = offset.chars().last()?;
+    let count: u32 = offset[..offset.len() - unit.len_utf8()].parse().ok()?;
+    match unit {
+        'd' => today.checked_add_days(Days::new(u64::from(count))),
+        'w' => today.checked_add_days(Days::new(u64::from(count) * 7)),
+        'm' => today.checked_add_months(Months::new(count)),
+        _ => None,
+    }
+}
+
+/// Jour de la semaine en anglais, complet ou abrégé (`monday`, `mon`), en minuscules
+pub(crate) fn parse_weekday(word: &str) -> Option<Weekday> {
+    match word {
+        "monday" | "mon" => Some(Weekday::Mon),
+        "tuesday" | "tue" => Some(Weekday::Tue),
+        "wednesday" | "wed" => Some(Weekday::Wed),
+        "thursday" | "thu" => Some(Weekday::Thu),
+        "friday" | "fri" => Some(Weekday::Fri),
+        "saturday" | "sat" => Some(Weekday::Sat),
+        "sunday" | "sun" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+/// Prochaine occurrence de `weekday`, strictement après `today`
+fn next_weekday(today: NaiveDate, weekday: Weekday) -> Option<NaiveDate> {
+    let days = (7 + weekday.num_days_from_monday() - today.weekday().num_days_from_monday()) % 7;
+    today.checked_add_days(Days::new(if days == 0 { 7 } else { u64::from(days) }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::FixedOffset;
+
+    /// Dimanche 18 octobre 2026, 10:30 à UTC+2
+    fn now() -> DateTime<FixedOffset> {
+        DateTime::parse_from_rfc3339("2026-10-18T10:30:00+02:00").unwrap()
+    }
+
+    fn day(input: &str) -> String {
+        parse_day_at(input, &now()).unwrap().to_string()
+    }
+
+    #[test]
+    fn test_relative_descriptions() {
+        let now = now().to_utc();
+        let at = |offset: Duration| now + offset;
+
+        assert_eq!(format_duration(Duration::seconds(20)), "moins d'une minute");
+        assert_eq!(format_duration(Duration::minutes(15)), "15 min");
+        assert_eq!(format_duration(Duration::minutes(-185)), "3 h");
+        assert_eq!(format_duration(Duration::hours(47)), "47 h");
+        assert_eq!(format_duration(Duration::days(1) * 2), "2 jours");
+        assert_eq!(format_duration(Duration::days(15)), "2 semaines");
+        assert_eq!(format_duration(Duration::days(95)), "3 mois");
+
+        assert_eq!(
+            describe_due(&at(Duration::days(2) + Duration::hours(3)), &now),
+            "dans 2 jours"
+        );
+        assert_eq!(
+            describe_due(&at(Duration::hours(-3)), &now),
+            "en retard de 3 h"
+        );
+        assert_eq!(
+            describe_ago(&at(Duration::days(-5)), &now),
+            "il y a 5 jours"
+        );
+        assert_eq!(
+            describe_ago(&at(Duration::minutes(1)), &now),
+            "il y a moins d'une minute"
+        );
+    }
+
+    #[test]
+    fn test_parse_relative_days() {
+        assert_eq!(day("today"), "2026-10-18");
+        assert_eq!(day("Tomorrow"), "2026-10-19");
+        assert_eq!(day("friday"), "2026-10-23");
+        assert_eq!(day("sunday"), "2026-10-25");
+        assert_eq!(day("next mon"), "2026-10-19");
+        assert_eq!(day("+3d"), "2026-10-21");
+        assert_eq!(day("+2w"), "2026-11-01");
+        assert_eq!(day("+1m"), "2026-11-18");
+        assert_eq!(day("next week"), "2026-10-19");
+        assert_eq!(day("next month"), "2026-11-01");
+        assert_eq!(day("eow"), "2026-10-18");
+        assert_eq!(day("eom"), "2026-10-31");
+        assert_eq!(day("eoy"), "2026-12-31");
+        assert_eq!(day("2026-11-02"), "2026-11-02");
+    }
+
+    #[test]
+    fn test_parse_due_in_local_timezone() {
+        let due = |input| parse_due_at(input, &now()).unwrap().unwrap().to_rfc3339();
+
+        // Minuit et 14:00 heure locale (UTC+2)
+        assert_eq!(due("2026-11-02"), "2026-11-01T22:00:00+00:00");
+        assert_eq!(due("2026-11-02 14:00"), "2026-11-02T12:00:00+00:00");
+        assert_eq!(due("tomorrow 09:30"), "2026-10-19T07:30:00+00:00");
+        // Un décalage explicite est conservé
+        assert_eq!(due("2026-11-02T14:00:00Z"), "2026-11-02T14:00:00+00:00");
+
+        assert_eq!(parse_due_at("none", &now()), Ok(None));
+    }
+
+    #[test]
+    fn test_parse_invalid_dates() {
+        for input in ["", "soon", "+3x", "+d", "2026-13-01", "next year", "14:00"] {
+            let err = parse_due_at(input, &now()).unwrap_err();
+            assert!(err.starts_with("Format de date invalide"), "{}", input);
+        }
+        assert!(parse_day_at("none", &now()).is_err());
+    }
+
+    #[test]
+    fn test_parse_due_across_dst_transitions() {
+        let paris = chrono_tz::Europe::Paris;
+        let now = paris.with_ymd_and_hms(2026, 3, 28, 10, 0, 0).unwrap();
+        let due = |input| parse_due_at(input, &now).unwrap().unwrap().to_rfc3339();
+
+        // Passage à l'heure d'été le 29 mars : la journée ne dure que 23 heures
+        assert_eq!(due("tomorrow"), "2026-03-28T23:00:00+00:00");
+        assert_eq!(due("+2d"), "2026-03-29T22:00:00+00:00");
+        // 02:30 n'existe pas : décalé à 03:30 (heure d'été)
+        assert_eq!(due("2026-03-29 02:30"), "2026-03-29T01:30:00+00:00");
+
+        // Retour à l'heure d'hiver le 25 octobre : 02:30 existe deux fois
+        assert_eq!(due("2026-10-25 02:30"), "2026-10-25T00:30:00+00:00");
+        assert_eq!(due("2026-10-26"), "2026-10-25T23:00:00+00:00");
+    }
+
+    #[test]
+    fn test_day_start_without_midnight() {
+        // À La Havane, le passage à l'heure d'été se fait à minuit (00:00 -> 01:00)
+        let havana = chrono_tz::America::Havana;
+        let day = NaiveDate::from_ymd_opt(2026, 3, 8).unwrap();
+        assert_eq!(
+            day_start_in(day, &havana).to_rfc3339(),
+            "2026-03-08T05:00:00+00:00"
+        );
+    }
+
+    #[test]
+    fn test_parse_timezone() {
+        assert_eq!(parse_timezone("Europe/Paris"), Ok(chrono_tz::Europe::Paris));
+        assert_eq!(parse_timezone(" UTC "), Ok(Tz::UTC));
+        assert!(
+            parse_timezone("Mars/Olympus")
+                .unwrap_err()
+                .starts_with("Fuseau horaire inconnu 'Mars/Olympus'")
+        );
+    }
+}
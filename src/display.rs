@@ -1 +1,134 @@
+use crate::dates;
+use crate::estimate::Estimate;
+use crate::filter::FilterError;
+use crate::storage::{Operation, OperationKind};
+use crate::task::{Status, Task};
+use chrono::{DateTime, Utc};
+use clap::ArgMatches;
+use colored::{ColoredString, Colorize};
+use serde::Serialize;
+use std::ops::Range;
 
+/// Format de sortie des commandes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Text,
+    Json,
+}
+
+impl OutputFormat {
+    /// Lit l'option globale `--format` (texte par défaut)
+    pub fn from_matches(matches: &ArgMatches) -> Self {
+        match matches.get_one::<String>("format").map(String::as_str) {
+            Some("json") => OutputFormat::Json,
+            _ => OutputFormat::Text,
+        }
+    }
+}
+
+/// Affiche une valeur en JSON sur la sortie standard
+pub fn print_json<T: Serialize + ?Sized>(value: &T) {
+    match serde_json::to_string_pretty(value) {
+        Ok(json) => println!("{}", json),
+        Err(e) => eprintln!("Erreur: Impossible de sérialiser la sortie JSON: {}", e),
+    }
+}
+
+/// Symbole coloré du statut d'une task (○ ✓ ⚙ ✗)
+pub fn status_symbol(status: Option<&Status>) -> ColoredString {
+    match status {
+        Some(Status::Completed) => "✓".green(),
+        Some(Status::InProgress) => "⚙".yellow(),
+        Some(Status::Canceled) => "✗".red(),
+        _ => "○".white(),
+    }
+}
+
+/// Formate l'ID court d'une task (`-` si la task n'a pas encore été enregistrée)
+pub fn short_id_label(task: &Task) -> String {
+    task.short_id
+        .map(|id| id.to_string())
+        .unwrap_or_else(|| "-".to_string())
+}
+
+/// Formate une échéance dans le fuseau configuré (`2026-11-02`, ou `2026-11-02 14:00`
+/// si elle ne tombe pas au début du jour)
+pub fn format_due(due_date: &DateTime<Utc>) -> String {
+    let local = dates::to_local(due_date);
+    if *due_date == dates::local_day_start(local.date_naive()) {
+        local.format("%Y-%m-%d").to_string()
+    } else {
+        local.format("%Y-%m-%d %H:%M").to_string()
+    }
+}
+
+/// Formate une durée de travail à la minute près (`45 min`, `2 h 05 min`)
+pub fn format_worked(seconds: i64) -> String {
+    let minutes = seconds.max(0) / 60;
+    if minutes < 60 {
+        format!("{} min", minutes)
+    } else {
+        format!("{} h {:02} min", minutes / 60, minutes % 60)
+    }
+}
+
+/// Formate une estimation d'effort (`1 h 30 min`, `3 pt`)
+pub fn format_estimate(estimate: &Estimate) -> String {
+    match estimate {
+        Estimate::Minutes(minutes) => format_worked(i64::from(*minutes) * 60),
+        Estimate::Points(points) => format!("{} pt", points),
+    }
+}
+
+/// Décrit une opération du journal (ex: `Suppression de la tâche [3] Faire les courses`)
+pub fn describe_operation(operation: &Operation) -> String {
+    let label = match operation.kind {
+        OperationKind::Add => "Ajout",
+        OperationKind::Update => "Modification",
+        OperationKind::Delete => "Suppression",
+        OperationKind::Restore => "Restauration",
+        OperationKind::Note => "Note",
+        OperationKind::TimeTracking => "Suivi du temps",
+    };
+    match operation.task() {
+        Some(task) => format!(
+            "{} de la tâche [{}] {}",
+            label,
+            short_id_label(task),
+            task.description
+        ),
+        None => format!("{} de la tâche {}", label, operation.task_id),
+    }
+}
+
+/// Met en évidence les passages `ranges` (positions en octets) d'un texte
+pub fn highlight_ranges(text: &str, ranges: &[Range<usize>]) -> String {
+    let mut result = String::new();
+    let mut pos = 0;
+
+    for range in ranges {
+        let (Some(before), Some(matched)) = (text.get(pos..range.start), text.get(range.clone()))
+        else {
+            continue;
+        };
+        result.push_str(before);
+        result.push_str(&matched.yellow().bold().to_string());
+        pos = range.end;
+    }
+    result.push_str(text.get(pos..).unwrap_or_default());
+    result
+}
+
+/// Affiche une erreur d'expression de filtre, avec un repère sous la position fautive
+pub fn print_filter_error(input: &str, error: &FilterError) {
+    eprintln!("{} Filtre invalide: {}", "Erreur:".red(), error);
+    eprintln!("  {}", input);
+    eprintln!("  {}{}", " ".repeat(error.position), "^".red());
+}
+
+/// Affiche une erreur de syntaxe de recherche, avec un repère sous la position fautive
+pub fn print_search_error(input: &str, error: &FilterError) {
+    eprintln!("{} Recherche invalide: {}", "Erreur:".red(), error);
+    eprintln!("  {}", input);
+    eprintln!("  {}{}", " ".repeat(error.position), "^".red());
+}
@@ -0,0 +1,152 @@
+//! Estimations d'effort des tasks.
+//!
+//! | Saisie | Estimation |
+//! |--------|------------|
+//! | `45m`, `45min` | 45 minutes |
+//! | `2h`, `1h30`, `1h30m` | Durée en heures et minutes |
+//! | `3pt`, `3pts`, `3sp` | 3 points d'effort (story points) |
+//!
+//! Seules les estimations en minutes se comparent au temps passé ; les points ne se
+//! cumulent qu'entre eux.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Effort estimé d'une task
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum Estimate {
+    /// Durée en minutes
+    Minutes(u32),
+    /// Points d'effort, sans unité de temps
+    Points(u32),
+}
+
+const FORMATS: &str = "45m, 2h, 1h30 ou 3pt";
+
+impl Estimate {
+    /// Analyse une estimation saisie par l'utilisateur (`45m`, `2h`, `1h30`, `3pt`)
+    pub fn parse(input: &str) -> Result<Estimate, String> {
+        let lower = input.trim().to_lowercase().replace(' ', "");
+        let invalid = || {
+            format!(
+                "Estimation invalide '{}'. Utilisez: {}",
+                input.trim(),
+                FORMATS
+            )
+        };
+        let number = |s: &str| -> Result<u32, String> {
+            if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+                return Err(invalid());
+            }
+            s.parse().map_err(|_| invalid())
+        };
+
+        let estimate = if let Some(points) = ["pts", "pt", "sp"]
+            .iter()
+            .find_map(|suffix| lower.strip_suffix(suffix))
+        {
+            Estimate::Points(number(points)?)
+        } else if let Some((hours, minutes)) = lower.split_once('h') {
+            let minutes = minutes.strip_suffix('m').unwrap_or(minutes);
+            let minutes = if minutes.is_empty() {
+                0
+            } else {
+                number(minutes)?
+            };
+            if minutes >= 60 {
+                return Err(invalid());
+            }
+            number(hours)?
+                .checked_mul(60)
+                .and_then(|h| h.checked_add(minutes))
+                .map(Estimate::Minutes)
+                .ok_or_else(invalid)?
+        } else if let Some(minutes) = ["min", "m"]
+            .iter()
+            .find_map(|suffix| lower.strip_suffix(suffix))
+        {
+            Estimate::Minutes(number(minutes)?)
+        } else {
+            return Err(invalid());
+        };
+
+        match estimate {
+            Estimate::Minutes(0) | Estimate::Points(0) => Err(invalid()),
+            estimate => Ok(estimate),
+        }
+    }
+
+    /// Durée estimée en minutes, `None` pour une estimation en points
+    pub fn minutes(&self) -> Option<u32> {
+        match self {
+            Estimate::Minutes(minutes) => Some(*minutes),
+            Estimate::Points(_) => None,
+        }
+    }
+
+    /// Points estimés, `None` pour une estimation en durée
+    pub fn points(&self) -> Option<u32> {
+        match self {
+            Estimate::Minutes(_) => None,
+            Estimate::Points(points) => Some(*points),
+        }
+    }
+}
+
+impl fmt::Display for Estimate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Estimate::Minutes(minutes) if *minutes < 60 => write!(f, "{}m", minutes),
+            Estimate::Minutes(minutes) if minutes % 60 == 0 => write!(f, "{}h", minutes / 60),
+            Estimate::Minutes(minutes) => write!(f, "{}h{:02}", minutes / 60, minutes % 60),
+            Estimate::Points(points) => write!(f, "{}pt", points),
+        }
+    }
+}
+
+impl TryFrom<String> for Estimate {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Estimate::parse(&value)
+    }
+}
+
+impl From<Estimate> for String {
+    fn from(estimate: Estimate) -> Self {
+        estimate.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_display() {
+        for input in ["45m", "2h", "1h30", "1h05", "3pt"] {
+            assert_eq!(Estimate::parse(input).unwrap().to_string(), input);
+        }
+        assert_eq!(Estimate::parse("90min").unwrap(), Estimate::Minutes(90));
+        assert_eq!(Estimate::parse(" 1H30M ").unwrap(), Estimate::Minutes(90));
+        assert_eq!(Estimate::parse("1 h 30").unwrap(), Estimate::Minutes(90));
+        assert_eq!(Estimate::parse("5 pts").unwrap(), Estimate::Points(5));
+        assert_eq!(Estimate::parse("8sp").unwrap(), Estimate::Points(8));
+        assert_eq!(Estimate::Minutes(90).minutes(), Some(90));
+        assert_eq!(Estimate::Points(3).minutes(), None);
+        assert_eq!(Estimate::Points(3).points(), Some(3));
+
+        for input in [
+            "", "45", "0m", "0pt", "1h60", "h30", "-5m", "1.5h", "3 jours",
+        ] {
+            assert!(
+                Estimate::parse(input)
+                    .unwrap_err()
+                    .starts_with("Estimation invalide"),
+                "{}",
+                input
+            );
+        }
+    }
+}
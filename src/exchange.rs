@@ -0,0 +1,237 @@
+use crate::estimate::Estimate;
+use crate::recurrence::Recurrence;
+use crate::task::{Priority, Status, Task};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use std::path::Path;
+use uuid::Uuid;
+
+/// Format des fichiers d'export/import
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileFormat {
+    Json,
+    Csv,
+}
+
+impl FileFormat {
+    /// Convertit une chaîne (`json` ou `csv`) en format
+    pub fn parse(format_str: &str) -> Result<FileFormat, String> {
+        match format_str.to_lowercase().as_str() {
+            "json" => Ok(FileFormat::Json),
+            "csv" => Ok(FileFormat::Csv),
+            _ => Err(format!(
+                "Format de fichier invalide '{}'. Utilisez: json ou csv",
+                format_str
+            )),
+        }
+    }
+
+    /// Déduit le format de l'extension du fichier (JSON par défaut)
+    pub fn from_path(path: &Path) -> FileFormat {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("csv") => FileFormat::Csv,
+            _ => FileFormat::Json,
+        }
+    }
+}
+
+/// Ligne CSV : une task par ligne, tags séparés par des virgules
+#[derive(Debug, Serialize, Deserialize)]
+struct CsvRecord {
+    id: Uuid,
+    short_id: Option<u32>,
+    description: String,
+    priority: Priority,
+    status: Option<Status>,
+    due_date: Option<DateTime<Utc>>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+    tags: String,
+    #[serde(default)]
+    deleted_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    recurrence: Option<Recurrence>,
+    #[serde(default)]
+    parent_id: Option<Uuid>,
+    /// UUID des dépendances, séparés par des virgules
+    #[serde(default)]
+    depends_on: String,
+    #[serde(default)]
+    project: Option<String>,
+    #[serde(default)]
+    estimate: Option<Estimate>,
+}
+
+impl From<&Task> for CsvRecord {
+    fn from(task: &Task) -> Self {
+        CsvRecord {
+            id: task.id,
+            short_id: task.short_id,
+            description: task.description.clone(),
+            priority: task.priority.clone(),
+            status: task.status.clone(),
+            due_date: task.due_date,
+            created_at: task.created_at,
+            updated_at: task.updated_at,
+            tags: task.tags.as_ref().map(|t| t.join(",")).unwrap_or_default(),
+            deleted_at: task.deleted_at,
+            recurrence: task.recurrence.clone(),
+            parent_id: task.parent_id,
+            depends_on: task
+                .depends_on
+                .iter()
+                .map(Uuid::to_string)
+                .collect::<Vec<_>>()
+                .join(","),
+            project: task.project.clone(),
+            estimate: task.estimate,
+        }
+    }
+}
+
+impl From<CsvRecord> for Task {
+    fn from(record: CsvRecord) -> Self {
+        let tags: Vec<String> = record
+            .tags
+            .split(',')
+            .map(|t| t.trim().to_string())
+            .filter(|t| !t.is_empty())
+            .collect();
+
+        Task {
+            id: record.id,
+            short_id: record.short_id,
+            description: record.description,
+            tags: if tags.is_empty() { None } else { Some(tags) },
+            status: record.status,
+            priority: record.priority,
+            due_date: record.due_date,
+            created_at: record.created_at,
+            updated_at: record.updated_at,
+            deleted_at: record.deleted_at,
+            recurrence: record.recurrence,
+            parent_id: record.parent_id,
+            depends_on: record
+                .depends_on
+                .split(',')
+                .filter_map(|id| Uuid::parse_str(id.trim()).ok())
+                .collect(),
+            project: record.project.filter(|p| !p.is_empty()),
+            estimate: record.estimate,
+        }
+    }
+}
+
+/// Écrit les tasks au format demandé
+pub fn write_tasks<W: Write>(writer: W, tasks: &[Task], format: FileFormat) -> Result<(), String> {
+    match format {
+        FileFormat::Json => {
+            let mut writer = writer;
+            serde_json::to_writer_pretty(&mut writer, tasks)
+                .map_err(|e| format!("Écriture JSON impossible: {}", e))?;
+            writeln!(writer).map_err(|e| format!("Écriture impossible: {}", e))
+        }
+        FileFormat::Csv => {
+            let mut csv_writer = csv::Writer::from_writer(writer);
+            for task in tasks {
+                csv_writer
+                    .serialize(CsvRecord::from(task))
+                    .map_err(|e| format!("Écriture CSV impossible: {}", e))?;
+            }
+            csv_writer
+                .flush()
+                .map_err(|e| format!("Écriture CSV impossible: {}", e))
+        }
+    }
+}
+
+/// Lit des tasks au format demandé
+pub fn read_tasks<R: Read>(reader: R, format: FileFormat) -> Result<Vec<Task>, String> {
+    match format {
+        FileFormat::Json => {
+            serde_json::from_reader(reader).map_err(|e| format!("JSON invalide: {}", e))
+        }
+        FileFormat::Csv => csv::Reader::from_reader(reader)
+            .deserialize::<CsvRecord>()
+            .map(|record| {
+                record
+                    .map(Task::from)
+                    .map_err(|e| format!("CSV invalide: {}", e))
+            })
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_tasks() -> Vec<Task> {
+        let mut task1 = Task::new(
+            "Préparer, \"relire\" le rapport",
+            Some(vec!["work".to_string(), "urgent".to_string()]),
+            Some(Status::InProgress),
+            Some(Priority::High),
+            None,
+        );
+        task1.short_id = Some(1);
+        task1.recurrence = Some(Recurrence::Daily);
+        task1.project = Some("Maison".to_string());
+        task1.estimate = Some(Estimate::Minutes(90));
+        let mut task2 = Task::new("Sans tags", None, None, None, None);
+        task2.deleted_at = Some(Utc::now());
+        task2.parent_id = Some(task1.id);
+        task2.depends_on = vec![task1.id, Uuid::new_v4()];
+        vec![task1, task2]
+    }
+
+    fn round_trip(format: FileFormat) {
+        let tasks = sample_tasks();
+        let mut buffer = Vec::new();
+        write_tasks(&mut buffer, &tasks, format).unwrap();
+        let read = read_tasks(buffer.as_slice(), format).unwrap();
+
+        assert_eq!(read.len(), tasks.len());
+        for (original, read) in tasks.iter().zip(&read) {
+            assert_eq!(read.id, original.id);
+            assert_eq!(read.short_id, original.short_id);
+            assert_eq!(read.description, original.description);
+            assert_eq!(read.tags, original.tags);
+            assert_eq!(read.status, original.status);
+            assert_eq!(read.priority, original.priority);
+            assert_eq!(read.due_date, original.due_date);
+            assert_eq!(read.created_at, original.created_at);
+            assert_eq!(read.updated_at, original.updated_at);
+            assert_eq!(read.deleted_at, original.deleted_at);
+            assert_eq!(read.recurrence, original.recurrence);
+            assert_eq!(read.parent_id, original.parent_id);
+            assert_eq!(read.depends_on, original.depends_on);
+            assert_eq!(read.project, original.project);
+            assert_eq!(read.estimate, original.estimate);
+        }
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        round_trip(FileFormat::Json);
+    }
+
+    #[test]
+    fn test_csv_round_trip() {
+        round_trip(FileFormat::Csv);
+    }
+
+    #[test]
+    fn test_invalid_csv_is_rejected() {
+        let input = "id,short_id,description\nnot-a-uuid,1,Test\n";
+        assert!(read_tasks(input.as_bytes(), FileFormat::Csv).is_err());
+    }
+
+    #[test]
+    fn test_format_from_path() {
+        assert_eq!(FileFormat::from_path(Path::new("a.CSV")), FileFormat::Csv);
+        assert_eq!(FileFormat::from_path(Path::new("a.json")), FileFormat::Json);
+        assert_eq!(FileFormat::from_path(Path::new("backup")), FileFormat::Json);
+    }
+}
@@ -0,0 +1,474 @@
+//! Langage de filtre des commandes `list` et `search`.
+//!
+//! ```text
+//! status:inprogress and (tag:work or priority:high) and due<2026-11-01
+//! ```
+//!
+//! Une expression combine des conditions `champ opérateur valeur` avec `and`, `or`,
+//! `not` et des parenthèses. Deux conditions juxtaposées sont combinées par `and`.
+
+use crate::dates;
+use crate::storage::check_search;
+use crate::task::{Priority, Status};
+use chrono::NaiveDate;
+use std::fmt;
+
+/// Opérateur de comparaison. `:` est un synonyme de `=`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompareOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+/// Condition élémentaire sur un champ d'une task
+#[derive(Debug, Clone, PartialEq)]
+pub enum Condition {
+    Status(CompareOp, Status),
+    /// Les comparaisons suivent l'ordre Low < Medium < High
+    Priority(CompareOp, Priority),
+    Tag(CompareOp, String),
+    /// Comparaison au jour près, dans le fuseau local.
+    /// `None` désigne les tasks sans échéance (`due:none`).
+    Due(CompareOp, Option<NaiveDate>),
+    /// Recherche plein texte dans la description
+    Text(String),
+}
+
+/// Arbre syntaxique d'une expression de filtre
+#[derive(Debug, Clone, PartialEq)]
+pub enum Filter {
+    Condition(Condition),
+    Not(Box<Filter>),
+    And(Box<Filter>, Box<Filter>),
+    Or(Box<Filter>, Box<Filter>),
+}
+
+impl Filter {
+    /// Combine deux filtres par ET
+    pub fn and(self, other: Filter) -> Filter {
+        Filter::And(Box::new(self), Box::new(other))
+    }
+}
+
+/// Erreur d'analyse, avec la position (en caractères, à partir de 0) du problème
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FilterError {
+    pub position: usize,
+    pub message: String,
+}
+
+impl fmt::Display for FilterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (position {})", self.message, self.position + 1)
+    }
+}
+
+impl std::error::Error for FilterError {}
+
+#[derive(Debug, Clone, PartialEq)]
+enum TokenKind {
+    LParen,
+    RParen,
+    Op(CompareOp),
+    Word(String),
+    Quoted(String),
+}
+
+#[derive(Debug, Clone)]
+struct Token {
+    kind: TokenKind,
+    position: usize,
+}
+
+fn error<T>(position: usize, message: impl Into<String>) -> Result<T, FilterError> {
+    Err(FilterError {
+        position,
+        message: message.into(),
+    })
+}
+
+/// Découpe l'expression en jetons
+fn tokenize(input: &str) -> Result<Vec<Token>, FilterError> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let position = i;
+        let kind = match chars[i] {
+            c if c.is_whitespace() => {
+                i += 1;
+                continue;
+            }
+            '(' => {
+                i += 1;
+                TokenKind::LParen
+            }
+            ')' => {
+                i += 1;
+                TokenKind::RParen
+            }
+            ':' | '=' => {
+                i += 1;
+                TokenKind::Op(CompareOp::Eq)
+            }
+            '!' if chars.get(i + 1) == Some(&'=') => {
+                i += 2;
+                TokenKind::Op(CompareOp::Ne)
+            }
+            '<' | '>' => {
+                let or_equal = chars.get(i + 1) == Some(&'=');
+                let op = match (chars[i], or_equal) {
+                    ('<', false) => CompareOp::Lt,
+                    ('<', true) => CompareOp::Le,
+                    (_, false) => CompareOp::Gt,
+                    (_, true) => CompareOp::Ge,
+                };
+                i += if or_equal { 2 } else { 1 };
+                TokenKind::Op(op)
+            }
+            '"' => {
+                let end = match chars[i + 1..].iter().position(|&c| c == '"') {
+                    Some(offset) => i + 1 + offset,
+                    None => return error(position, "Guillemet fermant manquant"),
+                };
+                let value: String = chars[i + 1..end].iter().collect();
+                i = end + 1;
+                TokenKind::Quoted(value)
+            }
+            _ => {
+                let start = i;
+                while i < chars.len()
+                    && !chars[i].is_whitespace()
+                    && !matches!(chars[i], '(' | ')' | ':' | '=' | '!' | '<' | '>' | '"')
+                {
+                    i += 1;
+                }
+                if i == start {
+                    return error(position, format!("Caractère inattendu '{}'", chars[i]));
+                }
+                TokenKind::Word(chars[start..i].iter().collect())
+            }
+        };
+        tokens.push(Token { kind, position });
+    }
+
+    Ok(tokens)
+}
+
+/// Analyse une expression de filtre
+pub fn parse(input: &str) -> Result<Filter, FilterError> {
+    let mut parser = Parser {
+        tokens: tokenize(input)?,
+        pos: 0,
+        end: input.chars().count(),
+    };
+
+    if parser.tokens.is_empty() {
+        return error(0, "Expression vide");
+    }
+
+    let filter = parser.parse_or()?;
+    match parser.peek() {
+        None => Ok(filter),
+        Some(Token {
+            kind: TokenKind::RParen,
+            position,
+        }) => error(*position, "Parenthèse fermante sans parenthèse ouvrante"),
+        Some(token) => error(token.position, "Opérateur 'and' ou 'or' attendu"),
+    }
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    /// Position de fin de l'expression, pour les erreurs en fin de saisie
+    end: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    /// Indique si le prochain jeton est le mot-clé `keyword` (insensible à la casse)
+    fn peek_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Token { kind: TokenKind::Word(w), .. }) if w.eq_ignore_ascii_case(keyword))
+    }
+
+    fn parse_or(&mut self) -> Result<Filter, FilterError> {
+        let mut left = self.parse_and()?;
+        while self.peek_keyword("or") {
+            self.next();
+            let right = self.parse_and()?;
+            left = Filter::Or(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_and(&mut self) -> Result<Filter, FilterError> {
+        let mut left = self.parse_not()?;
+        loop {
+            if self.peek_keyword("and") {
+                self.next();
+            } else if self.peek_keyword("or")
+                || matches!(
+                    self.peek(),
+                    None | Some(Token {
+                        kind: TokenKind::RParen,
+                        ..
+                    })
+                )
+            {
+                break;
+            }
+            // Deux conditions juxtaposées : ET implicite
+            let right = self.parse_not()?;
+            left = left.and(right);
+        }
+        Ok(left)
+    }
+
+    fn parse_not(&mut self) -> Result<Filter, FilterError> {
+        if self.peek_keyword("not") {
+            self.next();
+            return Ok(Filter::Not(Box::new(self.parse_not()?)));
+        }
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> Result<Filter, FilterError> {
+        let Some(token) = self.next() else {
+            return error(self.end, "Condition attendue en fin d'expression");
+        };
+
+        match token.kind {
+            TokenKind::LParen => {
+                let filter = self.parse_or()?;
+                match self.next() {
+                    Some(Token {
+                        kind: TokenKind::RParen,
+                        ..
+                    }) => Ok(filter),
+                    _ => error(token.position, "Parenthèse ouvrante non fermée"),
+                }
+            }
+            TokenKind::Word(field) => self.parse_condition(&field, token.position),
+            _ => error(
+                token.position,
+                "Condition attendue (ex: status:inprogress, tag:work, due<2026-11-01)",
+            ),
+        }
+    }
+
+    fn parse_condition(&mut self, field: &str, position: usize) -> Result<Filter, FilterError> {
+        let field_lower = field.to_lowercase();
+        if !matches!(
+            field_lower.as_str(),
+            "status" | "priority" | "tag" | "due" | "text"
+        ) {
+            return error(
+                position,
+                format!(
+                    "Champ inconnu '{}'. Utilisez: status, priority, tag, due ou text",
+                    field
+                ),
+            );
+        }
+
+        let (op, op_position) = match self.next() {
+            Some(Token {
+                kind: TokenKind::Op(op),
+                position,
+            }) => (op, position),
+            Some(token) => {
+                return error(
+                    token.position,
+                    format!(
+                        "Opérateur attendu après '{}' (:, =, !=, <, <=, >, >=)",
+                        field
+                    ),
+                );
+            }
+            None => {
+                return error(
+                    self.end,
+                    format!(
+                        "Opérateur attendu après '{}' (:, =, !=, <, <=, >, >=)",
+                        field
+                    ),
+                );
+            }
+        };
+
+        // `text_position` : premier caractère de la valeur, après un éventuel guillemet
+        let (value, value_position, text_position) = match self.next() {
+            Some(Token {
+                kind: TokenKind::Word(value),
+                position,
+            }) => (value, position, position),
+            Some(Token {
+                kind: TokenKind::Quoted(value),
+                position,
+            }) => (value, position, position + 1),
+            Some(token) => return error(token.position, "Valeur attendue"),
+            None => return error(self.end, "Valeur attendue en fin d'expression"),
+        };
+
+        let ordered = matches!(field_lower.as_str(), "priority" | "due");
+        let equality = matches!(op, CompareOp::Eq | CompareOp::Ne);
+        if !ordered && !equality || field_lower == "text" && op != CompareOp::Eq {
+            return error(
+                op_position,
+                format!("Opérateur non supporté pour le champ '{}'", field_lower),
+            );
+        }
+
+        let condition = match field_lower.as_str() {
+            "status" => Condition::Status(op, parse_status(&value, value_position)?),
+            "priority" => Condition::Priority(op, parse_priority(&value, value_position)?),
+            "tag" => Condition::Tag(op, value),
+            "due" if value.eq_ignore_ascii_case("none") => {
+                if !equality {
+                    return error(
+                        op_position,
+                        "Seuls les opérateurs :, = et != sont supportés avec 'none'",
+                    );
+                }
+                Condition::Due(op, None)
+            }
+            "due" => Condition::Due(op, Some(parse_date(&value, value_position)?)),
+            _ => {
+                check_search(&value).map_err(|e| FilterError {
+                    position: text_position + e.position,
+                    message: e.message,
+                })?;
+                Condition::Text(value)
+            }
+        };
+        Ok(Filter::Condition(condition))
+    }
+}
+
+fn parse_status(value: &str, position: usize) -> Result<Status, FilterError> {
+    match value.to_lowercase().as_str() {
+        "completed" => Ok(Status::Completed),
+        "notstarted" | "not_started" => Ok(Status::NotStarted),
+        "inprogress" | "in_progress" => Ok(Status::InProgress),
+        "canceled" | "cancelled" => Ok(Status::Canceled),
+        _ => error(
+            position,
+            format!(
+                "Statut invalide '{}'. Utilisez: completed, notstarted, inprogress ou canceled",
+                value
+            ),
+        ),
+    }
+}
+
+fn parse_priority(value: &str, position: usize) -> Result<Priority, FilterError> {
+    match value.to_lowercase().as_str() {
+        "high" => Ok(Priority::High),
+        "medium" => Ok(Priority::Medium),
+        "low" => Ok(Priority::Low),
+        _ => error(
+            position,
+            format!(
+                "Priorité invalide '{}'. Utilisez: high, medium ou low",
+                value
+            ),
+        ),
+    }
+}
+
+fn parse_date(value: &str, position: usize) -> Result<NaiveDate, FilterError> {
+    dates::parse_day(value).or_else(|message| error(position, message))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cond(condition: Condition) -> Filter {
+        Filter::Condition(condition)
+    }
+
+    #[test]
+    fn test_parse_precedence() {
+        let filter =
+            parse("status:inprogress and (tag:work or priority:high) and due<2026-11-01").unwrap();
+        let expected = cond(Condition::Status(CompareOp::Eq, Status::InProgress))
+            .and(Filter::Or(
+                Box::new(cond(Condition::Tag(CompareOp::Eq, "work".to_string()))),
+                Box::new(cond(Condition::Priority(CompareOp::Eq, Priority::High))),
+            ))
+            .and(cond(Condition::Due(
+                CompareOp::Lt,
+                NaiveDate::from_ymd_opt(2026, 11, 1),
+            )));
+        assert_eq!(filter, expected);
+
+        // `and` est prioritaire sur `or`, `not` sur `and`
+        let filter = parse("tag:a or not tag:b tag:c").unwrap();
+        let expected = Filter::Or(
+            Box::new(cond(Condition::Tag(CompareOp::Eq, "a".to_string()))),
+            Box::new(
+                Filter::Not(Box::new(cond(Condition::Tag(
+                    CompareOp::Eq,
+                    "b".to_string(),
+                ))))
+                .and(cond(Condition::Tag(CompareOp::Eq, "c".to_string()))),
+            ),
+        );
+        assert_eq!(filter, expected);
+    }
+
+    #[test]
+    fn test_parse_values() {
+        assert_eq!(
+            parse("PRIORITY>=medium").unwrap(),
+            cond(Condition::Priority(CompareOp::Ge, Priority::Medium))
+        );
+        assert_eq!(
+            parse("text:\"revue de code\"").unwrap(),
+            cond(Condition::Text("revue de code".to_string()))
+        );
+        assert_eq!(
+            parse("tag != perso").unwrap(),
+            cond(Condition::Tag(CompareOp::Ne, "perso".to_string()))
+        );
+        assert_eq!(
+            parse("due:None").unwrap(),
+            cond(Condition::Due(CompareOp::Eq, None))
+        );
+    }
+
+    #[test]
+    fn test_parse_error_positions() {
+        let position = |input: &str| parse(input).unwrap_err().position;
+
+        assert_eq!(position("stat:done"), 0);
+        assert_eq!(position("status:done"), 7);
+        assert_eq!(position("tag:work and (priority:high"), 13);
+        assert_eq!(position("tag:work)"), 8);
+        assert_eq!(position("tag<work"), 3);
+        assert_eq!(position("due<2026-13-01"), 4);
+        assert_eq!(position("due<none"), 3);
+        assert_eq!(position("tag:work and"), 12);
+        assert_eq!(position("tag work"), 4);
+        assert_eq!(position("text:\"non fermé"), 5);
+        assert_eq!(position("text:\"bug OR\""), 10);
+        assert_eq!(position("text:NOT"), 5);
+        assert_eq!(position("   "), 0);
+    }
+}
@@ -1,2 +1,8 @@
+pub mod dates;
+pub mod estimate;
+pub mod exchange;
+pub mod filter;
+pub mod recurrence;
 pub mod storage;
 pub mod task;
+pub mod urgency;
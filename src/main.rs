@@ -2,7 +2,8 @@ mod commands;
 mod display;
 
 use clap::{ArgAction, Command, arg, command};
-use task_cli::{storage, task};
+use colored::Colorize;
+use task_cli::{dates, estimate, exchange, filter, recurrence, storage, task, urgency};
 
 ///
 /// Powerful commands-line task manager with data persistence
@@ -12,6 +13,26 @@ fn main() {
         .propagate_version(true)
         .subcommand_required(true)
         .arg_required_else_help(true)
+        .arg(
+            arg!(--db <path> "Path to the SQLite database (env: TASK_CLI_DB)")
+                .required(false)
+                .global(true)
+                .action(ArgAction::Set),
+        )
+        .arg(
+            arg!(--tz <zone> "Time zone for dates, e.g. Europe/Paris (env: TASK_CLI_TZ)")
+                .required(false)
+                .global(true)
+                .action(ArgAction::Set),
+        )
+        .arg(
+            arg!(--format <format> "Output format")
+                .required(false)
+                .global(true)
+                .value_parser(["text", "json"])
+                .default_value("text")
+                .action(ArgAction::Set),
+        )
         .subcommand(
             Command::new("add")
                 .about("Adds a new task")
@@ -34,6 +55,26 @@ fn main() {
                     arg!(-d --due [due] "Due date")
                         .required(false)
                         .action(ArgAction::Set),
+                )
+                .arg(
+                    arg!(-r --recur [recur] "Recurrence rule: daily, weekly:mon,thu, monthly:15, after:3d")
+                        .required(false)
+                        .action(ArgAction::Set),
+                )
+                .arg(
+                    arg!(--parent [parent] "Parent task id")
+                        .required(false)
+                        .action(ArgAction::Set),
+                )
+                .arg(
+                    arg!(--project [project] "Project name")
+                        .required(false)
+                        .action(ArgAction::Set),
+                )
+                .arg(
+                    arg!(-e --estimate [estimate] "Effort estimate: 45m, 2h, 1h30 or 3pt")
+                        .required(false)
+                        .action(ArgAction::Set),
                 ),
         )
         .subcommand(
@@ -64,14 +105,67 @@ fn main() {
                     arg!(-d --due [due] "Due date")
                         .required(false)
                         .action(ArgAction::Set),
+                )
+                .arg(
+                    arg!(-r --recur [recur] "Recurrence rule, or 'none' to stop repeating")
+                        .required(false)
+                        .action(ArgAction::Set),
+                )
+                .arg(
+                    arg!(--parent [parent] "Parent task id, or 'none' to detach")
+                        .required(false)
+                        .action(ArgAction::Set),
+                )
+                .arg(
+                    arg!(--project [project] "Project name, or 'none' to remove it from its project")
+                        .required(false)
+                        .action(ArgAction::Set),
+                )
+                .arg(
+                    arg!(-e --estimate [estimate] "Effort estimate, or 'none' to remove it")
+                        .required(false)
+                        .action(ArgAction::Set),
+                )
+                .arg(
+                    arg!(-f --force "Complete even if subtasks or dependencies are still open")
+                        .required(false)
+                        .action(ArgAction::SetTrue),
                 ),
         )
         .subcommand(
-            Command::new("list").about("Lists all tasks").arg(
-                arg!(-s --status [status] "Task status")
-                    .required(false)
-                    .action(ArgAction::Append),
-            ),
+            Command::new("list")
+                .about("Lists all tasks")
+                .arg(
+                    arg!(-s --status [status] "Task status")
+                        .required(false)
+                        .action(ArgAction::Append),
+                )
+                .arg(
+                    arg!(-f --filter <expr> "Filter expression, e.g. 'status:inprogress and (tag:work or priority:high)'")
+                        .required(false)
+                        .action(ArgAction::Set),
+                )
+                .arg(
+                    arg!(--project <project> "Project name")
+                        .required(false)
+                        .action(ArgAction::Set),
+                )
+                .arg(
+                    arg!(--sort <keys> "Sort keys, e.g. 'due,-priority,description', or 'urgency' (coefficients: TASK_CLI_URGENCY)")
+                        .required(false)
+                        .allow_hyphen_values(true)
+                        .action(ArgAction::Set),
+                )
+                .arg(
+                    arg!(--limit <n> "Maximum number of tasks")
+                        .required(false)
+                        .action(ArgAction::Set),
+                )
+                .arg(
+                    arg!(--offset <n> "Number of tasks to skip")
+                        .required(false)
+                        .action(ArgAction::Set),
+                ),
         )
         .subcommand(
             Command::new("delete")
@@ -83,10 +177,185 @@ fn main() {
                         .action(ArgAction::SetTrue),
                 ),
         )
+        .subcommand(
+            Command::new("ready")
+                .about("Lists open tasks that are not blocked by a dependency")
+                .arg(
+                    arg!(-f --filter <expr> "Filter expression, e.g. 'tag:work'")
+                        .required(false)
+                        .action(ArgAction::Set),
+                ),
+        )
+        .subcommand(
+            Command::new("depend")
+                .about("Makes a task depend on another one")
+                .arg(arg!([id] "Task id").required(true).action(ArgAction::Set))
+                .arg(
+                    arg!(--on <id> "Id of the task that must be done first")
+                        .required(true)
+                        .action(ArgAction::Set),
+                ),
+        )
+        .subcommand(
+            Command::new("undepend")
+                .about("Removes a dependency between two tasks")
+                .arg(arg!([id] "Task id").required(true).action(ArgAction::Set))
+                .arg(
+                    arg!(--on <id> "Id of the task it depends on")
+                        .required(true)
+                        .action(ArgAction::Set),
+                ),
+        )
+        .subcommand(
+            Command::new("project")
+                .about("Manages projects")
+                .subcommand_required(true)
+                .subcommand(
+                    Command::new("add")
+                        .about("Creates a project")
+                        .arg(arg!([name] "Project name").required(true).action(ArgAction::Set)),
+                )
+                .subcommand(
+                    Command::new("list")
+                        .about("Lists projects with their completion stats")
+                        .arg(
+                            arg!(-a --all "Include archived projects")
+                                .required(false)
+                                .action(ArgAction::SetTrue),
+                        ),
+                )
+                .subcommand(
+                    Command::new("archive")
+                        .about("Archives a project")
+                        .arg(arg!([name] "Project name").required(true).action(ArgAction::Set)),
+                )
+                .subcommand(
+                    Command::new("rename")
+                        .about("Renames a project")
+                        .arg(arg!([name] "Project name").required(true).action(ArgAction::Set))
+                        .arg(
+                            arg!([new_name] "New project name")
+                                .required(true)
+                                .action(ArgAction::Set),
+                        ),
+                ),
+        )
+        .subcommand(
+            Command::new("note")
+                .about("Adds a note to a task, or edits one (opens $EDITOR without text)")
+                .arg(arg!([id] "Task id").required(true).action(ArgAction::Set))
+                .arg(arg!([text] "Note text").required(false).action(ArgAction::Set))
+                .arg(
+                    arg!(-e --edit <number> "Number of the note to edit (1 for the oldest)")
+                        .required(false)
+                        .action(ArgAction::Set),
+                ),
+        )
+        .subcommand(
+            Command::new("show")
+                .about("Shows a task with its notes")
+                .arg(arg!([id] "Task id").required(true).action(ArgAction::Set)),
+        )
+        .subcommand(
+            Command::new("start")
+                .about("Starts tracking time on a task (stops the running one)")
+                .arg(arg!([id] "Task id").required(true).action(ArgAction::Set)),
+        )
+        .subcommand(
+            Command::new("stop")
+                .about("Stops tracking time on the running task")
+                .arg(arg!([id] "Task id").required(false).action(ArgAction::Set)),
+        )
+        .subcommand(
+            Command::new("report")
+                .about("Shows activity reports")
+                .subcommand_required(true)
+                .subcommand(
+                    Command::new("time")
+                        .about("Shows the time tracked over a period")
+                        .arg(
+                            arg!(--since <date> "First day of the period")
+                                .required(false)
+                                .action(ArgAction::Set),
+                        )
+                        .arg(
+                            arg!(--until <date> "Last day of the period (included)")
+                                .required(false)
+                                .action(ArgAction::Set),
+                        )
+                        .arg(
+                            arg!(--by <group> "Group by task, tag or project")
+                                .required(false)
+                                .value_parser(["task", "tag", "project"])
+                                .default_value("task")
+                                .action(ArgAction::Set),
+                        ),
+                )
+                .subcommand(
+                    Command::new("estimate")
+                        .about("Compares estimates to tracked time")
+                        .arg(
+                            arg!(-t --tag <tag> "Tag name")
+                                .required(false)
+                                .action(ArgAction::Set),
+                        )
+                        .arg(
+                            arg!(--project <project> "Project name")
+                                .required(false)
+                                .action(ArgAction::Set),
+                        ),
+                )
+                .subcommand(
+                    Command::new("load")
+                        .about("Sums the estimated effort of open tasks")
+                        .arg(
+                            arg!(--by <group> "Group by tag, status or week (of the due date)")
+                                .required(false)
+                                .value_parser(["tag", "status", "week"])
+                                .default_value("week")
+                                .action(ArgAction::Set),
+                        )
+                        .arg(
+                            arg!(-t --tag <tag> "Tag name")
+                                .required(false)
+                                .action(ArgAction::Set),
+                        )
+                        .arg(
+                            arg!(--project <project> "Project name")
+                                .required(false)
+                                .action(ArgAction::Set),
+                        ),
+                ),
+        )
+        .subcommand(Command::new("trash").about("Lists deleted tasks"))
+        .subcommand(
+            Command::new("restore")
+                .about("Restores a deleted task")
+                .arg(arg!([id] "Task id").required(true).action(ArgAction::Set)),
+        )
+        .subcommand(
+            Command::new("purge")
+                .about("Permanently removes deleted tasks")
+                .arg(
+                    arg!(--"older-than" <age> "Only tasks deleted before this age (e.g. 30d, 2w, 12h)")
+                        .required(false)
+                        .action(ArgAction::Set),
+                )
+                .arg(
+                    arg!(-f --force "Purge without confirmation")
+                        .required(false)
+                        .action(ArgAction::SetTrue),
+                ),
+        )
         .subcommand(
             Command::new("complete")
                 .about("Completes a task")
-                .arg(arg!([id] "Task id").required(true).action(ArgAction::Set)),
+                .arg(arg!([id] "Task id").required(true).action(ArgAction::Set))
+                .arg(
+                    arg!(-f --force "Complete even if subtasks or dependencies are still open")
+                        .required(false)
+                        .action(ArgAction::SetTrue),
+                ),
         )
         .subcommand(
             Command::new("search")
@@ -115,17 +384,119 @@ fn main() {
                     arg!(-s --status [status] "Task status")
                         .required(false)
                         .action(ArgAction::Append),
+                )
+                .arg(
+                    arg!(-f --filter <expr> "Filter expression, e.g. 'status:inprogress and (tag:work or priority:high)'")
+                        .required(false)
+                        .action(ArgAction::Set),
+                )
+                .arg(
+                    arg!(--project <project> "Project name")
+                        .required(false)
+                        .action(ArgAction::Set),
+                )
+                .arg(
+                    arg!(--sort <keys> "Sort keys, e.g. 'due,-priority' (default: relevance)")
+                        .required(false)
+                        .allow_hyphen_values(true)
+                        .action(ArgAction::Set),
+                )
+                .arg(
+                    arg!(--limit <n> "Maximum number of tasks")
+                        .required(false)
+                        .action(ArgAction::Set),
+                )
+                .arg(
+                    arg!(--offset <n> "Number of tasks to skip")
+                        .required(false)
+                        .action(ArgAction::Set),
+                ),
+        )
+        .subcommand(
+            Command::new("export")
+                .about("Exports all tasks to JSON or CSV")
+                .arg(
+                    arg!([file] "Output file (stdout if omitted)")
+                        .required(false)
+                        .action(ArgAction::Set),
+                )
+                .arg(
+                    arg!(--type <type> "File format: json or csv (default: from extension)")
+                        .required(false)
+                        .action(ArgAction::Set),
+                ),
+        )
+        .subcommand(
+            Command::new("import")
+                .about("Imports tasks from JSON or CSV")
+                .arg(
+                    arg!([file] "Input file ('-' for stdin)")
+                        .required(true)
+                        .action(ArgAction::Set),
+                )
+                .arg(
+                    arg!(--type <type> "File format: json or csv (default: from extension)")
+                        .required(false)
+                        .action(ArgAction::Set),
+                )
+                .arg(
+                    arg!(--"on-conflict" <policy> "Existing UUIDs: skip, overwrite or newer")
+                        .required(false)
+                        .action(ArgAction::Set),
+                ),
+        )
+        .subcommand(
+            Command::new("history")
+                .about("Shows the change history of a task")
+                .arg(arg!([id] "Task id").required(true).action(ArgAction::Set)),
+        )
+        .subcommand(
+            Command::new("undo").about("Undoes the last changes").arg(
+                arg!(-n --count [count] "Number of changes to undo")
+                    .required(false)
+                    .action(ArgAction::Set),
+            ),
+        )
+        .subcommand(
+            Command::new("redo")
+                .about("Redoes the last undone changes")
+                .arg(
+                    arg!(-n --count [count] "Number of changes to redo")
+                        .required(false)
+                        .action(ArgAction::Set),
                 ),
         )
         .get_matches();
 
+    if let Err(e) = commands::common::configure_timezone(&matches) {
+        eprintln!("{} {}", "Erreur:".red(), e);
+        return;
+    }
+
     match matches.subcommand() {
         Some(("add", sub_m)) => commands::add::handle_add(sub_m),
         Some(("edit", sub_m)) => commands::edit::handle_edit(sub_m),
         Some(("list", sub_m)) => commands::list::handle_list(sub_m),
+        Some(("ready", sub_m)) => commands::ready::handle_ready(sub_m),
+        Some(("depend", sub_m)) => commands::depend::handle_depend(sub_m),
+        Some(("undepend", sub_m)) => commands::undepend::handle_undepend(sub_m),
+        Some(("project", sub_m)) => commands::project::handle_project(sub_m),
+        Some(("note", sub_m)) => commands::note::handle_note(sub_m),
+        Some(("show", sub_m)) => commands::show::handle_show(sub_m),
+        Some(("start", sub_m)) => commands::start::handle_start(sub_m),
+        Some(("stop", sub_m)) => commands::stop::handle_stop(sub_m),
+        Some(("report", sub_m)) => commands::report::handle_report(sub_m),
         Some(("delete", sub_m)) => commands::delete::handle_delete(sub_m),
+        Some(("trash", sub_m)) => commands::trash::handle_trash(sub_m),
+        Some(("restore", sub_m)) => commands::restore::handle_restore(sub_m),
+        Some(("purge", sub_m)) => commands::purge::handle_purge(sub_m),
         Some(("complete", sub_m)) => commands::complete::handle_complete(sub_m),
         Some(("search", sub_m)) => commands::search::handle_search(sub_m),
+        Some(("export", sub_m)) => commands::export::handle_export(sub_m),
+        Some(("import", sub_m)) => commands::import::handle_import(sub_m),
+        Some(("history", sub_m)) => commands::history::handle_history(sub_m),
+        Some(("undo", sub_m)) => commands::undo::handle_undo(sub_m),
+        Some(("redo", sub_m)) => commands::redo::handle_redo(sub_m),
         _ => unreachable!(),
     }
     // Continued program logic goes here...
@@ -0,0 +1,273 @@
+//! Règles de récurrence des tasks.
+//!
+//! | Règle | Prochaine occurrence |
+//! |-------|----------------------|
+//! | `daily` | Le lendemain |
+//! | `weekly:mon,thu` | Le prochain lundi ou jeudi |
+//! | `monthly:15` | Le 15 du mois (le dernier jour si le mois est plus court) |
+//! | `after:3d` | 3 jours après la complétion |
+//!
+//! Les règles calendaires partent de l'échéance de l'occurrence complétée, ou du jour de
+//! complétion s'il est plus tardif : une task complétée en retard ne génère pas
+//! d'occurrence déjà échue. L'heure de l'échéance est conservée.
+
+use crate::dates;
+use chrono::{DateTime, Datelike, Days, Months, NaiveDate, TimeZone, Utc, Weekday};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Règle de récurrence d'une task
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum Recurrence {
+    /// Tous les jours
+    Daily,
+    /// Chaque semaine, les jours indiqués
+    Weekly(Vec<Weekday>),
+    /// Chaque mois, le jour indiqué (1 à 31)
+    Monthly(u32),
+    /// Un nombre de jours après la complétion
+    AfterCompletion(u32),
+}
+
+const RULES: &str = "daily, weekly:mon,thu, monthly:15 ou after:3d";
+
+impl Recurrence {
+    /// Analyse une règle saisie par l'utilisateur (`daily`, `weekly:mon,thu`, `monthly:15`, `after:3d`)
+    pub fn parse(input: &str) -> Result<Recurrence, String> {
+        let lower = input.trim().to_lowercase();
+        let invalid = || {
+            format!(
+                "Récurrence invalide '{}'. Utilisez: {}",
+                input.trim(),
+                RULES
+            )
+        };
+
+        let (kind, value) = match lower.split_once(':') {
+            Some((kind, value)) => (kind.trim(), Some(value.trim())),
+            None => (lower.as_str(), None),
+        };
+
+        match (kind, value) {
+            ("daily", None) => Ok(Recurrence::Daily),
+            ("weekly", Some(days)) => {
+                let mut weekdays = Vec::new();
+                for day in days.split(',').map(str::trim) {
+                    let weekday = dates::parse_weekday(day).ok_or_else(invalid)?;
+                    if !weekdays.contains(&weekday) {
+                        weekdays.push(weekday);
+                    }
+                }
+                weekdays.sort_by_key(|d| d.num_days_from_monday());
+                Ok(Recurrence::Weekly(weekdays))
+            }
+            ("monthly", Some(day)) => match day.parse() {
+                Ok(day @ 1..=31) => Ok(Recurrence::Monthly(day)),
+                _ => Err(invalid()),
+            },
+            ("after", Some(days)) => match days.strip_suffix('d').map(str::parse) {
+                Some(Ok(days @ 1..)) => Ok(Recurrence::AfterCompletion(days)),
+                _ => Err(invalid()),
+            },
+            _ => Err(invalid()),
+        }
+    }
+
+    /// Échéance de l'occurrence suivante, dans le fuseau configuré
+    pub fn next_due(
+        &self,
+        due_date: Option<DateTime<Utc>>,
+        completed_at: DateTime<Utc>,
+    ) -> DateTime<Utc> {
+        self.next_due_in(due_date, completed_at, &dates::timezone())
+    }
+
+    /// Comme [`Recurrence::next_due`], dans le fuseau `tz`
+    pub fn next_due_in<Z: TimeZone>(
+        &self,
+        due_date: Option<DateTime<Utc>>,
+        completed_at: DateTime<Utc>,
+        tz: &Z,
+    ) -> DateTime<Utc> {
+        let completed_day = completed_at.with_timezone(tz).date_naive();
+
+        // Jour de l'échéance et heure, si elle ne tombe pas au début du jour
+        let (due_day, time) = match due_date {
+            Some(due) => {
+                let local = due.with_timezone(tz);
+                let day = local.date_naive();
+                let time = (due != dates::day_start_in(day, tz)).then(|| local.time());
+                (Some(day), time)
+            }
+            None => (None, None),
+        };
+        let from = due_day.map_or(completed_day, |day| day.max(completed_day));
+
+        let day = match self {
+            Recurrence::Daily => from + Days::new(1),
+            Recurrence::Weekly(weekdays) => (1..=7)
+                .map(|n| from + Days::new(n))
+                .find(|day| weekdays.contains(&day.weekday()))
+                .unwrap_or(from + Days::new(7)),
+            Recurrence::Monthly(day) => {
+                let this_month = day_of_month(from, *day);
+                if this_month > from {
+                    this_month
+                } else {
+                    let first = from.with_day(1).unwrap_or(from) + Months::new(1);
+                    day_of_month(first, *day)
+                }
+            }
+            Recurrence::AfterCompletion(days) => completed_day + Days::new(u64::from(*days)),
+        };
+
+        time.and_then(|time| dates::local_to_utc(day.and_time(time), tz))
+            .unwrap_or_else(|| dates::day_start_in(day, tz))
+    }
+}
+
+/// Jour `day` du mois de `date`, ramené au dernier jour du mois si nécessaire
+fn day_of_month(date: NaiveDate, day: u32) -> NaiveDate {
+    (1..=day)
+        .rev()
+        .find_map(|d| date.with_day(d))
+        .unwrap_or(date)
+}
+
+fn weekday_name(weekday: Weekday) -> &'static str {
+    match weekday {
+        Weekday::Mon => "mon",
+        Weekday::Tue => "tue",
+        Weekday::Wed => "wed",
+        Weekday::Thu => "thu",
+        Weekday::Fri => "fri",
+        Weekday::Sat => "sat",
+        Weekday::Sun => "sun",
+    }
+}
+
+impl fmt::Display for Recurrence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Recurrence::Daily => write!(f, "daily"),
+            Recurrence::Weekly(weekdays) => {
+                let days: Vec<&str> = weekdays.iter().map(|d| weekday_name(*d)).collect();
+                write!(f, "weekly:{}", days.join(","))
+            }
+            Recurrence::Monthly(day) => write!(f, "monthly:{}", day),
+            Recurrence::AfterCompletion(days) => write!(f, "after:{}d", days),
+        }
+    }
+}
+
+impl TryFrom<String> for Recurrence {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Recurrence::parse(&value)
+    }
+}
+
+impl From<Recurrence> for String {
+    fn from(recurrence: Recurrence) -> Self {
+        recurrence.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono_tz::Europe::Paris;
+
+    /// Instant à l'heure de Paris
+    fn paris(s: &str) -> DateTime<Utc> {
+        dates::parse_due_at(s, &Paris.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap())
+            .unwrap()
+            .unwrap()
+    }
+
+    fn next(rule: &str, due: Option<&str>, completed: &str) -> DateTime<Utc> {
+        Recurrence::parse(rule)
+            .unwrap()
+            .next_due_in(due.map(paris), paris(completed), &Paris)
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        for rule in ["daily", "weekly:mon,thu", "monthly:31", "after:3d"] {
+            assert_eq!(Recurrence::parse(rule).unwrap().to_string(), rule);
+        }
+        assert_eq!(
+            Recurrence::parse(" Weekly: Friday, mon, fri ").unwrap(),
+            Recurrence::Weekly(vec![Weekday::Mon, Weekday::Fri])
+        );
+        for rule in [
+            "",
+            "hourly",
+            "weekly",
+            "weekly:",
+            "monthly:0",
+            "monthly:32",
+            "after:3",
+            "after:0d",
+        ] {
+            assert!(
+                Recurrence::parse(rule)
+                    .unwrap_err()
+                    .starts_with("Récurrence invalide"),
+                "{}",
+                rule
+            );
+        }
+    }
+
+    #[test]
+    fn test_next_due() {
+        // Complétée à temps : l'heure de l'échéance est conservée
+        assert_eq!(
+            next("daily", Some("2026-11-02 09:00"), "2026-11-02 08:00"),
+            paris("2026-11-03 09:00")
+        );
+        // Complétée en retard : pas d'occurrence déjà échue
+        assert_eq!(
+            next("daily", Some("2026-11-02"), "2026-11-05 18:00"),
+            paris("2026-11-06")
+        );
+        // Lundi 2 novembre -> jeudi 5, puis jeudi -> lundi suivant
+        assert_eq!(
+            next("weekly:mon,thu", Some("2026-11-02"), "2026-11-02 10:00"),
+            paris("2026-11-05")
+        );
+        assert_eq!(
+            next("weekly:mon,thu", Some("2026-11-05"), "2026-11-05 10:00"),
+            paris("2026-11-09")
+        );
+        // Le 31 devient le 30 novembre, puis le 31 décembre
+        assert_eq!(
+            next("monthly:31", Some("2026-10-31"), "2026-10-30 12:00"),
+            paris("2026-11-30")
+        );
+        assert_eq!(
+            next("monthly:31", Some("2026-11-30"), "2026-11-30 12:00"),
+            paris("2026-12-31")
+        );
+        // Sans échéance, à partir de la complétion
+        assert_eq!(
+            next("after:3d", Some("2026-11-02"), "2026-11-04 22:30"),
+            paris("2026-11-07")
+        );
+        assert_eq!(
+            next("monthly:1", None, "2026-11-04 10:00"),
+            paris("2026-12-01")
+        );
+    }
+
+    #[test]
+    fn test_next_due_keeps_local_time_across_dst() {
+        // Passage à l'heure d'hiver le 25 octobre : 09:00 reste 09:00 heure locale
+        let due = next("daily", Some("2026-10-24 09:00"), "2026-10-24 08:00");
+        assert_eq!(due, paris("2026-10-25 09:00"));
+        assert_eq!(due.to_rfc3339(), "2026-10-25T08:00:00+00:00");
+    }
+}
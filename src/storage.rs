@@ -1,9 +1,123 @@
-use crate::task::{Priority, Status, Task};
+use crate::estimate::Estimate;
+use crate::recurrence::Recurrence;
+use crate::task::{Priority, Status, Task, TaskRef};
 use chrono::{DateTime, Utc};
-use rusqlite::{Connection, OptionalExtension, Result as SqlResult, params};
+use rusqlite::{Connection, OptionalExtension, Result as SqlResult, params, params_from_iter};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt;
 use std::path::PathBuf;
 use uuid::Uuid;
 
+mod dependencies;
+mod estimates;
+mod events;
+mod hierarchy;
+mod journal;
+mod migrations;
+mod notes;
+mod projects;
+mod query;
+mod search;
+mod time_tracking;
+
+pub use dependencies::DependencyError;
+pub use estimates::{EffortGroup, EffortLine, EffortReport, EstimateComparison};
+pub use events::{TaskEvent, TaskField};
+pub use hierarchy::{ParentError, Progress, tree_order};
+pub use journal::{Operation, OperationKind};
+pub use notes::Note;
+pub use projects::{Project, ProjectError, ProjectSummary};
+pub use query::{SortField, SortKey, TagMatch, TaskQuery};
+pub use search::{SearchHit, check_search};
+pub use time_tracking::{ReportGroup, TimeEntry, TimeReport, TimeReportLine};
+
+/// Erreurs d'ouverture du stockage
+#[derive(Debug)]
+pub enum StorageError {
+    /// Erreur SQLite
+    Sql(rusqlite::Error),
+    /// La base a été créée par une version plus récente de l'application
+    UnsupportedVersion { found: u32, supported: u32 },
+}
+
+impl fmt::Display for StorageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StorageError::Sql(e) => write!(f, "{}", e),
+            StorageError::UnsupportedVersion { found, supported } => write!(
+                f,
+                "schéma de version {} non supporté (version maximale: {}), mettez à jour task-cli",
+                found, supported
+            ),
+        }
+    }
+}
+
+impl std::error::Error for StorageError {}
+
+impl From<rusqlite::Error> for StorageError {
+    fn from(e: rusqlite::Error) -> Self {
+        StorageError::Sql(e)
+    }
+}
+
+/// Erreurs d'import
+#[derive(Debug)]
+pub enum ImportError {
+    /// Erreur SQLite
+    Sql(rusqlite::Error),
+    /// Les dépendances importées forment un cycle passant par cette task
+    DependencyCycle { id: Uuid, description: String },
+    /// Les tasks parentes importées forment un cycle passant par cette task
+    ParentCycle { id: Uuid, description: String },
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImportError::Sql(e) => write!(f, "{}", e),
+            ImportError::DependencyCycle { id, description } => write!(
+                f,
+                "Dépendance circulaire: la tâche '{}' ({}) dépend, directement ou non, d'elle-même",
+                description, id
+            ),
+            ImportError::ParentCycle { id, description } => write!(
+                f,
+                "Rattachement circulaire: la tâche '{}' ({}) est sa propre parente, directement ou non",
+                description, id
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ImportError {}
+
+impl From<rusqlite::Error> for ImportError {
+    fn from(e: rusqlite::Error) -> Self {
+        ImportError::Sql(e)
+    }
+}
+
+/// Politique d'import pour les tasks dont l'UUID existe déjà
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictPolicy {
+    /// Conserver la task existante
+    Skip,
+    /// Remplacer la task existante
+    Overwrite,
+    /// Garder la version dont `updated_at` est la plus récente
+    Newer,
+}
+
+/// Bilan d'un import
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+pub struct ImportReport {
+    pub added: usize,
+    pub updated: usize,
+    pub skipped: usize,
+}
+
 /// Gestionnaire de stockage des tasks dans SQLite
 pub struct TaskStorage {
     #[allow(dead_code)]
@@ -12,208 +126,260 @@ pub struct TaskStorage {
 }
 
 impl TaskStorage {
-    /// Crée ou ouvre une base de données SQLite
-    pub fn new(db_path: PathBuf) -> SqlResult<Self> {
-        let conn = Connection::open(&db_path)?;
-        let storage = TaskStorage { db_path, conn };
-        storage.init_db()?;
-        Ok(storage)
-    }
-
-    /// Initialise les tables si elles n'existent pas
-    fn init_db(&self) -> SqlResult<()> {
-        self.conn.execute_batch(
-            "CREATE TABLE IF NOT EXISTS tasks (
-                id TEXT PRIMARY KEY,
-                description TEXT NOT NULL,
-                priority TEXT NOT NULL,
-                status TEXT,
-                due_date TEXT NOT NULL,
-                created_at TEXT NOT NULL,
-                updated_at TEXT NOT NULL
-            );
-            CREATE TABLE IF NOT EXISTS task_tags (
-                task_id TEXT NOT NULL,
-                tag TEXT NOT NULL,
-                PRIMARY KEY (task_id, tag),
-                FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE
-            );",
-        )?;
-        Ok(())
+    /// Crée ou ouvre une base de données SQLite et applique les migrations en attente
+    pub fn new(db_path: PathBuf) -> Result<Self, StorageError> {
+        let mut conn = Connection::open(&db_path)?;
+
+        let version = migrations::current_version(&conn)?;
+        let supported = migrations::latest_version();
+        if version > supported {
+            return Err(StorageError::UnsupportedVersion {
+                found: version,
+                supported,
+            });
+        }
+
+        // Nécessaire pour la suppression en cascade des tags
+        conn.pragma_update(None, "foreign_keys", true)?;
+
+        migrations::run(&mut conn)?;
+        Ok(TaskStorage { db_path, conn })
+    }
+
+    /// Retourne la version du schéma de la base de données
+    pub fn schema_version(&self) -> SqlResult<u32> {
+        migrations::current_version(&self.conn)
+    }
+
+    /// Ajoute une nouvelle task et retourne son ID court.
+    /// L'ID court de la task est conservé s'il est renseigné, sinon le prochain disponible est attribué.
+    pub fn add_task(&mut self, task: &Task) -> SqlResult<u32> {
+        let tx = self.conn.unchecked_transaction()?;
+        let batch = self.begin_batch()?;
+        let short_id = self.insert_task(task)?;
+
+        let mut added = task.clone();
+        added.short_id = Some(short_id);
+        self.record_operation(batch, OperationKind::Add, None, Some(&added))?;
+
+        tx.commit()?;
+        Ok(short_id)
+    }
+
+    /// Insère une task et ses tags, sans transaction.
+    /// Sans ID court, le suivant du compteur `short_id` est attribué : un ID court n'est
+    /// jamais réutilisé, même après une purge.
+    fn insert_task(&self, task: &Task) -> SqlResult<u32> {
+        self.insert_task_in(task, self.project_id_for(task)?)
     }
 
-    /// Ajoute une nouvelle task
-    pub fn add_task(&mut self, task: &Task) -> SqlResult<()> {
+    /// Insère une task dans le projet `project_id`, sans résoudre son nom de projet
+    fn insert_task_in(&self, task: &Task, project_id: Option<i64>) -> SqlResult<u32> {
         let priority_str = priority_to_string(&task.priority);
         let status_str = task.status.as_ref().map(status_to_string);
 
         self.conn.execute(
-            "INSERT INTO tasks (id, description, priority, status, due_date, created_at, updated_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            "INSERT INTO tasks (id, description, priority, status, due_date, created_at, updated_at, deleted_at, recurrence, parent_id, project_id, estimate, short_id)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12,
+                     COALESCE(?13, (SELECT value + 1 FROM sequences WHERE name = 'short_id')))",
             params![
                 task.id.to_string(),
                 &task.description,
                 priority_str,
                 status_str,
-                task.due_date.to_rfc3339(),
+                task.due_date.map(|d| d.to_rfc3339()),
                 task.created_at.to_rfc3339(),
                 task.updated_at.to_rfc3339(),
+                task.deleted_at.map(|d| d.to_rfc3339()),
+                task.recurrence.as_ref().map(|r| r.to_string()),
+                task.parent_id.map(|id| id.to_string()),
+                project_id,
+                task.estimate.map(|e| e.to_string()),
+                task.short_id,
             ],
         )?;
+        self.insert_tags(task)?;
+        self.insert_dependencies(task)?;
+
+        let short_id: u32 = self.conn.query_row(
+            "SELECT short_id FROM tasks WHERE id = ?1",
+            params![task.id.to_string()],
+            |row| row.get(0),
+        )?;
+        self.conn.execute(
+            "UPDATE sequences SET value = MAX(value, ?1) WHERE name = 'short_id'",
+            params![short_id],
+        )?;
+        Ok(short_id)
+    }
 
-        // Ajouter les tags si présents
+    /// Insère les tags d'une task
+    fn insert_tags(&self, task: &Task) -> SqlResult<()> {
         if let Some(tags) = &task.tags {
             for tag in tags {
                 self.conn.execute(
-                    "INSERT INTO task_tags (task_id, tag) VALUES (?1, ?2)",
+                    "INSERT OR IGNORE INTO task_tags (task_id, tag) VALUES (?1, ?2)",
                     params![task.id.to_string(), tag],
                 )?;
             }
         }
-
         Ok(())
     }
 
     /// Récupère une task par son ID
     pub fn get_task(&self, id: &Uuid) -> SqlResult<Option<Task>> {
-        let mut stmt = self.conn.prepare(
-            "SELECT id, description, priority, status, due_date, created_at, updated_at
-             FROM tasks WHERE id = ?1",
-        )?;
+        let sql = format!("SELECT {} FROM tasks WHERE id = ?1", TASK_COLUMNS);
+        Ok(self
+            .fetch_tasks(&sql, params![id.to_string()])?
+            .into_iter()
+            .next())
+    }
 
-        let task = stmt
-            .query_row(params![id.to_string()], |row| {
-                Ok((
-                    row.get::<_, String>(0)?,
-                    row.get::<_, String>(1)?,
-                    row.get::<_, String>(2)?,
-                    row.get::<_, Option<String>>(3)?,
-                    row.get::<_, String>(4)?,
-                    row.get::<_, String>(5)?,
-                    row.get::<_, String>(6)?,
-                ))
-            })
-            .optional()?;
-
-        if let Some((
-            id_str,
-            description,
-            priority_str,
-            status_str,
-            due_date_str,
-            created_at_str,
-            updated_at_str,
-        )) = task
-        {
-            let priority = string_to_priority(&priority_str);
-            let status = status_str.as_ref().map(|s| string_to_status(s));
-            let due_date = DateTime::parse_from_rfc3339(&due_date_str)
-                .map(|dt| dt.with_timezone(&Utc))
-                .unwrap_or_else(|_| Utc::now());
-            let created_at = DateTime::parse_from_rfc3339(&created_at_str)
-                .map(|dt| dt.with_timezone(&Utc))
-                .unwrap_or_else(|_| Utc::now());
-            let updated_at = DateTime::parse_from_rfc3339(&updated_at_str)
-                .map(|dt| dt.with_timezone(&Utc))
-                .unwrap_or_else(|_| Utc::now());
-
-            let task_id = Uuid::parse_str(&id_str).unwrap_or_else(|_| Uuid::new_v4());
-
-            // Récupérer les tags
-            let tags = self.get_tags(&task_id)?;
-
-            Ok(Some(Task {
-                id: task_id,
-                description,
-                tags,
-                status,
-                priority,
-                due_date,
-                created_at,
-                updated_at,
-            }))
-        } else {
-            Ok(None)
-        }
+    /// Exécute une requête sélectionnant `TASK_COLUMNS` et charge les tags des tasks
+    fn fetch_tasks<P: rusqlite::Params>(&self, sql: &str, params: P) -> SqlResult<Vec<Task>> {
+        let mut tasks = self
+            .conn
+            .prepare(sql)?
+            .query_map(params, task_from_row)?
+            .collect::<SqlResult<Vec<Task>>>()?;
+
+        self.load_tags(&mut tasks)?;
+        self.load_dependencies(&mut tasks)?;
+        Ok(tasks)
+    }
+
+    /// Résout une référence utilisateur (ID court, UUID ou préfixe d'UUID)
+    /// et retourne les UUID des tasks correspondantes, hors corbeille.
+    pub fn resolve_task_ref(&self, task_ref: &TaskRef) -> SqlResult<Vec<Uuid>> {
+        self.resolve_ref(task_ref, false)
+    }
+
+    /// Résout une référence utilisateur parmi les tasks de la corbeille
+    pub fn resolve_trashed_task_ref(&self, task_ref: &TaskRef) -> SqlResult<Vec<Uuid>> {
+        self.resolve_ref(task_ref, true)
+    }
+
+    fn resolve_ref(&self, task_ref: &TaskRef, trashed: bool) -> SqlResult<Vec<Uuid>> {
+        let ids: Vec<String> = match task_ref {
+            TaskRef::Short(short_id) => {
+                let ids = self
+                    .conn
+                    .prepare(
+                        "SELECT id FROM tasks WHERE short_id = ?1 AND (deleted_at IS NOT NULL) = ?2",
+                    )?
+                    .query_map(params![short_id, trashed], |row| row.get(0))?
+                    .collect::<SqlResult<Vec<String>>>()?;
+                // Un nombre sans ID court correspondant peut être un préfixe d'UUID (`12345678`)
+                if ids.is_empty() {
+                    return self.resolve_ref(&TaskRef::Prefix(short_id.to_string()), trashed);
+                }
+                ids
+            }
+            TaskRef::Full(uuid) => self
+                .conn
+                .prepare("SELECT id FROM tasks WHERE id = ?1 AND (deleted_at IS NOT NULL) = ?2")?
+                .query_map(params![uuid.to_string(), trashed], |row| row.get(0))?
+                .collect::<SqlResult<Vec<String>>>()?,
+            TaskRef::Prefix(prefix) => self
+                .conn
+                .prepare(
+                    "SELECT id FROM tasks WHERE id LIKE ?1 || '%' AND (deleted_at IS NOT NULL) = ?2
+                     ORDER BY short_id",
+                )?
+                .query_map(params![prefix, trashed], |row| row.get(0))?
+                .collect::<SqlResult<Vec<String>>>()?,
+        };
+
+        Ok(ids
+            .iter()
+            .filter_map(|id| Uuid::parse_str(id).ok())
+            .collect())
     }
 
     /// Récupère toutes les tasks
     pub fn get_all_tasks(&self) -> SqlResult<Vec<Task>> {
-        let mut stmt = self.conn.prepare(
-            "SELECT id, description, priority, status, due_date, created_at, updated_at
-             FROM tasks ORDER BY created_at DESC",
-        )?;
+        self.query_tasks(&TaskQuery::new())
+    }
 
-        let tasks = stmt.query_map([], |row| {
-            Ok((
-                row.get::<_, String>(0)?,
-                row.get::<_, String>(1)?,
-                row.get::<_, String>(2)?,
-                row.get::<_, Option<String>>(3)?,
-                row.get::<_, String>(4)?,
-                row.get::<_, String>(5)?,
-                row.get::<_, String>(6)?,
-            ))
-        })?;
-
-        let mut result = Vec::new();
-        for task_row in tasks {
-            let (
-                id_str,
-                description,
-                priority_str,
-                status_str,
-                due_date_str,
-                created_at_str,
-                updated_at_str,
-            ) = task_row?;
-
-            let priority = string_to_priority(&priority_str);
-            let status = status_str.as_ref().map(|s| string_to_status(s));
-            let due_date = DateTime::parse_from_rfc3339(&due_date_str)
-                .map(|dt| dt.with_timezone(&Utc))
-                .unwrap_or_else(|_| Utc::now());
-            let created_at = DateTime::parse_from_rfc3339(&created_at_str)
-                .map(|dt| dt.with_timezone(&Utc))
-                .unwrap_or_else(|_| Utc::now());
-            let updated_at = DateTime::parse_from_rfc3339(&updated_at_str)
-                .map(|dt| dt.with_timezone(&Utc))
-                .unwrap_or_else(|_| Utc::now());
-
-            let task_id = Uuid::parse_str(&id_str).unwrap_or_else(|_| Uuid::new_v4());
-
-            // Récupérer les tags
-            let tags = self.get_tags(&task_id)?;
-
-            result.push(Task {
-                id: task_id,
-                description,
-                tags,
-                status,
-                priority,
-                due_date,
-                created_at,
-                updated_at,
-            });
+    /// Met à jour une task existante
+    pub fn update_task(&mut self, task: &Task) -> SqlResult<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        if let Some(before) = self.get_task(&task.id)? {
+            let batch = self.begin_batch()?;
+            self.rewrite_task(batch, &before, task)?;
         }
+        tx.commit()
+    }
 
-        Ok(result)
+    /// Enregistre une task complétée et arrête le suivi de son temps. Si elle est récurrente,
+    /// l'occurrence suivante est créée dans la même opération (un seul `undo` annule les deux)
+    /// et retournée.
+    pub fn complete_task(&mut self, task: &Task) -> SqlResult<Option<Task>> {
+        let tx = self.conn.unchecked_transaction()?;
+        let Some(before) = self.get_task(&task.id)? else {
+            return Ok(None);
+        };
+
+        let batch = self.begin_batch()?;
+        self.rewrite_task(batch, &before, task)?;
+        self.stop_running(batch, Some(&task.id), task.updated_at)?;
+
+        let next = match task.next_occurrence(task.updated_at) {
+            Some(mut next) => {
+                next.short_id = Some(self.insert_task(&next)?);
+                self.record_operation(batch, OperationKind::Add, None, Some(&next))?;
+                Some(next)
+            }
+            None => None,
+        };
+
+        tx.commit()?;
+        Ok(next)
     }
 
-    /// Met à jour une task existante
-    pub fn update_task(&mut self, task: &Task) -> SqlResult<()> {
+    /// Réécrit une task dans l'opération `batch`, avec son historique par champ.
+    /// Le suivi du temps d'une task complétée ou annulée est arrêté dans la même opération.
+    fn rewrite_task(&self, batch: i64, before: &Task, task: &Task) -> SqlResult<()> {
+        self.write_task(task)?;
+        let after = self.get_task(&task.id)?;
+        if let Some(after) = &after {
+            self.record_changes(before, after)?;
+        }
+        self.record_operation(batch, OperationKind::Update, Some(before), after.as_ref())?;
+        if !task.is_open() {
+            self.stop_running(batch, Some(&task.id), task.updated_at)?;
+        }
+        Ok(())
+    }
+
+    /// Réécrit tous les champs d'une task existante, ses tags et ses dépendances, sans transaction.
+    /// L'ID court n'est jamais modifié.
+    fn write_task(&self, task: &Task) -> SqlResult<()> {
+        self.write_task_in(task, self.project_id_for(task)?)
+    }
+
+    /// Réécrit une task dans le projet `project_id`, sans résoudre son nom de projet
+    fn write_task_in(&self, task: &Task, project_id: Option<i64>) -> SqlResult<()> {
         let priority_str = priority_to_string(&task.priority);
         let status_str = task.status.as_ref().map(status_to_string);
 
         self.conn.execute(
-            "UPDATE tasks SET description = ?1, priority = ?2, status = ?3, due_date = ?4, updated_at = ?5
-             WHERE id = ?6",
+            "UPDATE tasks SET description = ?1, priority = ?2, status = ?3, due_date = ?4,
+                created_at = ?5, updated_at = ?6, deleted_at = ?7, recurrence = ?8,
+                parent_id = ?9, project_id = ?10, estimate = ?11
+             WHERE id = ?12",
             params![
                 &task.description,
                 priority_str,
                 status_str,
-                task.due_date.to_rfc3339(),
+                task.due_date.map(|d| d.to_rfc3339()),
+                task.created_at.to_rfc3339(),
                 task.updated_at.to_rfc3339(),
+                task.deleted_at.map(|d| d.to_rfc3339()),
+                task.recurrence.as_ref().map(|r| r.to_string()),
+                task.parent_id.map(|id| id.to_string()),
+                project_id,
+                task.estimate.map(|e| e.to_string()),
                 task.id.to_string(),
             ],
         )?;
@@ -223,227 +389,301 @@ impl TaskStorage {
             "DELETE FROM task_tags WHERE task_id = ?1",
             params![task.id.to_string()],
         )?;
-        if let Some(tags) = &task.tags {
-            for tag in tags {
-                self.conn.execute(
-                    "INSERT INTO task_tags (task_id, tag) VALUES (?1, ?2)",
-                    params![task.id.to_string(), tag],
-                )?;
+        self.insert_tags(task)?;
+
+        // Mettre à jour les dépendances
+        self.conn.execute(
+            "DELETE FROM task_dependencies WHERE task_id = ?1",
+            params![task.id.to_string()],
+        )?;
+        self.insert_dependencies(task)
+    }
+
+    /// Exporte toutes les tasks, corbeille comprise, lues dans une seule transaction
+    pub fn export_tasks(&self) -> SqlResult<Vec<Task>> {
+        let tx = self.conn.unchecked_transaction()?;
+        let mut tasks = self.get_all_tasks()?;
+        tasks.append(&mut self.get_trashed_tasks()?);
+        tx.commit()?;
+        Ok(tasks)
+    }
+
+    /// Importe des tasks dans une seule transaction.
+    /// Les UUID déjà présents sont traités selon `policy` ; en cas d'erreur, rien n'est importé.
+    /// L'ID court importé est conservé s'il est libre, sinon un nouvel ID est attribué.
+    /// Une fois toutes les tasks écrites, les tasks parentes et les dépendances sont vérifiées
+    /// comme par `check_parent` et `check_dependency` : un cycle annule l'import.
+    pub fn import_tasks(
+        &mut self,
+        tasks: &[Task],
+        policy: ConflictPolicy,
+    ) -> Result<ImportReport, ImportError> {
+        let tx = self.conn.unchecked_transaction()?;
+        let batch = self.begin_batch()?;
+        let mut report = ImportReport::default();
+        let mut written = Vec::new();
+
+        for task in tasks {
+            let existing: Option<String> = self
+                .conn
+                .query_row(
+                    "SELECT updated_at FROM tasks WHERE id = ?1",
+                    params![task.id.to_string()],
+                    |row| row.get(0),
+                )
+                .optional()?;
+
+            match existing {
+                None => {
+                    let short_id_taken = match task.short_id {
+                        Some(short_id) => self.conn.query_row(
+                            "SELECT COUNT(*) > 0 FROM tasks WHERE short_id = ?1",
+                            params![short_id],
+                            |row| row.get(0),
+                        )?,
+                        None => false,
+                    };
+                    let mut task = task.clone();
+                    if short_id_taken {
+                        task.short_id = None;
+                    }
+                    task.short_id = Some(self.insert_task(&task)?);
+                    self.record_operation(batch, OperationKind::Add, None, Some(&task))?;
+                    report.added += 1;
+                    written.push(task);
+                }
+                Some(updated_at_str) => {
+                    let overwrite = match policy {
+                        ConflictPolicy::Skip => false,
+                        ConflictPolicy::Overwrite => true,
+                        ConflictPolicy::Newer => DateTime::parse_from_rfc3339(&updated_at_str)
+                            .map(|dt| task.updated_at > dt.with_timezone(&Utc))
+                            .unwrap_or(true),
+                    };
+                    if overwrite {
+                        let before = self.get_task(&task.id)?;
+                        self.write_task(task)?;
+                        let after = self.get_task(&task.id)?;
+                        self.record_operation(
+                            batch,
+                            OperationKind::Update,
+                            before.as_ref(),
+                            after.as_ref(),
+                        )?;
+                        report.updated += 1;
+                        written.push(task.clone());
+                    } else {
+                        report.skipped += 1;
+                    }
+                }
             }
         }
 
-        Ok(())
+        // Les liens ne sont vérifiés qu'une fois tout le lot écrit : une task peut être
+        // rattachée à une autre, ou dépendre d'une autre, importée après elle
+        for task in &written {
+            if let Some(parent_id) = &task.parent_id
+                && self.descends_from(parent_id, &task.id)?
+            {
+                return Err(ImportError::ParentCycle {
+                    id: task.id,
+                    description: task.description.clone(),
+                });
+            }
+            for depends_on in &task.depends_on {
+                if *depends_on == task.id || self.depends_on_transitively(depends_on, &task.id)? {
+                    return Err(ImportError::DependencyCycle {
+                        id: task.id,
+                        description: task.description.clone(),
+                    });
+                }
+            }
+        }
+
+        tx.commit()?;
+        Ok(report)
     }
 
-    /// Supprime une task
+    /// Place une task dans la corbeille (`deleted_at` = maintenant) et arrête le suivi de son temps
     pub fn delete_task(&mut self, id: &Uuid) -> SqlResult<()> {
-        self.conn
-            .execute("DELETE FROM tasks WHERE id = ?1", params![id.to_string()])?;
-        Ok(())
+        let tx = self.conn.unchecked_transaction()?;
+        if let Some(before) = self.get_task(id)?
+            && before.deleted_at.is_none()
+        {
+            let batch = self.begin_batch()?;
+            let mut after = before.clone();
+            after.deleted_at = Some(Utc::now());
+            self.write_task(&after)?;
+            self.record_operation(batch, OperationKind::Delete, Some(&before), Some(&after))?;
+            self.stop_running(batch, Some(id), Utc::now())?;
+        }
+        tx.commit()
     }
 
-    /// Récupère les tags d'une task
-    fn get_tags(&self, task_id: &Uuid) -> SqlResult<Option<Vec<String>>> {
-        let mut stmt = self
-            .conn
-            .prepare("SELECT tag FROM task_tags WHERE task_id = ?1")?;
-        let tags: Vec<String> = stmt
-            .query_map(params![task_id.to_string()], |row| row.get(0))?
-            .collect::<SqlResult<Vec<String>>>()?;
-
-        Ok(if tags.is_empty() { None } else { Some(tags) })
+    /// Sort une task de la corbeille
+    pub fn restore_task(&mut self, id: &Uuid) -> SqlResult<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        if let Some(before) = self.get_task(id)?
+            && before.deleted_at.is_some()
+        {
+            let batch = self.begin_batch()?;
+            let mut after = before.clone();
+            after.deleted_at = None;
+            self.write_task(&after)?;
+            self.record_operation(batch, OperationKind::Restore, Some(&before), Some(&after))?;
+        }
+        tx.commit()
     }
 
-    /// Récupère les tasks par statut
-    pub fn get_tasks_by_status(&self, status: &Status) -> SqlResult<Vec<Task>> {
-        let status_str = status_to_string(status);
-        let mut stmt = self.conn.prepare(
-            "SELECT id, description, priority, status, due_date, created_at, updated_at
-             FROM tasks WHERE status = ?1 ORDER BY created_at DESC",
-        )?;
+    /// Récupère les tasks de la corbeille, les plus récemment supprimées en premier
+    pub fn get_trashed_tasks(&self) -> SqlResult<Vec<Task>> {
+        let sql = format!(
+            "SELECT {} FROM tasks WHERE deleted_at IS NOT NULL ORDER BY deleted_at DESC",
+            TASK_COLUMNS
+        );
+        self.fetch_tasks(&sql, [])
+    }
 
-        let tasks = stmt.query_map(params![status_str], |row| {
-            Ok((
-                row.get::<_, String>(0)?,
-                row.get::<_, String>(1)?,
-                row.get::<_, String>(2)?,
-                row.get::<_, Option<String>>(3)?,
-                row.get::<_, String>(4)?,
-                row.get::<_, String>(5)?,
-                row.get::<_, String>(6)?,
-            ))
-        })?;
-
-        let mut result = Vec::new();
-        for task_row in tasks {
-            let (
-                id_str,
-                description,
-                priority_str,
-                status_str,
-                due_date_str,
-                created_at_str,
-                updated_at_str,
-            ) = task_row?;
-
-            let priority = string_to_priority(&priority_str);
-            let status = status_str.as_ref().map(|s| string_to_status(s));
-            let due_date = DateTime::parse_from_rfc3339(&due_date_str)
-                .map(|dt| dt.with_timezone(&Utc))
-                .unwrap_or_else(|_| Utc::now());
-            let created_at = DateTime::parse_from_rfc3339(&created_at_str)
-                .map(|dt| dt.with_timezone(&Utc))
-                .unwrap_or_else(|_| Utc::now());
-            let updated_at = DateTime::parse_from_rfc3339(&updated_at_str)
-                .map(|dt| dt.with_timezone(&Utc))
-                .unwrap_or_else(|_| Utc::now());
-
-            let task_id = Uuid::parse_str(&id_str).unwrap_or_else(|_| Uuid::new_v4());
-            let tags = self.get_tags(&task_id)?;
-
-            result.push(Task {
-                id: task_id,
-                description,
-                tags,
-                status,
-                priority,
-                due_date,
-                created_at,
-                updated_at,
-            });
+    /// Supprime définitivement les tasks de la corbeille, avec leurs tags, leur historique
+    /// et leurs opérations. Avec `older_than`, seules les tasks supprimées avant cette date
+    /// sont purgées. Retourne le nombre de tasks purgées.
+    pub fn purge_tasks(&mut self, older_than: Option<DateTime<Utc>>) -> SqlResult<usize> {
+        let tx = self.conn.unchecked_transaction()?;
+        let ids: Vec<String> = match older_than {
+            Some(cutoff) => self
+                .conn
+                .prepare("SELECT id FROM tasks WHERE deleted_at IS NOT NULL AND deleted_at <= ?1")?
+                .query_map(params![cutoff.to_rfc3339()], |row| row.get(0))?
+                .collect::<SqlResult<Vec<String>>>()?,
+            None => self
+                .conn
+                .prepare("SELECT id FROM tasks WHERE deleted_at IS NOT NULL")?
+                .query_map([], |row| row.get(0))?
+                .collect::<SqlResult<Vec<String>>>()?,
+        };
+
+        for id in &ids {
+            self.conn
+                .execute("DELETE FROM task_tags WHERE task_id = ?1", params![id])?;
+            self.conn.execute(
+                "DELETE FROM task_dependencies WHERE task_id = ?1 OR depends_on = ?1",
+                params![id],
+            )?;
+            self.conn
+                .execute("DELETE FROM task_notes WHERE task_id = ?1", params![id])?;
+            self.conn
+                .execute("DELETE FROM time_entries WHERE task_id = ?1", params![id])?;
+            self.conn
+                .execute("DELETE FROM task_events WHERE task_id = ?1", params![id])?;
+            self.conn
+                .execute("DELETE FROM operations WHERE task_id = ?1", params![id])?;
+            self.conn
+                .execute("DELETE FROM tasks WHERE id = ?1", params![id])?;
         }
 
-        Ok(result)
+        tx.commit()?;
+        Ok(ids.len())
     }
 
-    /// Récupère les tasks par priorité
-    pub fn get_tasks_by_priority(&self, priority: &Priority) -> SqlResult<Vec<Task>> {
-        let priority_str = priority_to_string(priority);
-        let mut stmt = self.conn.prepare(
-            "SELECT id, description, priority, status, due_date, created_at, updated_at
-             FROM tasks WHERE priority = ?1 ORDER BY created_at DESC",
-        )?;
+    /// Charge les tags de plusieurs tasks, par lots de `TAG_BATCH_SIZE` tasks
+    /// plutôt qu'une requête par task
+    fn load_tags(&self, tasks: &mut [Task]) -> SqlResult<()> {
+        let mut tags_by_task: HashMap<String, Vec<String>> = HashMap::new();
 
-        let tasks = stmt.query_map(params![priority_str], |row| {
-            Ok((
-                row.get::<_, String>(0)?,
-                row.get::<_, String>(1)?,
-                row.get::<_, String>(2)?,
-                row.get::<_, Option<String>>(3)?,
-                row.get::<_, String>(4)?,
-                row.get::<_, String>(5)?,
-                row.get::<_, String>(6)?,
-            ))
-        })?;
-
-        let mut result = Vec::new();
-        for task_row in tasks {
-            let (
-                id_str,
-                description,
-                priority_str,
-                status_str,
-                due_date_str,
-                created_at_str,
-                updated_at_str,
-            ) = task_row?;
-
-            let priority = string_to_priority(&priority_str);
-            let status = status_str.as_ref().map(|s| string_to_status(s));
-            let due_date = DateTime::parse_from_rfc3339(&due_date_str)
-                .map(|dt| dt.with_timezone(&Utc))
-                .unwrap_or_else(|_| Utc::now());
-            let created_at = DateTime::parse_from_rfc3339(&created_at_str)
-                .map(|dt| dt.with_timezone(&Utc))
-                .unwrap_or_else(|_| Utc::now());
-            let updated_at = DateTime::parse_from_rfc3339(&updated_at_str)
-                .map(|dt| dt.with_timezone(&Utc))
-                .unwrap_or_else(|_| Utc::now());
-
-            let task_id = Uuid::parse_str(&id_str).unwrap_or_else(|_| Uuid::new_v4());
-            let tags = self.get_tags(&task_id)?;
-
-            result.push(Task {
-                id: task_id,
-                description,
-                tags,
-                status,
-                priority,
-                due_date,
-                created_at,
-                updated_at,
-            });
+        for chunk in tasks.chunks(TAG_BATCH_SIZE) {
+            let sql = format!(
+                "SELECT task_id, tag FROM task_tags WHERE task_id IN ({}) ORDER BY rowid",
+                vec!["?"; chunk.len()].join(", ")
+            );
+            let mut stmt = self.conn.prepare(&sql)?;
+            let rows = stmt.query_map(
+                params_from_iter(chunk.iter().map(|task| task.id.to_string())),
+                |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)),
+            )?;
+            for row in rows {
+                let (task_id, tag) = row?;
+                tags_by_task.entry(task_id).or_default().push(tag);
+            }
         }
 
-        Ok(result)
+        for task in tasks.iter_mut() {
+            task.tags = tags_by_task.remove(&task.id.to_string());
+        }
+        Ok(())
     }
 
-    /// Récupère les tasks par tag
-    pub fn get_tasks_by_tag(&self, tag: &str) -> SqlResult<Vec<Task>> {
-        let mut stmt = self.conn.prepare(
-            "SELECT DISTINCT t.id, t.description, t.priority, t.status, t.due_date, t.created_at, t.updated_at
-             FROM tasks t
-             INNER JOIN task_tags tt ON t.id = tt.task_id
-             WHERE tt.tag = ?1
-             ORDER BY t.created_at DESC",
-        )?;
+    /// Récupère les tasks par statut
+    pub fn get_tasks_by_status(&self, status: &Status) -> SqlResult<Vec<Task>> {
+        self.query_tasks(&TaskQuery::new().status(status.clone()))
+    }
 
-        let tasks = stmt.query_map(params![tag], |row| {
-            Ok((
-                row.get::<_, String>(0)?,
-                row.get::<_, String>(1)?,
-                row.get::<_, String>(2)?,
-                row.get::<_, Option<String>>(3)?,
-                row.get::<_, String>(4)?,
-                row.get::<_, String>(5)?,
-                row.get::<_, String>(6)?,
-            ))
-        })?;
-
-        let mut result = Vec::new();
-        for task_row in tasks {
-            let (
-                id_str,
-                description,
-                priority_str,
-                status_str,
-                due_date_str,
-                created_at_str,
-                updated_at_str,
-            ) = task_row?;
-
-            let priority = string_to_priority(&priority_str);
-            let status = status_str.as_ref().map(|s| string_to_status(s));
-            let due_date = DateTime::parse_from_rfc3339(&due_date_str)
-                .map(|dt| dt.with_timezone(&Utc))
-                .unwrap_or_else(|_| Utc::now());
-            let created_at = DateTime::parse_from_rfc3339(&created_at_str)
-                .map(|dt| dt.with_timezone(&Utc))
-                .unwrap_or_else(|_| Utc::now());
-            let updated_at = DateTime::parse_from_rfc3339(&updated_at_str)
-                .map(|dt| dt.with_timezone(&Utc))
-                .unwrap_or_else(|_| Utc::now());
-
-            let task_id = Uuid::parse_str(&id_str).unwrap_or_else(|_| Uuid::new_v4());
-            let tags = self.get_tags(&task_id)?;
-
-            result.push(Task {
-                id: task_id,
-                description,
-                tags,
-                status,
-                priority,
-                due_date,
-                created_at,
-                updated_at,
-            });
-        }
+    /// Récupère les tasks par priorité
+    pub fn get_tasks_by_priority(&self, priority: &Priority) -> SqlResult<Vec<Task>> {
+        self.query_tasks(&TaskQuery::new().priority(priority.clone()))
+    }
 
-        Ok(result)
+    /// Récupère les tasks par tag
+    pub fn get_tasks_by_tag(&self, tag: &str) -> SqlResult<Vec<Task>> {
+        self.query_tasks(&TaskQuery::new().tag(tag))
     }
 }
 
+/// Nombre maximal de tasks par requête de chargement des tags et des dépendances
+const TAG_BATCH_SIZE: usize = 500;
+
+/// Colonnes lues par `task_from_row`, dans l'ordre
+const TASK_COLUMNS: &str = "id, description, priority, status, due_date, created_at, updated_at, \
+     short_id, deleted_at, recurrence, parent_id, \
+     (SELECT name FROM projects WHERE projects.id = project_id), estimate";
+
+/// Nombre de colonnes de `TASK_COLUMNS` : les colonnes supplémentaires d'une requête suivent
+const TASK_COLUMN_COUNT: usize = 13;
+
+/// Construit une task (sans ses tags) à partir d'une ligne sélectionnée avec `TASK_COLUMNS`
+fn task_from_row(row: &rusqlite::Row) -> SqlResult<Task> {
+    let id_str: String = row.get(0)?;
+    let priority_str: String = row.get(2)?;
+    let status_str: Option<String> = row.get(3)?;
+    let due_date_str: Option<String> = row.get(4)?;
+    let created_at_str: String = row.get(5)?;
+    let updated_at_str: String = row.get(6)?;
+    let deleted_at_str: Option<String> = row.get(8)?;
+    let recurrence_str: Option<String> = row.get(9)?;
+    let parent_id_str: Option<String> = row.get(10)?;
+    let estimate_str: Option<String> = row.get(12)?;
+
+    Ok(Task {
+        id: Uuid::parse_str(&id_str).unwrap_or_else(|_| Uuid::new_v4()),
+        short_id: row.get(7)?,
+        description: row.get(1)?,
+        tags: None,
+        depends_on: Vec::new(),
+        status: status_str.as_deref().map(string_to_status),
+        priority: string_to_priority(&priority_str),
+        due_date: due_date_str.as_deref().and_then(parse_optional_date),
+        created_at: parse_optional_date(&created_at_str).unwrap_or_else(Utc::now),
+        updated_at: parse_optional_date(&updated_at_str).unwrap_or_else(Utc::now),
+        deleted_at: deleted_at_str.as_deref().and_then(parse_optional_date),
+        recurrence: recurrence_str
+            .as_deref()
+            .and_then(|r| Recurrence::parse(r).ok()),
+        parent_id: parent_id_str
+            .as_deref()
+            .and_then(|id| Uuid::parse_str(id).ok()),
+        project: row.get(11)?,
+        estimate: estimate_str
+            .as_deref()
+            .and_then(|e| Estimate::parse(e).ok()),
+    })
+}
+
 // Fonctions utilitaires de conversion
+fn parse_optional_date(s: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(s)
+        .map(|dt| dt.with_timezone(&Utc))
+        .ok()
+}
+
 fn priority_to_string(priority: &Priority) -> &'static str {
     match priority {
         Priority::High => "High",
@@ -558,8 +798,88 @@ mod tests {
         storage.add_task(&task).unwrap();
         storage.delete_task(&task_id).unwrap();
 
-        let retrieved = storage.get_task(&task_id).unwrap();
-        assert!(retrieved.is_none());
+        // La task est placée dans la corbeille, hors des listes
+        let retrieved = storage.get_task(&task_id).unwrap().unwrap();
+        assert!(retrieved.deleted_at.is_some());
+        assert!(storage.get_all_tasks().unwrap().is_empty());
+        assert!(
+            storage
+                .resolve_task_ref(&TaskRef::Short(1))
+                .unwrap()
+                .is_empty()
+        );
+        assert_eq!(storage.get_trashed_tasks().unwrap().len(), 1);
+
+        let _ = fs::remove_file(&db_path);
+    }
+
+    #[test]
+    fn test_restore_task() {
+        let (mut storage, db_path) = create_test_db();
+        let task = Task::new("Task to restore", None, None, None, None);
+
+        storage.add_task(&task).unwrap();
+        storage.delete_task(&task.id).unwrap();
+        assert_eq!(
+            storage
+                .resolve_trashed_task_ref(&TaskRef::Short(1))
+                .unwrap(),
+            vec![task.id]
+        );
+
+        storage.restore_task(&task.id).unwrap();
+        let restored = storage.get_task(&task.id).unwrap().unwrap();
+        assert!(restored.deleted_at.is_none());
+        assert_eq!(restored.short_id, Some(1));
+        assert_eq!(storage.get_all_tasks().unwrap().len(), 1);
+        assert!(storage.get_trashed_tasks().unwrap().is_empty());
+
+        // La restauration s'annule comme les autres opérations
+        let undone = storage.undo().unwrap();
+        assert_eq!(undone[0].kind, OperationKind::Restore);
+        assert!(storage.get_all_tasks().unwrap().is_empty());
+
+        let _ = fs::remove_file(&db_path);
+    }
+
+    #[test]
+    fn test_purge_tasks_removes_tags_and_journal() {
+        let (mut storage, db_path) = create_test_db();
+        let old = Task::new("Ancienne", Some(vec!["a".to_string()]), None, None, None);
+        let recent = Task::new("Récente", Some(vec!["b".to_string()]), None, None, None);
+        let kept = Task::new("Conservée", Some(vec!["c".to_string()]), None, None, None);
+
+        for task in [&old, &recent, &kept] {
+            storage.add_task(task).unwrap();
+        }
+        storage.delete_task(&old.id).unwrap();
+        storage.delete_task(&recent.id).unwrap();
+
+        // Vieillir la suppression de la première task
+        let mut aged = storage.get_task(&old.id).unwrap().unwrap();
+        aged.deleted_at = Some(Utc::now() - chrono::Duration::days(40));
+        storage.write_task(&aged).unwrap();
+
+        let cutoff = Utc::now() - chrono::Duration::days(30);
+        assert_eq!(storage.purge_tasks(Some(cutoff)).unwrap(), 1);
+        assert!(storage.get_task(&old.id).unwrap().is_none());
+        assert!(storage.get_task(&recent.id).unwrap().is_some());
+
+        assert_eq!(storage.purge_tasks(None).unwrap(), 1);
+        assert!(storage.get_task(&recent.id).unwrap().is_none());
+        assert_eq!(storage.get_all_tasks().unwrap().len(), 1);
+
+        // Aucun tag ni opération orphelins
+        let orphans: i64 = storage
+            .conn
+            .query_row(
+                "SELECT (SELECT COUNT(*) FROM task_tags WHERE task_id != ?1)
+                      + (SELECT COUNT(*) FROM operations WHERE task_id != ?1)",
+                params![kept.id.to_string()],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(orphans, 0);
 
         let _ = fs::remove_file(&db_path);
     }
@@ -666,4 +986,1552 @@ mod tests {
 
         let _ = fs::remove_file(&db_path);
     }
+
+    fn date(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    /// Date et heure locales (`YYYY-MM-DD HH:MM`), comme les filtres au jour près
+    fn local(s: &str) -> DateTime<Utc> {
+        crate::dates::parse_due(s).unwrap().unwrap()
+    }
+
+    fn descriptions(tasks: &[Task]) -> Vec<&str> {
+        tasks.iter().map(|t| t.description.as_str()).collect()
+    }
+
+    #[test]
+    fn test_query_tasks_filters() {
+        let (mut storage, db_path) = create_test_db();
+        let tasks = [
+            Task::new(
+                "Rapport 100% terminé",
+                Some(vec!["Work".to_string(), "urgent".to_string()]),
+                Some(Status::InProgress),
+                Some(Priority::High),
+                Some(date("2026-11-01T00:00:00Z")),
+            ),
+            Task::new(
+                "Rapport mensuel",
+                Some(vec!["work".to_string()]),
+                Some(Status::NotStarted),
+                Some(Priority::Low),
+                Some(date("2026-11-15T00:00:00Z")),
+            ),
+            Task::new(
+                "Courses",
+                Some(vec!["perso".to_string(), "urgent".to_string()]),
+                Some(Status::Completed),
+                Some(Priority::Medium),
+                Some(date("2026-12-01T00:00:00Z")),
+            ),
+        ];
+        for task in &tasks {
+            storage.add_task(task).unwrap();
+        }
+
+        // Statuts et priorités : ensembles de valeurs
+        let query = TaskQuery::new()
+            .status(Status::InProgress)
+            .status(Status::Completed)
+            .priority(Priority::Medium);
+        assert_eq!(
+            descriptions(&storage.query_tasks(&query).unwrap()),
+            vec!["Courses"]
+        );
+
+        // Tags : au moins un (insensible à la casse) ou tous
+        let any = TaskQuery::new().tag("WORK").tag("perso");
+        assert_eq!(storage.query_tasks(&any).unwrap().len(), 3);
+        let all = TaskQuery::new()
+            .tag("work")
+            .tag("urgent")
+            .tag_match(TagMatch::All);
+        assert_eq!(
+            descriptions(&storage.query_tasks(&all).unwrap()),
+            vec!["Rapport 100% terminé"]
+        );
+
+        // Intervalle d'échéance [after, before[
+        let due = TaskQuery::new()
+            .due_after(date("2026-11-01T00:00:00Z"))
+            .due_before(date("2026-12-01T00:00:00Z"))
+            .sort_by(SortKey::asc(SortField::DueDate));
+        assert_eq!(
+            descriptions(&storage.query_tasks(&due).unwrap()),
+            vec!["Rapport 100% terminé", "Rapport mensuel"]
+        );
+
+        // Texte : insensible à la casse, ponctuation ignorée
+        assert_eq!(
+            storage
+                .query_tasks(&TaskQuery::new().text("RAPPORT"))
+                .unwrap()
+                .len(),
+            2
+        );
+        assert_eq!(
+            descriptions(&storage.query_tasks(&TaskQuery::new().text("100%")).unwrap()),
+            vec!["Rapport 100% terminé"]
+        );
+
+        // La corbeille est exclue
+        storage.delete_task(&tasks[2].id).unwrap();
+        assert!(
+            storage
+                .query_tasks(&TaskQuery::new().text("Courses"))
+                .unwrap()
+                .is_empty()
+        );
+
+        let _ = fs::remove_file(&db_path);
+    }
+
+    #[test]
+    fn test_tags_loaded_in_batches() {
+        let (mut storage, db_path) = create_test_db();
+        let tasks: Vec<Task> = (0..TAG_BATCH_SIZE + 10)
+            .map(|i| {
+                let tags = (i % 3 != 0).then(|| vec![format!("tag{}", i), "commun".to_string()]);
+                Task::new(format!("Task {}", i), tags, None, None, None)
+            })
+            .collect();
+        storage.import_tasks(&tasks, ConflictPolicy::Skip).unwrap();
+
+        let loaded = storage.get_all_tasks().unwrap();
+        assert_eq!(loaded.len(), tasks.len());
+        for task in &tasks {
+            let found = loaded.iter().find(|t| t.id == task.id).unwrap();
+            assert_eq!(found.tags, task.tags);
+        }
+
+        let _ = fs::remove_file(&db_path);
+    }
+
+    #[test]
+    fn test_filters_use_indexes() {
+        let (storage, db_path) = create_test_db();
+        let plan = |sql: &str| -> String {
+            let mut stmt = storage
+                .conn
+                .prepare(&format!("EXPLAIN QUERY PLAN {}", sql))
+                .unwrap();
+            stmt.query_map([], |row| row.get::<_, String>(3))
+                .unwrap()
+                .collect::<SqlResult<Vec<String>>>()
+                .unwrap()
+                .join("\n")
+        };
+
+        assert!(
+            plan("SELECT task_id FROM task_tags WHERE tag COLLATE NOCASE IN ('work')")
+                .contains("idx_task_tags_tag")
+        );
+        assert!(
+            plan("SELECT id FROM tasks WHERE status = 'Completed'").contains("idx_tasks_status")
+        );
+        assert!(
+            plan("SELECT id FROM tasks WHERE due_date < '2026-11-01'")
+                .contains("idx_tasks_due_date")
+        );
+
+        let _ = fs::remove_file(&db_path);
+    }
+
+    #[test]
+    fn test_optional_due_date() {
+        let (mut storage, db_path) = create_test_db();
+        let undated = Task::new("Sans échéance", None, None, None, None);
+        let dated = Task::new(
+            "Avec échéance",
+            None,
+            None,
+            None,
+            Some(local("2026-11-02 14:00")),
+        );
+        storage.add_task(&undated).unwrap();
+        storage.add_task(&dated).unwrap();
+
+        let retrieved = storage.get_task(&undated.id).unwrap().unwrap();
+        assert_eq!(retrieved.due_date, None);
+
+        // Les tasks sans échéance sont triées en dernier, dans les deux sens
+        for key in [
+            SortKey::asc(SortField::DueDate),
+            SortKey::desc(SortField::DueDate),
+        ] {
+            let tasks = storage.query_tasks(&TaskQuery::new().sort_by(key)).unwrap();
+            assert_eq!(descriptions(&tasks), vec!["Avec échéance", "Sans échéance"]);
+        }
+
+        let matching = |expr: &str| -> Vec<String> {
+            let query = TaskQuery::new().filter(crate::filter::parse(expr).unwrap());
+            storage
+                .query_tasks(&query)
+                .unwrap()
+                .into_iter()
+                .map(|t| t.description)
+                .collect()
+        };
+        assert_eq!(matching("due:none"), vec!["Sans échéance"]);
+        assert_eq!(matching("due!=none"), vec!["Avec échéance"]);
+        assert!(matching("due<2026-12-01").contains(&"Avec échéance".to_string()));
+        assert!(!matching("due<2026-12-01").contains(&"Sans échéance".to_string()));
+        assert_eq!(matching("due!=2026-11-02"), vec!["Sans échéance"]);
+        assert_eq!(matching("not due<2026-12-01"), vec!["Sans échéance"]);
+
+        // Effacer l'échéance
+        let mut task = storage.get_task(&dated.id).unwrap().unwrap();
+        task.set_due_date(None);
+        storage.update_task(&task).unwrap();
+        assert_eq!(storage.get_task(&dated.id).unwrap().unwrap().due_date, None);
+
+        let _ = fs::remove_file(&db_path);
+    }
+
+    #[test]
+    fn test_query_tasks_with_filter_expression() {
+        let (mut storage, db_path) = create_test_db();
+        let tasks = [
+            Task::new(
+                "Déployer l'API",
+                Some(vec!["work".to_string()]),
+                Some(Status::InProgress),
+                Some(Priority::Low),
+                Some(local("2026-10-31 23:00")),
+            ),
+            Task::new(
+                "Revue de sécurité",
+                None,
+                Some(Status::InProgress),
+                Some(Priority::High),
+                Some(local("2026-11-01 08:00")),
+            ),
+            Task::new(
+                "Courses",
+                Some(vec!["perso".to_string()]),
+                Some(Status::NotStarted),
+                Some(Priority::High),
+                Some(local("2026-10-20")),
+            ),
+            Task::new("Sans statut", None, None, Some(Priority::Low), None),
+        ];
+        for task in &tasks {
+            storage.add_task(task).unwrap();
+        }
+        let matching = |expr: &str| -> Vec<String> {
+            let query = TaskQuery::new()
+                .filter(crate::filter::parse(expr).unwrap())
+                .sort_by(SortKey::asc(SortField::ShortId));
+            storage
+                .query_tasks(&query)
+                .unwrap()
+                .into_iter()
+                .map(|t| t.description)
+                .collect()
+        };
+
+        assert_eq!(
+            matching("status:inprogress and (tag:work or priority:high) and due<2026-11-01"),
+            vec!["Déployer l'API"]
+        );
+        assert_eq!(
+            matching("due:2026-11-01 or due<=2026-10-20"),
+            vec!["Revue de sécurité", "Courses"]
+        );
+        assert_eq!(matching("due>2026-10-31"), vec!["Revue de sécurité"]);
+        assert_eq!(
+            matching("priority>low tag!=perso"),
+            vec!["Revue de sécurité"]
+        );
+        // Une task sans statut est NotStarted
+        assert_eq!(
+            matching("not status:notstarted"),
+            vec!["Déployer l'API", "Revue de sécurité"]
+        );
+        assert_eq!(
+            matching("status:notstarted"),
+            vec!["Courses", "Sans statut"]
+        );
+        let not_started = storage
+            .query_tasks(
+                &TaskQuery::new()
+                    .status(Status::NotStarted)
+                    .sort_by(SortKey::asc(SortField::ShortId)),
+            )
+            .unwrap();
+        assert_eq!(descriptions(&not_started), vec!["Courses", "Sans statut"]);
+        assert_eq!(
+            matching("not status:completed"),
+            matching("status!=completed")
+        );
+        assert_eq!(matching("not status:completed").len(), 4);
+        assert_eq!(matching("text:securite or tag:PERSO").len(), 2);
+
+        let _ = fs::remove_file(&db_path);
+    }
+
+    #[test]
+    fn test_search_tasks_full_text() {
+        let (mut storage, db_path) = create_test_db();
+        let mut tasks = [
+            Task::new("Revue de code du module API", None, None, None, None),
+            Task::new(
+                "Écrire la documentation de l'API REST",
+                None,
+                None,
+                None,
+                None,
+            ),
+            Task::new("Code review: API API API", None, None, None, None),
+            Task::new("Préparer la réunion", None, None, None, None),
+        ];
+        for task in &tasks {
+            storage.add_task(task).unwrap();
+        }
+        let search = |storage: &TaskStorage, text: &str| -> Vec<String> {
+            storage
+                .search_tasks(&TaskQuery::new().text(text))
+                .unwrap()
+                .into_iter()
+                .map(|hit| hit.task.description)
+                .collect()
+        };
+
+        // Phrase, préfixe et accents
+        assert_eq!(
+            search(&storage, "\"revue de code\""),
+            vec!["Revue de code du module API"]
+        );
+        assert_eq!(
+            search(&storage, "docu*"),
+            vec!["Écrire la documentation de l'API REST"]
+        );
+        assert_eq!(search(&storage, "reunion"), vec!["Préparer la réunion"]);
+
+        // Opérateurs
+        assert_eq!(search(&storage, "code NOT review").len(), 1);
+        assert_eq!(search(&storage, "réunion OR REST").len(), 2);
+        assert_eq!(search(&storage, "api AND (revue OR review)").len(), 2);
+
+        // Classement bm25 : la description la plus dense en premier
+        let hits = storage.search_tasks(&TaskQuery::new().text("api")).unwrap();
+        assert_eq!(hits.len(), 3);
+        assert_eq!(hits[0].task.description, "Code review: API API API");
+        assert!(hits.windows(2).all(|w| w[0].score <= w[1].score));
+
+        // Passages trouvés
+        let hit = &storage
+            .search_tasks(&TaskQuery::new().text("revue module"))
+            .unwrap()[0];
+        let matched: Vec<&str> = hit
+            .highlights
+            .iter()
+            .map(|r| &hit.task.description[r.clone()])
+            .collect();
+        assert_eq!(matched, vec!["Revue", "module"]);
+
+        // L'index suit les modifications et les purges
+        tasks[3].set_description("Préparer le comité");
+        storage.update_task(&tasks[3]).unwrap();
+        assert!(search(&storage, "réunion").is_empty());
+        assert_eq!(search(&storage, "comite").len(), 1);
+
+        storage.delete_task(&tasks[3].id).unwrap();
+        assert!(search(&storage, "comite").is_empty());
+        storage.purge_tasks(None).unwrap();
+        let indexed: i64 = storage
+            .conn
+            .query_row("SELECT COUNT(*) FROM tasks_fts", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(indexed, 3);
+
+        let _ = fs::remove_file(&db_path);
+    }
+
+    #[test]
+    fn test_query_tasks_sort_and_pagination() {
+        let (mut storage, db_path) = create_test_db();
+        for (description, priority) in [
+            ("b", Priority::Medium),
+            ("C", Priority::High),
+            ("a", Priority::Low),
+            ("d", Priority::High),
+        ] {
+            let task = Task::new(description, None, None, Some(priority), None);
+            storage.add_task(&task).unwrap();
+        }
+
+        // Priorité dans l'ordre logique, puis description sans tenir compte de la casse
+        let query = TaskQuery::new()
+            .sort_by(SortKey::desc(SortField::Priority))
+            .sort_by(SortKey::asc(SortField::Description));
+        assert_eq!(
+            descriptions(&storage.query_tasks(&query).unwrap()),
+            vec!["C", "d", "b", "a"]
+        );
+
+        let page = query.clone().limit(2).offset(1);
+        assert_eq!(
+            descriptions(&storage.query_tasks(&page).unwrap()),
+            vec!["d", "b"]
+        );
+        let rest = query.offset(3);
+        assert_eq!(
+            descriptions(&storage.query_tasks(&rest).unwrap()),
+            vec!["a"]
+        );
+
+        let _ = fs::remove_file(&db_path);
+    }
+
+    #[test]
+    fn test_parse_sort_keys() {
+        assert_eq!(
+            SortKey::parse_list("due, -Priority,+description").unwrap(),
+            vec![
+                SortKey::asc(SortField::DueDate),
+                SortKey::desc(SortField::Priority),
+                SortKey::asc(SortField::Description),
+            ]
+        );
+        assert_eq!(
+            SortKey::parse_list("-id,created,updated_at,status").unwrap(),
+            vec![
+                SortKey::desc(SortField::ShortId),
+                SortKey::asc(SortField::CreatedAt),
+                SortKey::asc(SortField::UpdatedAt),
+                SortKey::asc(SortField::Status),
+            ]
+        );
+        for input in ["", " , ", "-", "due,size", "--due"] {
+            assert!(SortKey::parse_list(input).is_err(), "{}", input);
+        }
+        assert!(
+            SortKey::parse_list("due,size")
+                .unwrap_err()
+                .starts_with("Critère de tri invalide 'size'")
+        );
+    }
+
+    #[test]
+    fn test_short_ids_are_sequential() {
+        let (mut storage, db_path) = create_test_db();
+
+        let task1 = Task::new("Task 1", None, None, None, None);
+        let task2 = Task::new("Task 2", None, None, None, None);
+
+        assert_eq!(storage.add_task(&task1).unwrap(), 1);
+        assert_eq!(storage.add_task(&task2).unwrap(), 2);
+
+        // L'ID court reste stable après suppression d'une autre task
+        storage.delete_task(&task1.id).unwrap();
+        let retrieved = storage.get_task(&task2.id).unwrap().unwrap();
+        assert_eq!(retrieved.short_id, Some(2));
+
+        let task3 = Task::new("Task 3", None, None, None, None);
+        assert_eq!(storage.add_task(&task3).unwrap(), 3);
+
+        // Ni l'annulation d'un ajout ni la purge ne libèrent un ID court
+        storage.undo().unwrap();
+        let task4 = Task::new("Task 4", None, None, None, None);
+        assert_eq!(storage.add_task(&task4).unwrap(), 4);
+        storage.delete_task(&task4.id).unwrap();
+        storage.purge_tasks(None).unwrap();
+        let task5 = Task::new("Task 5", None, None, None, None);
+        assert_eq!(storage.add_task(&task5).unwrap(), 5);
+
+        let _ = fs::remove_file(&db_path);
+    }
+
+    #[test]
+    fn test_resolve_task_ref() {
+        let (mut storage, db_path) = create_test_db();
+
+        let task = Task::new("Task", None, None, None, None);
+        let short_id = storage.add_task(&task).unwrap();
+        let uuid_str = task.id.to_string();
+
+        let by_short = storage.resolve_task_ref(&TaskRef::Short(short_id)).unwrap();
+        assert_eq!(by_short, vec![task.id]);
+
+        let by_full = storage.resolve_task_ref(&TaskRef::Full(task.id)).unwrap();
+        assert_eq!(by_full, vec![task.id]);
+
+        let prefix = TaskRef::parse(&uuid_str[..8]).unwrap();
+        let by_prefix = storage.resolve_task_ref(&prefix).unwrap();
+        assert_eq!(by_prefix, vec![task.id]);
+
+        let missing = storage.resolve_task_ref(&TaskRef::Short(999)).unwrap();
+        assert!(missing.is_empty());
+
+        // Un préfixe uniquement numérique est résolu s'il ne correspond à aucun ID court
+        let mut digits = Task::new("Digits", None, None, None, None);
+        digits.id = Uuid::parse_str("12345678-5ff1-4f8d-a0c3-b29a7e506742").unwrap();
+        storage.add_task(&digits).unwrap();
+        for prefix in ["12345678", "123456", "12345678-5ff1"] {
+            let task_ref = TaskRef::parse(prefix).unwrap();
+            assert_eq!(
+                storage.resolve_task_ref(&task_ref).unwrap(),
+                vec![digits.id],
+                "{}",
+                prefix
+            );
+        }
+        // L'ID court reste prioritaire
+        assert_eq!(
+            storage.resolve_task_ref(&TaskRef::Short(1)).unwrap(),
+            vec![task.id]
+        );
+
+        let _ = fs::remove_file(&db_path);
+    }
+
+    #[test]
+    fn test_task_ref_parse() {
+        assert_eq!(TaskRef::parse("42"), Some(TaskRef::Short(42)));
+        // Zéro initial ou nombre trop grand : préfixe d'UUID
+        assert_eq!(
+            TaskRef::parse("0042"),
+            Some(TaskRef::Prefix("0042".to_string()))
+        );
+        assert_eq!(
+            TaskRef::parse("98765432109"),
+            Some(TaskRef::Prefix("98765432109".to_string()))
+        );
+        assert_eq!(
+            TaskRef::parse("671BC1"),
+            Some(TaskRef::Prefix("671bc1".to_string()))
+        );
+        let uuid = Uuid::new_v4();
+        assert_eq!(TaskRef::parse(&uuid.to_string()), Some(TaskRef::Full(uuid)));
+        assert_eq!(TaskRef::parse("not-a-uuid"), None);
+        assert_eq!(TaskRef::parse(""), None);
+    }
+
+    fn create_v0_1_0_db() -> PathBuf {
+        let db_path = PathBuf::from(format!("/tmp/test_tasks_{}.db", uuid::Uuid::new_v4()));
+        let conn = Connection::open(&db_path).unwrap();
+        conn.execute_batch(include_str!("../tests/fixtures/tasks_v0.1.0.sql"))
+            .unwrap();
+        db_path
+    }
+
+    #[test]
+    fn test_new_database_is_at_latest_version() {
+        let (storage, db_path) = create_test_db();
+        assert_eq!(
+            storage.schema_version().unwrap(),
+            migrations::latest_version()
+        );
+
+        let _ = fs::remove_file(&db_path);
+    }
+
+    #[test]
+    fn test_upgrade_from_v0_1_0() {
+        let db_path = create_v0_1_0_db();
+
+        let storage = TaskStorage::new(db_path.clone()).unwrap();
+        assert_eq!(
+            storage.schema_version().unwrap(),
+            migrations::latest_version()
+        );
+
+        // Les données existantes sont conservées
+        let tasks = storage.get_all_tasks().unwrap();
+        assert_eq!(tasks.len(), 3);
+        let api = tasks
+            .iter()
+            .find(|t| t.description == "Implémenter API REST")
+            .unwrap();
+        assert_eq!(api.priority, Priority::High);
+        assert_eq!(api.status, Some(Status::InProgress));
+        let mut tags = api.tags.clone().unwrap();
+        tags.sort();
+        assert_eq!(tags, vec!["backend".to_string(), "work".to_string()]);
+        assert!(api.due_date.is_some());
+
+        // La reconstruction de `tasks` (v8) conserve l'index plein texte et ses triggers
+        let hits = storage.search_tasks(&TaskQuery::new().text("api")).unwrap();
+        assert_eq!(hits.len(), 1);
+        let foreign_keys: bool = storage
+            .conn
+            .query_row("PRAGMA foreign_keys", [], |row| row.get(0))
+            .unwrap();
+        assert!(foreign_keys);
+
+        // Les tags orphelins sont nettoyés
+        assert!(storage.get_tasks_by_tag("supprimée").unwrap().is_empty());
+        let tag_rows: i64 = storage
+            .conn
+            .query_row("SELECT COUNT(*) FROM task_tags", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(tag_rows, 4);
+
+        // Les IDs courts sont attribués par ordre de création
+        let first = storage.resolve_task_ref(&TaskRef::Short(1)).unwrap();
+        assert_eq!(
+            storage.get_task(&first[0]).unwrap().unwrap().description,
+            "Apprendre Rust"
+        );
+        drop(storage);
+
+        // Une seconde ouverture ne rejoue pas les migrations
+        let storage = TaskStorage::new(db_path.clone()).unwrap();
+        assert_eq!(storage.get_all_tasks().unwrap().len(), 3);
+
+        let _ = fs::remove_file(&db_path);
+    }
+
+    #[test]
+    fn test_refuses_newer_schema_version() {
+        let (storage, db_path) = create_test_db();
+        let newer = migrations::latest_version() + 1;
+        storage
+            .conn
+            .pragma_update(None, "user_version", newer)
+            .unwrap();
+        drop(storage);
+
+        match TaskStorage::new(db_path.clone()) {
+            Err(StorageError::UnsupportedVersion { found, .. }) => assert_eq!(found, newer),
+            other => panic!("erreur attendue, obtenu: {:?}", other.map(|_| ())),
+        }
+
+        let _ = fs::remove_file(&db_path);
+    }
+
+    #[test]
+    fn test_failed_migration_is_rolled_back() {
+        let db_path = create_v0_1_0_db();
+        let mut conn = Connection::open(&db_path).unwrap();
+
+        fn broken(tx: &rusqlite::Transaction) -> SqlResult<()> {
+            tx.execute_batch("CREATE TABLE partial (id TEXT); INVALID SQL;")
+        }
+        let broken_migrations = [
+            migrations::Migration {
+                version: 1,
+                up: |_| Ok(()),
+            },
+            migrations::Migration {
+                version: 2,
+                up: broken,
+            },
+        ];
+
+        // La migration invalide ne laisse ni table partielle ni version incrémentée
+        assert!(migrations::apply(&mut conn, &broken_migrations).is_err());
+        assert_eq!(migrations::current_version(&conn).unwrap(), 1);
+        let partial: bool = conn
+            .query_row(
+                "SELECT COUNT(*) > 0 FROM sqlite_master WHERE name = 'partial'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert!(!partial);
+
+        let _ = fs::remove_file(&db_path);
+    }
+
+    #[test]
+    fn test_import_tasks_conflict_policies() {
+        let (mut storage, db_path) = create_test_db();
+
+        let mut existing = Task::new("Original", None, None, None, None);
+        storage.add_task(&existing).unwrap();
+
+        // Version importée plus récente de la même task
+        let mut newer = existing.clone();
+        newer.set_description("Plus récente");
+        let fresh = Task::new(
+            "Nouvelle",
+            Some(vec!["import".to_string()]),
+            None,
+            None,
+            None,
+        );
+
+        let report = storage
+            .import_tasks(&[newer.clone(), fresh.clone()], ConflictPolicy::Skip)
+            .unwrap();
+        assert_eq!(
+            report,
+            ImportReport {
+                added: 1,
+                updated: 0,
+                skipped: 1
+            }
+        );
+        let stored = storage.get_task(&existing.id).unwrap().unwrap();
+        assert_eq!(stored.description, "Original");
+
+        let report = storage
+            .import_tasks(std::slice::from_ref(&newer), ConflictPolicy::Newer)
+            .unwrap();
+        assert_eq!(report.updated, 1);
+        let stored = storage.get_task(&existing.id).unwrap().unwrap();
+        assert_eq!(stored.description, "Plus récente");
+        assert_eq!(stored.short_id, Some(1));
+
+        // Une version plus ancienne n'écrase pas avec `Newer`, mais écrase avec `Overwrite`
+        existing.updated_at = newer.updated_at - chrono::Duration::hours(1);
+        let report = storage
+            .import_tasks(std::slice::from_ref(&existing), ConflictPolicy::Newer)
+            .unwrap();
+        assert_eq!(report.skipped, 1);
+        let report = storage
+            .import_tasks(std::slice::from_ref(&existing), ConflictPolicy::Overwrite)
+            .unwrap();
+        assert_eq!(report.updated, 1);
+        let stored = storage.get_task(&existing.id).unwrap().unwrap();
+        assert_eq!(stored.description, "Original");
+
+        let _ = fs::remove_file(&db_path);
+    }
+
+    #[test]
+    fn test_import_tasks_keeps_free_short_ids() {
+        let (mut storage, db_path) = create_test_db();
+        let local = Task::new("Locale", None, None, None, None);
+        storage.add_task(&local).unwrap();
+
+        let mut clashing = Task::new("Conflit d'ID court", None, None, None, None);
+        clashing.short_id = Some(1);
+        let mut free = Task::new("ID court libre", None, None, None, None);
+        free.short_id = Some(10);
+
+        storage
+            .import_tasks(&[clashing.clone(), free.clone()], ConflictPolicy::Skip)
+            .unwrap();
+        assert_eq!(
+            storage.get_task(&free.id).unwrap().unwrap().short_id,
+            Some(10)
+        );
+        assert_eq!(
+            storage.get_task(&clashing.id).unwrap().unwrap().short_id,
+            Some(2)
+        );
+
+        let _ = fs::remove_file(&db_path);
+    }
+
+    #[test]
+    fn test_import_tasks_is_atomic() {
+        let (mut storage, db_path) = create_test_db();
+        let task = Task::new("Valide", None, None, None, None);
+        let mut duplicate = Task::new("Doublon", None, None, None, None);
+        duplicate.short_id = Some(5);
+        let mut other = duplicate.clone();
+        other.id = Uuid::new_v4();
+
+        // Deux UUID différents avec le même ID court : le second est renuméroté
+        storage
+            .import_tasks(&[task.clone(), duplicate, other], ConflictPolicy::Skip)
+            .unwrap();
+        assert_eq!(storage.get_all_tasks().unwrap().len(), 3);
+
+        // Une erreur SQL annule l'import entier
+        storage
+            .conn
+            .execute_batch(
+                "CREATE TRIGGER fail BEFORE INSERT ON tasks
+                 WHEN NEW.description = 'Erreur' BEGIN SELECT RAISE(ABORT, 'échec'); END;",
+            )
+            .unwrap();
+        let ok = Task::new("Avant l'erreur", None, None, None, None);
+        let failing = Task::new("Erreur", None, None, None, None);
+        assert!(
+            storage
+                .import_tasks(&[ok.clone(), failing], ConflictPolicy::Skip)
+                .is_err()
+        );
+        assert!(storage.get_task(&ok.id).unwrap().is_none());
+
+        let _ = fs::remove_file(&db_path);
+    }
+
+    #[test]
+    fn test_import_tasks_rejects_cycles() {
+        let (mut storage, db_path) = create_test_db();
+        let mut x = Task::new("X", None, None, None, None);
+        let mut y = Task::new("Y", None, None, None, None);
+        x.depends_on = vec![y.id];
+        y.depends_on = vec![x.id];
+
+        // Un cycle entre deux tasks du même fichier annule l'import
+        let error = storage
+            .import_tasks(&[x.clone(), y.clone()], ConflictPolicy::Skip)
+            .unwrap_err();
+        assert!(matches!(error, ImportError::DependencyCycle { .. }));
+        assert!(error.to_string().contains("Dépendance circulaire"));
+        assert!(storage.get_all_tasks().unwrap().is_empty());
+
+        // Le cycle formé en remplaçant une task existante est refusé aussi
+        let mut existing = y.clone();
+        existing.depends_on.clear();
+        storage.add_task(&existing).unwrap();
+        assert!(matches!(
+            storage.import_tasks(&[x.clone(), y.clone()], ConflictPolicy::Overwrite),
+            Err(ImportError::DependencyCycle { .. })
+        ));
+        assert!(storage.get_task(&x.id).unwrap().is_none());
+        assert!(
+            storage
+                .get_task(&y.id)
+                .unwrap()
+                .unwrap()
+                .depends_on
+                .is_empty()
+        );
+
+        // Même refus pour des tasks parentes l'une de l'autre
+        let mut child = Task::new("Enfant", None, None, None, None);
+        let mut parent = Task::new("Parent", None, None, None, None);
+        child.parent_id = Some(parent.id);
+        parent.parent_id = Some(child.id);
+        assert!(matches!(
+            storage.import_tasks(&[child.clone(), parent.clone()], ConflictPolicy::Skip),
+            Err(ImportError::ParentCycle { .. })
+        ));
+        assert!(storage.get_task(&child.id).unwrap().is_none());
+        parent.parent_id = None;
+        storage
+            .import_tasks(&[child.clone(), parent.clone()], ConflictPolicy::Skip)
+            .unwrap();
+        assert_eq!(
+            storage.get_task(&child.id).unwrap().unwrap().parent_id,
+            Some(parent.id)
+        );
+
+        // Sans cycle, l'import est accepté
+        assert_eq!(
+            storage
+                .import_tasks(std::slice::from_ref(&x), ConflictPolicy::Skip)
+                .unwrap()
+                .added,
+            1
+        );
+
+        let _ = fs::remove_file(&db_path);
+    }
+
+    #[test]
+    fn test_undo_redo_add_update_delete() {
+        let (mut storage, db_path) = create_test_db();
+
+        let mut task = Task::new("Original", Some(vec!["a".to_string()]), None, None, None);
+        storage.add_task(&task).unwrap();
+        task.set_description("Modifiée");
+        task.tags = Some(vec!["b".to_string()]);
+        storage.update_task(&task).unwrap();
+        storage.delete_task(&task.id).unwrap();
+        assert!(
+            storage
+                .get_task(&task.id)
+                .unwrap()
+                .unwrap()
+                .deleted_at
+                .is_some()
+        );
+
+        // Annuler la suppression restaure la task avec son ID court
+        let undone = storage.undo().unwrap();
+        assert_eq!(undone.len(), 1);
+        assert_eq!(undone[0].kind, OperationKind::Delete);
+        let restored = storage.get_task(&task.id).unwrap().unwrap();
+        assert!(restored.deleted_at.is_none());
+        assert_eq!(restored.description, "Modifiée");
+        assert_eq!(restored.short_id, Some(1));
+
+        // Annuler la modification restaure la description et les tags
+        storage.undo().unwrap();
+        let restored = storage.get_task(&task.id).unwrap().unwrap();
+        assert_eq!(restored.description, "Original");
+        assert_eq!(restored.tags, Some(vec!["a".to_string()]));
+
+        // L'historique suit les champs rétablis
+        let events = storage.get_task_events(&task.id).unwrap();
+        let last = events.last().unwrap();
+        assert_eq!(last.field, TaskField::Tags);
+        assert_eq!(last.old_value.as_deref(), Some("b"));
+        assert_eq!(last.new_value.as_deref(), Some("a"));
+
+        // Annuler l'ajout place la task dans la corbeille, puis il n'y a plus rien à annuler
+        storage.undo().unwrap();
+        assert!(storage.get_all_tasks().unwrap().is_empty());
+        assert!(
+            storage
+                .get_task(&task.id)
+                .unwrap()
+                .unwrap()
+                .deleted_at
+                .is_some()
+        );
+        assert!(storage.undo().unwrap().is_empty());
+
+        // Rejouer dans l'ordre chronologique
+        assert_eq!(storage.redo().unwrap()[0].kind, OperationKind::Add);
+        assert!(
+            storage
+                .get_task(&task.id)
+                .unwrap()
+                .unwrap()
+                .deleted_at
+                .is_none()
+        );
+        assert_eq!(storage.redo().unwrap()[0].kind, OperationKind::Update);
+        let replayed = storage.get_task(&task.id).unwrap().unwrap();
+        assert_eq!(replayed.description, "Modifiée");
+        assert_eq!(replayed.short_id, Some(1));
+
+        // Une nouvelle modification vide la pile de redo
+        let other = Task::new("Autre", None, None, None, None);
+        storage.add_task(&other).unwrap();
+        assert!(storage.redo().unwrap().is_empty());
+        assert!(storage.get_task(&task.id).unwrap().is_some());
+
+        let _ = fs::remove_file(&db_path);
+    }
+
+    #[test]
+    fn test_undo_import_as_single_batch() {
+        let (mut storage, db_path) = create_test_db();
+        let tasks = vec![
+            Task::new("Import 1", None, None, None, None),
+            Task::new("Import 2", None, None, None, None),
+        ];
+        storage.import_tasks(&tasks, ConflictPolicy::Skip).unwrap();
+
+        let undone = storage.undo().unwrap();
+        assert_eq!(undone.len(), 2);
+        assert!(storage.get_all_tasks().unwrap().is_empty());
+
+        let _ = fs::remove_file(&db_path);
+    }
+
+    #[test]
+    fn test_complete_recurring_task_creates_next_occurrence() {
+        let (mut storage, db_path) = create_test_db();
+        let mut task = Task::new(
+            "Réunion",
+            Some(vec!["travail".to_string()]),
+            None,
+            Some(Priority::High),
+            Some(local("2026-11-02 09:00")),
+        );
+        task.recurrence = Some(Recurrence::parse("weekly:mon,thu").unwrap());
+        storage.add_task(&task).unwrap();
+
+        task.set_status(Some(Status::Completed));
+        let next = storage.complete_task(&task).unwrap().unwrap();
+        assert_eq!(next.short_id, Some(2));
+        assert_eq!(next.description, "Réunion");
+        assert_eq!(next.tags, Some(vec!["travail".to_string()]));
+        assert_eq!(next.status, None);
+        assert_eq!(next.recurrence, task.recurrence);
+        assert!(next.due_date.unwrap() > task.due_date.unwrap());
+
+        let stored = storage.get_task(&next.id).unwrap().unwrap();
+        assert_eq!(stored.recurrence, task.recurrence);
+        assert_eq!(
+            storage.get_task(&task.id).unwrap().unwrap().status,
+            Some(Status::Completed)
+        );
+
+        // Une seule annulation retire l'occurrence et rouvre la task
+        let undone = storage.undo().unwrap();
+        assert_eq!(undone.len(), 2);
+        assert!(
+            storage
+                .get_task(&next.id)
+                .unwrap()
+                .unwrap()
+                .deleted_at
+                .is_some()
+        );
+        assert_eq!(storage.get_task(&task.id).unwrap().unwrap().status, None);
+
+        // Sans récurrence, aucune occurrence n'est créée
+        let mut single = Task::new("Unique", None, None, None, None);
+        storage.add_task(&single).unwrap();
+        single.set_status(Some(Status::Completed));
+        assert!(storage.complete_task(&single).unwrap().is_none());
+        assert_eq!(storage.get_all_tasks().unwrap().len(), 2);
+
+        let _ = fs::remove_file(&db_path);
+    }
+
+    #[test]
+    fn test_subtasks_and_parent_checks() {
+        let (mut storage, db_path) = create_test_db();
+        let projet = Task::new("Projet", None, None, None, None);
+        let mut etape = Task::new("Étape", None, Some(Status::Completed), None, None);
+        etape.parent_id = Some(projet.id);
+        let mut detail = Task::new("Détail", None, None, None, None);
+        detail.parent_id = Some(etape.id);
+        let mut annulee = Task::new("Annulée", None, Some(Status::Canceled), None, None);
+        annulee.parent_id = Some(projet.id);
+        for task in [&projet, &etape, &detail, &annulee] {
+            storage.add_task(task).unwrap();
+        }
+
+        assert_eq!(
+            storage.get_task(&detail.id).unwrap().unwrap().parent_id,
+            Some(etape.id)
+        );
+        let subtasks = storage.get_subtasks(&projet.id).unwrap();
+        assert_eq!(descriptions(&subtasks), vec!["Étape", "Annulée"]);
+
+        // Les sous-tâches annulées ne comptent pas dans l'avancement
+        let progress = storage.subtask_progress().unwrap();
+        assert_eq!(progress[&projet.id], Progress { done: 1, total: 1 });
+        assert_eq!(progress[&etape.id], Progress { done: 0, total: 1 });
+        assert_eq!(progress[&etape.id].to_string(), "0/1");
+        assert!(!progress.contains_key(&detail.id));
+
+        // Une tâche ne peut pas être rattachée à elle-même ni à ses descendantes
+        assert!(matches!(
+            storage.check_parent(&projet.id, &projet.id),
+            Err(ParentError::Cycle)
+        ));
+        assert!(matches!(
+            storage.check_parent(&projet.id, &detail.id),
+            Err(ParentError::Cycle)
+        ));
+        assert!(storage.check_parent(&detail.id, &projet.id).is_ok());
+        assert!(storage.check_parent(&annulee.id, &detail.id).is_ok());
+
+        // Une parente dans la corbeille est refusée et ses sous-tâches ne sont plus comptées
+        storage.delete_task(&etape.id).unwrap();
+        assert!(matches!(
+            storage.check_parent(&detail.id, &etape.id),
+            Err(ParentError::NotFound(_))
+        ));
+        let progress = storage.subtask_progress().unwrap();
+        assert_eq!(progress[&projet.id], Progress { done: 0, total: 0 });
+
+        let _ = fs::remove_file(&db_path);
+    }
+
+    #[test]
+    fn test_dependencies_and_ready_tasks() {
+        let (mut storage, db_path) = create_test_db();
+        let cartons = Task::new("Cartons", None, None, None, None);
+        let mut emballer = Task::new("Emballer", None, None, None, None);
+        emballer.add_dependency(cartons.id);
+        let mut camion = Task::new("Camion", None, None, None, None);
+        camion.add_dependency(emballer.id);
+        let fini = Task::new("Fini", None, Some(Status::Completed), None, None);
+        for task in [&cartons, &emballer, &camion, &fini] {
+            storage.add_task(task).unwrap();
+        }
+
+        assert_eq!(
+            storage.get_task(&camion.id).unwrap().unwrap().depends_on,
+            vec![emballer.id]
+        );
+        let hits = storage
+            .search_tasks(&TaskQuery::new().text("camion"))
+            .unwrap();
+        assert_eq!(hits[0].task.depends_on, vec![emballer.id]);
+
+        // Les cycles, directs ou transitifs, sont refusés
+        for (task, on) in [
+            (&cartons, &cartons),
+            (&cartons, &emballer),
+            (&cartons, &camion),
+        ] {
+            assert!(matches!(
+                storage.check_dependency(&task.id, &on.id),
+                Err(DependencyError::Cycle)
+            ));
+        }
+        assert!(storage.check_dependency(&camion.id, &cartons.id).is_ok());
+
+        let ready = |storage: &TaskStorage| -> Vec<String> {
+            let query = TaskQuery::new()
+                .ready()
+                .sort_by(SortKey::asc(SortField::ShortId));
+            storage
+                .query_tasks(&query)
+                .unwrap()
+                .into_iter()
+                .map(|t| t.description)
+                .collect()
+        };
+        assert_eq!(ready(&storage), vec!["Cartons"]);
+        assert_eq!(
+            descriptions(&storage.get_blockers(&emballer.id).unwrap()),
+            vec!["Cartons"]
+        );
+        let blockers = storage.open_blockers().unwrap();
+        assert_eq!(blockers[&emballer.id], vec![1]);
+        assert_eq!(blockers[&camion.id], vec![2]);
+        assert!(!blockers.contains_key(&cartons.id));
+
+        // Une dépendance complétée ne bloque plus
+        let mut done = cartons.clone();
+        done.set_status(Some(Status::Completed));
+        storage.update_task(&done).unwrap();
+        assert_eq!(ready(&storage), vec!["Emballer"]);
+        assert!(storage.get_blockers(&emballer.id).unwrap().is_empty());
+
+        // Retirer une dépendance est annulable comme toute modification
+        let mut camion = storage.get_task(&camion.id).unwrap().unwrap();
+        assert!(camion.remove_dependency(&emballer.id));
+        assert!(!camion.remove_dependency(&emballer.id));
+        storage.update_task(&camion).unwrap();
+        assert_eq!(ready(&storage), vec!["Emballer", "Camion"]);
+        storage.undo().unwrap();
+        assert_eq!(
+            storage.get_task(&camion.id).unwrap().unwrap().depends_on,
+            vec![emballer.id]
+        );
+        assert_eq!(storage.open_blockers().unwrap()[&camion.id], vec![2]);
+
+        // Une tâche terminée malgré ses dépendances n'est plus signalée comme bloquée
+        let mut forced = storage.get_task(&camion.id).unwrap().unwrap();
+        forced.set_status(Some(Status::Completed));
+        storage.update_task(&forced).unwrap();
+        assert!(!storage.open_blockers().unwrap().contains_key(&camion.id));
+        storage.undo().unwrap();
+
+        // La purge d'une dépendance supprime les liens vers elle
+        storage.delete_task(&emballer.id).unwrap();
+        assert_eq!(ready(&storage), vec!["Camion"]);
+        storage.purge_tasks(None).unwrap();
+        assert!(
+            storage
+                .get_task(&camion.id)
+                .unwrap()
+                .unwrap()
+                .depends_on
+                .is_empty()
+        );
+
+        let _ = fs::remove_file(&db_path);
+    }
+
+    #[test]
+    fn test_projects_and_project_filter() {
+        let (mut storage, db_path) = create_test_db();
+        storage.add_project("Maison").unwrap();
+        assert!(matches!(
+            storage.add_project("maison"),
+            Err(ProjectError::AlreadyExists(_))
+        ));
+        assert!(matches!(
+            storage.add_project("  "),
+            Err(ProjectError::InvalidName)
+        ));
+
+        let mut peindre = Task::new("Peindre", None, Some(Status::Completed), None, None);
+        peindre.project = Some("maison".to_string());
+        let mut ranger = Task::new("Ranger", None, None, None, None);
+        ranger.project = Some("Maison".to_string());
+        let mut annulee = Task::new("Annulée", None, Some(Status::Canceled), None, None);
+        annulee.project = Some("Maison".to_string());
+        let autre = Task::new("Autre", None, None, None, None);
+        for task in [&peindre, &ranger, &annulee, &autre] {
+            storage.add_task(task).unwrap();
+        }
+
+        let query = TaskQuery::new()
+            .project("MAISON")
+            .sort_by(SortKey::asc(SortField::ShortId));
+        assert_eq!(
+            descriptions(&storage.query_tasks(&query).unwrap()),
+            vec!["Peindre", "Ranger", "Annulée"]
+        );
+
+        // Les tâches annulées ne comptent pas dans l'avancement
+        let projects = storage.list_projects(false).unwrap();
+        assert_eq!(projects.len(), 1);
+        assert_eq!(projects[0].progress, Progress { done: 1, total: 2 });
+
+        // Le renommage est suivi par les tâches du projet
+        storage.rename_project("maison", "Appartement").unwrap();
+        assert_eq!(
+            storage.get_task(&ranger.id).unwrap().unwrap().project,
+            Some("Appartement".to_string())
+        );
+        assert!(storage.query_tasks(&query).unwrap().is_empty());
+
+        // Un projet archivé n'est listé qu'à la demande
+        storage.add_project("Bureau").unwrap();
+        assert!(matches!(
+            storage.rename_project("Bureau", "appartement"),
+            Err(ProjectError::AlreadyExists(_))
+        ));
+        let archived = storage.archive_project("Appartement").unwrap();
+        assert!(archived.is_archived());
+        let names = |projects: Vec<ProjectSummary>| -> Vec<String> {
+            projects.into_iter().map(|p| p.project.name).collect()
+        };
+        assert_eq!(names(storage.list_projects(false).unwrap()), vec!["Bureau"]);
+        assert_eq!(
+            names(storage.list_projects(true).unwrap()),
+            vec!["Appartement", "Bureau"]
+        );
+        assert!(matches!(
+            storage.archive_project("Inconnu"),
+            Err(ProjectError::NotFound(_))
+        ));
+
+        // Un projet inconnu est créé à l'écriture (import)
+        let mut importee = Task::new("Importée", None, None, None, None);
+        importee.project = Some("Jardin".to_string());
+        storage.add_task(&importee).unwrap();
+        assert!(storage.get_project("jardin").unwrap().is_some());
+
+        let _ = fs::remove_file(&db_path);
+    }
+
+    #[test]
+    fn test_undo_redo_follow_renamed_project() {
+        let (mut storage, db_path) = create_test_db();
+        storage.add_project("work").unwrap();
+        let mut task = Task::new("Rapport", None, None, None, None);
+        storage.add_task(&task).unwrap();
+
+        task.project = Some("work".to_string());
+        storage.update_task(&task).unwrap();
+        storage.rename_project("work", "job").unwrap();
+
+        storage.undo().unwrap();
+        assert_eq!(storage.get_task(&task.id).unwrap().unwrap().project, None);
+
+        // Le projet est retrouvé par son id : l'ancien nom n'est pas recréé
+        storage.redo().unwrap();
+        assert_eq!(
+            storage.get_task(&task.id).unwrap().unwrap().project,
+            Some("job".to_string())
+        );
+        assert!(storage.get_project("work").unwrap().is_none());
+
+        storage.rename_project("job", "emploi").unwrap();
+        task.project = None;
+        storage.update_task(&task).unwrap();
+        storage.undo().unwrap();
+        assert_eq!(
+            storage.get_task(&task.id).unwrap().unwrap().project,
+            Some("emploi".to_string())
+        );
+        assert_eq!(storage.list_projects(true).unwrap().len(), 1);
+
+        let _ = fs::remove_file(&db_path);
+    }
+
+    #[test]
+    fn test_notes_are_searchable() {
+        let (mut storage, db_path) = create_test_db();
+        let task = Task::new("Appeler le plombier", None, None, None, None);
+        let other = Task::new("Acheter du pain", None, None, None, None);
+        storage.add_task(&task).unwrap();
+        storage.add_task(&other).unwrap();
+
+        let first = storage
+            .add_note(&task.id, "Fuite sous l'évier\nDevis demandé")
+            .unwrap();
+        storage.add_note(&task.id, "Rappeler lundi").unwrap();
+        let notes = storage.get_notes(&task.id).unwrap();
+        assert_eq!(notes.len(), 2);
+        assert_eq!(notes[0], first);
+        assert!(storage.get_notes(&other.id).unwrap().is_empty());
+
+        let search = |storage: &TaskStorage, text: &str| -> Vec<SearchHit> {
+            storage.search_tasks(&TaskQuery::new().text(text)).unwrap()
+        };
+        let hits = search(&storage, "evier");
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].task.id, task.id);
+        assert!(hits[0].highlights.is_empty());
+        let excerpt = hits[0].note_excerpt.as_deref().unwrap();
+        assert_eq!(&excerpt[hits[0].note_highlights[0].clone()], "évier");
+
+        // L'index suit la modification des notes et de la description
+        let updated = storage
+            .update_note(first.id, "Fuite réparée")
+            .unwrap()
+            .unwrap();
+        assert_eq!(updated.text, "Fuite réparée");
+        assert!(updated.updated_at >= updated.created_at);
+        assert!(search(&storage, "evier").is_empty());
+        let mut renamed = task.clone();
+        renamed.description = "Appeler l'artisan".to_string();
+        storage.update_task(&renamed).unwrap();
+        assert_eq!(search(&storage, "reparee").len(), 1);
+        let hits = search(&storage, "artisan");
+        assert_eq!(hits.len(), 1);
+        assert!(hits[0].note_excerpt.is_none());
+
+        // La purge supprime les notes
+        storage.delete_task(&task.id).unwrap();
+        storage.purge_tasks(None).unwrap();
+        assert!(storage.get_notes(&task.id).unwrap().is_empty());
+        assert!(search(&storage, "lundi").is_empty());
+
+        let _ = fs::remove_file(&db_path);
+    }
+
+    #[test]
+    fn test_time_tracking_and_report() {
+        let (mut storage, db_path) = create_test_db();
+        let mut first = Task::new(
+            "Rédiger",
+            Some(vec!["doc".to_string(), "urgent".to_string()]),
+            None,
+            None,
+            None,
+        );
+        first.set_project(Some("Site".to_string()));
+        let second = Task::new("Relire", None, None, None, None);
+        storage.add_task(&first).unwrap();
+        storage.add_task(&second).unwrap();
+
+        // Démarrer passe la tâche en cours ; une seule tâche est suivie à la fois
+        assert!(storage.start_task(&first).unwrap().is_none());
+        assert_eq!(
+            storage.get_task(&first.id).unwrap().unwrap().status,
+            Some(Status::InProgress)
+        );
+        assert!(storage.start_task(&first).unwrap().is_none());
+        let stopped = storage.start_task(&second).unwrap().unwrap();
+        assert_eq!(stopped.task_id, first.id);
+        assert!(!stopped.is_running());
+        let running = storage.running_entry().unwrap().unwrap();
+        assert_eq!(running.task_id, second.id);
+
+        // Compléter la tâche suivie arrête le suivi
+        let second = storage.get_task(&second.id).unwrap().unwrap();
+        storage.complete_task(&second).unwrap();
+        assert!(storage.running_entry().unwrap().is_none());
+        assert!(storage.stop_task().unwrap().is_none());
+        assert_eq!(storage.get_time_entries(&second.id).unwrap().len(), 1);
+
+        // L'arrêt est annulé avec la complétion
+        storage.undo().unwrap();
+        assert_eq!(
+            storage.running_entry().unwrap().map(|e| e.task_id),
+            Some(second.id)
+        );
+        storage.redo().unwrap();
+        assert!(storage.running_entry().unwrap().is_none());
+
+        // Intervalles à dates fixes pour le rapport
+        storage
+            .conn
+            .execute("DELETE FROM time_entries", [])
+            .unwrap();
+        let at = |s: &str| DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc);
+        for (task, start, end) in [
+            (
+                &first,
+                "2024-03-01T09:00:00+00:00",
+                "2024-03-01T11:00:00+00:00",
+            ),
+            (
+                &first,
+                "2024-03-01T23:30:00+00:00",
+                "2024-03-02T00:30:00+00:00",
+            ),
+            (
+                &second,
+                "2024-03-02T10:00:00+00:00",
+                "2024-03-02T10:45:00+00:00",
+            ),
+        ] {
+            storage
+                .conn
+                .execute(
+                    "INSERT INTO time_entries (task_id, started_at, ended_at) VALUES (?1, ?2, ?3)",
+                    params![task.id.to_string(), start, end],
+                )
+                .unwrap();
+        }
+        let now = at("2024-03-05T00:00:00+00:00");
+        let first = storage.get_task(&first.id).unwrap().unwrap();
+
+        let report = storage
+            .time_report(None, None, ReportGroup::Task, now)
+            .unwrap();
+        assert_eq!(report.total_seconds, 3 * 3600 + 45 * 60);
+        assert_eq!(
+            report.lines[0].label.as_deref(),
+            Some(format!("[{}] Rédiger", first.short_id.unwrap()).as_str())
+        );
+        assert_eq!(report.lines[0].seconds, 3 * 3600);
+        assert_eq!(
+            storage.tracked_time(now).unwrap()[&second.id],
+            chrono::Duration::minutes(45)
+        );
+
+        // Les intervalles à cheval sur les bornes sont tronqués
+        let since = Some(at("2024-03-02T00:00:00+00:00"));
+        let until = Some(at("2024-03-03T00:00:00+00:00"));
+        let report = storage
+            .time_report(since, until, ReportGroup::Tag, now)
+            .unwrap();
+        assert_eq!(report.total_seconds, 30 * 60 + 45 * 60);
+        let lines: Vec<(Option<&str>, i64)> = report
+            .lines
+            .iter()
+            .map(|line| (line.label.as_deref(), line.seconds))
+            .collect();
+        assert_eq!(
+            lines,
+            vec![(Some("doc"), 1800), (Some("urgent"), 1800), (None, 2700)]
+        );
+
+        let report = storage
+            .time_report(since, until, ReportGroup::Project, now)
+            .unwrap();
+        assert_eq!(report.lines[0].label.as_deref(), Some("Site"));
+        assert_eq!(report.lines[1].label, None);
+
+        // La purge supprime les intervalles
+        storage.delete_task(&first.id).unwrap();
+        storage.purge_tasks(None).unwrap();
+        assert!(storage.get_time_entries(&first.id).unwrap().is_empty());
+
+        let _ = fs::remove_file(&db_path);
+    }
+
+    #[test]
+    fn test_estimates_and_effort_report() {
+        let (mut storage, db_path) = create_test_db();
+        let at = |s: &str| DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc);
+        let tags = |tags: &[&str]| Some(tags.iter().map(|t| t.to_string()).collect());
+
+        let mut write = Task::new("Rédiger", tags(&["doc"]), None, None, None);
+        write.estimate = Some(Estimate::Minutes(90));
+        write.due_date = Some(at("2026-10-21T12:00:00+00:00"));
+        let mut review = Task::new("Relire", tags(&["doc", "qa"]), None, None, None);
+        review.estimate = Some(Estimate::Points(3));
+        review.due_date = Some(at("2026-10-24T12:00:00+00:00"));
+        let test = Task::new("Tester", tags(&["qa"]), None, None, None);
+        let mut done = Task::new("Publier", None, Some(Status::Completed), None, None);
+        done.estimate = Some(Estimate::Minutes(30));
+        for task in [&write, &review, &test, &done] {
+            storage.add_task(task).unwrap();
+        }
+
+        // L'estimation est persistée et suivie dans l'historique
+        let stored = storage.get_task(&write.id).unwrap().unwrap();
+        assert_eq!(stored.estimate, Some(Estimate::Minutes(90)));
+        let mut updated = stored.clone();
+        updated.set_estimate(Some(Estimate::Minutes(120)));
+        storage.update_task(&updated).unwrap();
+        let events = storage.get_task_events(&write.id).unwrap();
+        assert_eq!(events[0].field, TaskField::Estimate);
+        assert_eq!(events[0].old_value.as_deref(), Some("1h30"));
+        assert_eq!(events[0].new_value.as_deref(), Some("2h"));
+
+        // Comparaison au temps passé, tâches fermées comprises
+        storage
+            .conn
+            .execute(
+                "INSERT INTO time_entries (task_id, started_at, ended_at) VALUES (?1, ?2, ?3)",
+                params![
+                    write.id.to_string(),
+                    "2026-10-20T09:00:00+00:00",
+                    "2026-10-20T11:30:00+00:00"
+                ],
+            )
+            .unwrap();
+        let now = at("2026-10-22T00:00:00+00:00");
+        let comparison = storage
+            .estimate_comparison(
+                &TaskQuery::new().sort_by(SortKey::asc(SortField::ShortId)),
+                now,
+            )
+            .unwrap();
+        let ids: Vec<Uuid> = comparison.iter().map(|c| c.task.id).collect();
+        assert_eq!(ids, vec![write.id, review.id, done.id]);
+        assert_eq!(comparison[0].tracked_seconds, 150 * 60);
+        assert_eq!(comparison[0].variance_seconds(), Some(30 * 60));
+        assert_eq!(comparison[1].variance_seconds(), None);
+        assert_eq!(comparison[2].variance_seconds(), Some(-30 * 60));
+
+        // Charge des tâches à faire : une ligne par tag, le total sans doublon
+        let report = storage
+            .effort_report(&TaskQuery::new(), EffortGroup::Tag)
+            .unwrap();
+        let lines: Vec<(Option<&str>, usize, usize, u64, u64)> = report
+            .lines
+            .iter()
+            .map(|l| {
+                (
+                    l.label.as_deref(),
+                    l.tasks,
+                    l.unestimated,
+                    l.minutes,
+                    l.points,
+                )
+            })
+            .collect();
+        assert_eq!(
+            lines,
+            vec![(Some("doc"), 2, 0, 120, 3), (Some("qa"), 2, 1, 0, 3)]
+        );
+        assert_eq!(
+            (
+                report.total.tasks,
+                report.total.minutes,
+                report.total.points
+            ),
+            (3, 120, 3)
+        );
+
+        let report = storage
+            .effort_report(&TaskQuery::new(), EffortGroup::Week)
+            .unwrap();
+        let weeks: Vec<Option<&str>> = report.lines.iter().map(|l| l.label.as_deref()).collect();
+        assert_eq!(weeks, vec![Some("2026-10-19"), None]);
+        assert_eq!(report.lines[0].tasks, 2);
+
+        let report = storage
+            .effort_report(&TaskQuery::new().tag("qa"), EffortGroup::Status)
+            .unwrap();
+        assert_eq!(report.lines.len(), 1);
+        assert_eq!(report.lines[0].label.as_deref(), Some("NotStarted"));
+        assert_eq!(report.total.tasks, 2);
+
+        let _ = fs::remove_file(&db_path);
+    }
+
+    #[test]
+    fn test_update_task_records_field_events() {
+        let (mut storage, db_path) = create_test_db();
+
+        let mut task = Task::new("Task", None, None, None, None);
+        storage.add_task(&task).unwrap();
+        assert!(storage.get_task_events(&task.id).unwrap().is_empty());
+
+        task.set_status(Some(Status::InProgress));
+        task.set_priority(Priority::High);
+        storage.update_task(&task).unwrap();
+
+        // Une mise à jour sans changement n'enregistre rien
+        storage.update_task(&task).unwrap();
+
+        task.add_tag("work");
+        storage.update_task(&task).unwrap();
+
+        let events = storage.get_task_events(&task.id).unwrap();
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[0].field, TaskField::Priority);
+        assert_eq!(events[0].old_value.as_deref(), Some("Medium"));
+        assert_eq!(events[0].new_value.as_deref(), Some("High"));
+        assert_eq!(events[1].field, TaskField::Status);
+        assert_eq!(events[1].old_value, None);
+        assert_eq!(events[1].new_value.as_deref(), Some("InProgress"));
+        assert_eq!(events[2].field, TaskField::Tags);
+        assert_eq!(events[2].new_value.as_deref(), Some("work"));
+
+        let _ = fs::remove_file(&db_path);
+    }
 }
@@ -0,0 +1,166 @@
+use super::{TAG_BATCH_SIZE, TASK_COLUMNS, TaskStorage};
+use crate::task::Task;
+use rusqlite::{Result as SqlResult, params, params_from_iter};
+use std::collections::HashMap;
+use std::fmt;
+use uuid::Uuid;
+
+/// Condition SQL : `blocker` (alias de `tasks`) est une dépendance ouverte, hors corbeille
+pub(super) const OPEN_BLOCKER: &str = "blocker.deleted_at IS NULL
+    AND blocker.status IS NOT 'Completed' AND blocker.status IS NOT 'Canceled'";
+
+/// Erreurs d'ajout d'une dépendance entre deux tasks
+#[derive(Debug)]
+pub enum DependencyError {
+    /// Erreur SQLite
+    Sql(rusqlite::Error),
+    /// La dépendance n'existe pas ou est dans la corbeille
+    NotFound(Uuid),
+    /// La dépendance est la task elle-même ou dépend déjà, directement ou non, de la task
+    Cycle,
+}
+
+impl fmt::Display for DependencyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DependencyError::Sql(e) => write!(f, "{}", e),
+            DependencyError::NotFound(id) => write!(f, "Dépendance introuvable: {}", id),
+            DependencyError::Cycle => write!(
+                f,
+                "Dépendance circulaire: une tâche ne peut pas dépendre d'elle-même ni d'une tâche qui dépend d'elle"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for DependencyError {}
+
+impl From<rusqlite::Error> for DependencyError {
+    fn from(e: rusqlite::Error) -> Self {
+        DependencyError::Sql(e)
+    }
+}
+
+impl TaskStorage {
+    /// Vérifie que `task_id` peut dépendre de `depends_on` : la dépendance doit exister
+    /// hors corbeille et ne pas dépendre elle-même, directement ou non, de `task_id`.
+    pub fn check_dependency(
+        &self,
+        task_id: &Uuid,
+        depends_on: &Uuid,
+    ) -> Result<(), DependencyError> {
+        let exists: bool = self.conn.query_row(
+            "SELECT COUNT(*) > 0 FROM tasks WHERE id = ?1 AND deleted_at IS NULL",
+            params![depends_on.to_string()],
+            |row| row.get(0),
+        )?;
+        if !exists {
+            return Err(DependencyError::NotFound(*depends_on));
+        }
+
+        if self.depends_on_transitively(depends_on, task_id)? {
+            return Err(DependencyError::Cycle);
+        }
+        Ok(())
+    }
+
+    /// Indique si `task_id` est `target` ou dépend, directement ou non, de `target`
+    pub(super) fn depends_on_transitively(&self, task_id: &Uuid, target: &Uuid) -> SqlResult<bool> {
+        // Parcourt les dépendances de `task_id` ; `UNION` arrête le parcours sur un cycle existant
+        self.conn.query_row(
+            "WITH RECURSIVE reachable(id) AS (
+                SELECT ?1
+                UNION
+                SELECT task_dependencies.depends_on FROM task_dependencies
+                JOIN reachable ON task_dependencies.task_id = reachable.id
+            )
+            SELECT COUNT(*) > 0 FROM reachable WHERE id = ?2",
+            params![task_id.to_string(), target.to_string()],
+            |row| row.get(0),
+        )
+    }
+
+    /// Récupère les dépendances encore ouvertes d'une task, qui la bloquent, par ID court
+    pub fn get_blockers(&self, task_id: &Uuid) -> SqlResult<Vec<Task>> {
+        let sql = format!(
+            "SELECT {} FROM tasks AS blocker
+             WHERE blocker.id IN (SELECT depends_on FROM task_dependencies WHERE task_id = ?1)
+               AND {}
+             ORDER BY blocker.short_id",
+            TASK_COLUMNS, OPEN_BLOCKER
+        );
+        self.fetch_tasks(&sql, params![task_id.to_string()])
+    }
+
+    /// IDs courts des dépendances ouvertes de chaque task bloquée.
+    /// Une task terminée, annulée ou dans la corbeille n'est plus bloquée.
+    pub fn open_blockers(&self) -> SqlResult<HashMap<Uuid, Vec<u32>>> {
+        let sql = format!(
+            "SELECT task_dependencies.task_id, blocker.short_id FROM task_dependencies
+             JOIN tasks AS blocker ON blocker.id = task_dependencies.depends_on
+             JOIN tasks AS blocked ON blocked.id = task_dependencies.task_id
+             WHERE {}
+               AND blocked.deleted_at IS NULL
+               AND blocked.status IS NOT 'Completed' AND blocked.status IS NOT 'Canceled'
+             ORDER BY blocker.short_id",
+            OPEN_BLOCKER
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, u32>(1)?))
+        })?;
+
+        let mut blockers: HashMap<Uuid, Vec<u32>> = HashMap::new();
+        for row in rows {
+            let (task_id, short_id) = row?;
+            if let Ok(task_id) = Uuid::parse_str(&task_id) {
+                blockers.entry(task_id).or_default().push(short_id);
+            }
+        }
+        Ok(blockers)
+    }
+
+    /// Insère les dépendances d'une task
+    pub(super) fn insert_dependencies(&self, task: &Task) -> SqlResult<()> {
+        for depends_on in &task.depends_on {
+            self.conn.execute(
+                "INSERT OR IGNORE INTO task_dependencies (task_id, depends_on) VALUES (?1, ?2)",
+                params![task.id.to_string(), depends_on.to_string()],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Charge les dépendances de plusieurs tasks, par lots comme les tags.
+    /// Les dépendances vers une task absente (purgée ou pas encore importée) sont ignorées.
+    pub(super) fn load_dependencies(&self, tasks: &mut [Task]) -> SqlResult<()> {
+        let mut dependencies: HashMap<String, Vec<Uuid>> = HashMap::new();
+
+        for chunk in tasks.chunks(TAG_BATCH_SIZE) {
+            let sql = format!(
+                "SELECT task_id, depends_on FROM task_dependencies
+                 WHERE task_id IN ({}) AND depends_on IN (SELECT id FROM tasks)
+                 ORDER BY rowid",
+                vec!["?"; chunk.len()].join(", ")
+            );
+            let mut stmt = self.conn.prepare(&sql)?;
+            let rows = stmt.query_map(
+                params_from_iter(chunk.iter().map(|task| task.id.to_string())),
+                |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)),
+            )?;
+            for row in rows {
+                let (task_id, depends_on) = row?;
+                if let Ok(depends_on) = Uuid::parse_str(&depends_on) {
+                    dependencies.entry(task_id).or_default().push(depends_on);
+                }
+            }
+        }
+
+        for task in tasks.iter_mut() {
+            task.depends_on = dependencies
+                .remove(&task.id.to_string())
+                .unwrap_or_default();
+        }
+        Ok(())
+    }
+}
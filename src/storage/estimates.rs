@@ -0,0 +1,163 @@
+use super::{TaskQuery, TaskStorage, status_to_string};
+use crate::dates;
+use crate::task::{Status, Task};
+use chrono::{DateTime, Datelike, Days, Utc};
+use rusqlite::Result as SqlResult;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Estimation d'une task comparée au temps passé
+#[derive(Debug, Clone, Serialize)]
+pub struct EstimateComparison {
+    #[serde(flatten)]
+    pub task: Task,
+    /// Temps passé, intervalle en cours compris
+    pub tracked_seconds: i64,
+}
+
+impl EstimateComparison {
+    /// Temps passé moins temps estimé ; `None` pour une estimation en points
+    pub fn variance_seconds(&self) -> Option<i64> {
+        let minutes = self.task.estimate?.minutes()?;
+        Some(self.tracked_seconds - i64::from(minutes) * 60)
+    }
+}
+
+/// Regroupement de la charge estimée
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EffortGroup {
+    Tag,
+    Status,
+    /// Semaine de l'échéance (du lundi au dimanche, dans le fuseau configuré)
+    Week,
+}
+
+impl EffortGroup {
+    /// Convertit une chaîne (`tag`, `status` ou `week`) en regroupement
+    pub fn parse(input: &str) -> Result<EffortGroup, String> {
+        match input.trim().to_lowercase().as_str() {
+            "tag" => Ok(EffortGroup::Tag),
+            "status" => Ok(EffortGroup::Status),
+            "week" => Ok(EffortGroup::Week),
+            _ => Err(format!(
+                "Regroupement invalide '{}'. Utilisez: tag, status ou week",
+                input.trim()
+            )),
+        }
+    }
+}
+
+/// Charge estimée d'un groupe de tasks à faire
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct EffortLine {
+    /// Tag, statut ou lundi de la semaine (`2026-10-19`) ; `None` pour les tasks sans tag
+    /// ou sans échéance
+    pub label: Option<String>,
+    /// Tasks à faire du groupe
+    pub tasks: usize,
+    /// Tasks sans estimation
+    pub unestimated: usize,
+    /// Somme des estimations en minutes
+    pub minutes: u64,
+    /// Somme des estimations en points
+    pub points: u64,
+}
+
+impl EffortLine {
+    fn add(&mut self, task: &Task) {
+        self.tasks += 1;
+        match task.estimate {
+            Some(estimate) => {
+                self.minutes += u64::from(estimate.minutes().unwrap_or(0));
+                self.points += u64::from(estimate.points().unwrap_or(0));
+            }
+            None => self.unestimated += 1,
+        }
+    }
+}
+
+/// Charge estimée des tasks à faire, par groupe
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct EffortReport {
+    pub lines: Vec<EffortLine>,
+    /// Total ; une task à plusieurs tags n'y est comptée qu'une fois
+    pub total: EffortLine,
+}
+
+/// Lundi de la semaine d'une échéance, dans le fuseau configuré
+fn week_start(due: &DateTime<Utc>) -> String {
+    let day = dates::to_local(due).date_naive();
+    let monday = day
+        .checked_sub_days(Days::new(u64::from(day.weekday().num_days_from_monday())))
+        .unwrap_or(day);
+    monday.format("%Y-%m-%d").to_string()
+}
+
+impl TaskStorage {
+    /// Tasks estimées correspondant à `query`, avec le temps passé (intervalle en cours
+    /// compté jusqu'à `now`), dans l'ordre de la requête
+    pub fn estimate_comparison(
+        &self,
+        query: &TaskQuery,
+        now: DateTime<Utc>,
+    ) -> SqlResult<Vec<EstimateComparison>> {
+        let tracked = self.tracked_time(now)?;
+        Ok(self
+            .query_tasks(query)?
+            .into_iter()
+            .filter(|task| task.estimate.is_some())
+            .map(|task| EstimateComparison {
+                tracked_seconds: tracked.get(&task.id).map_or(0, |d| d.num_seconds()),
+                task,
+            })
+            .collect())
+    }
+
+    /// Somme des estimations des tasks à faire correspondant à `query`, par tag, statut
+    /// ou semaine d'échéance. Les tasks sans estimation sont comptées à part.
+    pub fn effort_report(&self, query: &TaskQuery, group: EffortGroup) -> SqlResult<EffortReport> {
+        let mut lines: HashMap<Option<String>, EffortLine> = HashMap::new();
+        let mut total = EffortLine::default();
+
+        for task in self.query_tasks(query)?.iter().filter(|t| t.is_open()) {
+            total.add(task);
+            let labels: Vec<Option<String>> = match group {
+                EffortGroup::Tag => match &task.tags {
+                    Some(tags) if !tags.is_empty() => tags.iter().cloned().map(Some).collect(),
+                    _ => vec![None],
+                },
+                EffortGroup::Status => vec![Some(
+                    status_to_string(task.status.as_ref().unwrap_or(&Status::NotStarted))
+                        .to_string(),
+                )],
+                EffortGroup::Week => vec![task.due_date.as_ref().map(week_start)],
+            };
+            for label in labels {
+                lines
+                    .entry(label.clone())
+                    .or_insert_with(|| EffortLine {
+                        label,
+                        ..EffortLine::default()
+                    })
+                    .add(task);
+            }
+        }
+
+        // Semaines dans l'ordre chronologique, tags par charge décroissante ;
+        // les tasks sans tag ou sans échéance en dernier
+        let mut lines: Vec<EffortLine> = lines.into_values().collect();
+        lines.sort_by(|a, b| {
+            let by_effort = match group {
+                EffortGroup::Tag => b.minutes.cmp(&a.minutes).then(b.points.cmp(&a.points)),
+                EffortGroup::Status | EffortGroup::Week => std::cmp::Ordering::Equal,
+            };
+            a.label
+                .is_none()
+                .cmp(&b.label.is_none())
+                .then(by_effort)
+                .then(a.label.cmp(&b.label))
+        });
+
+        Ok(EffortReport { lines, total })
+    }
+}
@@ -0,0 +1,185 @@
+use super::{TaskStorage, priority_to_string, status_to_string};
+use crate::task::Task;
+use chrono::{DateTime, Utc};
+use rusqlite::{Result as SqlResult, params};
+use serde::Serialize;
+use uuid::Uuid;
+
+/// Champ d'une task suivi dans l'historique
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum TaskField {
+    Description,
+    Priority,
+    Status,
+    DueDate,
+    Tags,
+    Recurrence,
+    Parent,
+    Dependencies,
+    Project,
+    Estimate,
+}
+
+/// Modification d'un champ d'une task
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskEvent {
+    pub id: i64,
+    pub task_id: Uuid,
+    pub field: TaskField,
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+    pub author: Option<String>,
+    pub changed_at: DateTime<Utc>,
+}
+
+impl TaskStorage {
+    /// Enregistre un événement par champ modifié entre deux états d'une task
+    pub(super) fn record_changes(&self, before: &Task, after: &Task) -> SqlResult<()> {
+        self.record_changes_at(before, after, after.updated_at)
+    }
+
+    /// Comme `record_changes`, daté de `changed_at` plutôt que de la modification de `after`
+    /// (état rétabli par `undo`/`redo`)
+    pub(super) fn record_changes_at(
+        &self,
+        before: &Task,
+        after: &Task,
+        changed_at: DateTime<Utc>,
+    ) -> SqlResult<()> {
+        let author = current_author();
+        let changed_at = changed_at.to_rfc3339();
+
+        for field in [
+            TaskField::Description,
+            TaskField::Priority,
+            TaskField::Status,
+            TaskField::DueDate,
+            TaskField::Tags,
+            TaskField::Recurrence,
+            TaskField::Parent,
+            TaskField::Dependencies,
+            TaskField::Project,
+            TaskField::Estimate,
+        ] {
+            let old_value = field_value(before, field);
+            let new_value = field_value(after, field);
+            if old_value == new_value {
+                continue;
+            }
+
+            self.conn.execute(
+                "INSERT INTO task_events (task_id, field, old_value, new_value, author, changed_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    after.id.to_string(),
+                    field_to_string(field),
+                    old_value,
+                    new_value,
+                    author,
+                    changed_at,
+                ],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Récupère l'historique des modifications d'une task, dans l'ordre chronologique
+    pub fn get_task_events(&self, task_id: &Uuid) -> SqlResult<Vec<TaskEvent>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, field, old_value, new_value, author, changed_at
+             FROM task_events WHERE task_id = ?1 ORDER BY id",
+        )?;
+        let rows = stmt.query_map(params![task_id.to_string()], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, Option<String>>(2)?,
+                row.get::<_, Option<String>>(3)?,
+                row.get::<_, Option<String>>(4)?,
+                row.get::<_, String>(5)?,
+            ))
+        })?;
+
+        let mut events = Vec::new();
+        for row in rows {
+            let (id, field_str, old_value, new_value, author, changed_at_str) = row?;
+            events.push(TaskEvent {
+                id,
+                task_id: *task_id,
+                field: string_to_field(&field_str),
+                old_value,
+                new_value,
+                author,
+                changed_at: DateTime::parse_from_rfc3339(&changed_at_str)
+                    .map(|dt| dt.with_timezone(&Utc))
+                    .unwrap_or_else(|_| Utc::now()),
+            });
+        }
+        Ok(events)
+    }
+}
+
+/// Valeur d'un champ telle que stockée dans l'historique
+fn field_value(task: &Task, field: TaskField) -> Option<String> {
+    match field {
+        TaskField::Description => Some(task.description.clone()),
+        TaskField::Priority => Some(priority_to_string(&task.priority).to_string()),
+        TaskField::Status => task
+            .status
+            .as_ref()
+            .map(|s| status_to_string(s).to_string()),
+        TaskField::DueDate => task.due_date.map(|d| d.to_rfc3339()),
+        TaskField::Tags => task.tags.as_ref().filter(|t| !t.is_empty()).map(|tags| {
+            let mut tags = tags.clone();
+            tags.sort();
+            tags.join(", ")
+        }),
+        TaskField::Recurrence => task.recurrence.as_ref().map(|r| r.to_string()),
+        TaskField::Parent => task.parent_id.map(|id| id.to_string()),
+        TaskField::Dependencies => (!task.depends_on.is_empty()).then(|| {
+            let mut ids: Vec<String> = task.depends_on.iter().map(Uuid::to_string).collect();
+            ids.sort();
+            ids.join(", ")
+        }),
+        TaskField::Project => task.project.clone(),
+        TaskField::Estimate => task.estimate.map(|e| e.to_string()),
+    }
+}
+
+/// Auteur des modifications : utilisateur du système
+fn current_author() -> Option<String> {
+    std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .ok()
+        .filter(|u| !u.is_empty())
+}
+
+fn field_to_string(field: TaskField) -> &'static str {
+    match field {
+        TaskField::Description => "Description",
+        TaskField::Priority => "Priority",
+        TaskField::Status => "Status",
+        TaskField::DueDate => "DueDate",
+        TaskField::Tags => "Tags",
+        TaskField::Recurrence => "Recurrence",
+        TaskField::Parent => "Parent",
+        TaskField::Dependencies => "Dependencies",
+        TaskField::Project => "Project",
+        TaskField::Estimate => "Estimate",
+    }
+}
+
+fn string_to_field(s: &str) -> TaskField {
+    match s {
+        "Priority" => TaskField::Priority,
+        "Status" => TaskField::Status,
+        "DueDate" => TaskField::DueDate,
+        "Tags" => TaskField::Tags,
+        "Recurrence" => TaskField::Recurrence,
+        "Parent" => TaskField::Parent,
+        "Dependencies" => TaskField::Dependencies,
+        "Project" => TaskField::Project,
+        "Estimate" => TaskField::Estimate,
+        _ => TaskField::Description,
+    }
+}
@@ -0,0 +1,216 @@
+use super::{TASK_COLUMNS, TaskStorage};
+use crate::task::Task;
+use rusqlite::{Result as SqlResult, params};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use uuid::Uuid;
+
+/// Erreurs de rattachement d'une task à une task parente
+#[derive(Debug)]
+pub enum ParentError {
+    /// Erreur SQLite
+    Sql(rusqlite::Error),
+    /// La task parente n'existe pas ou est dans la corbeille
+    NotFound(Uuid),
+    /// La task parente est la task elle-même ou l'une de ses sous-tasks
+    Cycle,
+}
+
+impl fmt::Display for ParentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParentError::Sql(e) => write!(f, "{}", e),
+            ParentError::NotFound(id) => write!(f, "Tâche parente introuvable: {}", id),
+            ParentError::Cycle => write!(
+                f,
+                "Une tâche ne peut pas être rattachée à elle-même ni à l'une de ses sous-tâches"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ParentError {}
+
+impl From<rusqlite::Error> for ParentError {
+    fn from(e: rusqlite::Error) -> Self {
+        ParentError::Sql(e)
+    }
+}
+
+/// Avancement des sous-tasks d'une task (les sous-tasks annulées ne sont pas comptées)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct Progress {
+    pub done: usize,
+    pub total: usize,
+}
+
+impl fmt::Display for Progress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.done, self.total)
+    }
+}
+
+impl TaskStorage {
+    /// Vérifie que `parent_id` peut devenir la task parente de `task_id` : elle doit
+    /// exister hors corbeille et ne pas être `task_id` ni l'une de ses sous-tasks.
+    pub fn check_parent(&self, task_id: &Uuid, parent_id: &Uuid) -> Result<(), ParentError> {
+        let exists: bool = self.conn.query_row(
+            "SELECT COUNT(*) > 0 FROM tasks WHERE id = ?1 AND deleted_at IS NULL",
+            params![parent_id.to_string()],
+            |row| row.get(0),
+        )?;
+        if !exists {
+            return Err(ParentError::NotFound(*parent_id));
+        }
+
+        if self.descends_from(parent_id, task_id)? {
+            return Err(ParentError::Cycle);
+        }
+        Ok(())
+    }
+
+    /// Indique si `task_id` est `ancestor` ou l'une de ses sous-tasks, directe ou non
+    pub(super) fn descends_from(&self, task_id: &Uuid, ancestor: &Uuid) -> SqlResult<bool> {
+        // Remonte les ancêtres de `task_id` ; `UNION` arrête le parcours sur un cycle existant
+        self.conn.query_row(
+            "WITH RECURSIVE ancestors(id) AS (
+                SELECT ?1
+                UNION
+                SELECT tasks.parent_id FROM tasks JOIN ancestors ON tasks.id = ancestors.id
+                WHERE tasks.parent_id IS NOT NULL
+            )
+            SELECT COUNT(*) > 0 FROM ancestors WHERE id = ?2",
+            params![task_id.to_string(), ancestor.to_string()],
+            |row| row.get(0),
+        )
+    }
+
+    /// Récupère les sous-tasks directes d'une task, hors corbeille, par ID court
+    pub fn get_subtasks(&self, parent_id: &Uuid) -> SqlResult<Vec<Task>> {
+        let sql = format!(
+            "SELECT {} FROM tasks WHERE parent_id = ?1 AND deleted_at IS NULL ORDER BY short_id",
+            TASK_COLUMNS
+        );
+        self.fetch_tasks(&sql, params![parent_id.to_string()])
+    }
+
+    /// Avancement des sous-tasks directes de chaque task qui en a
+    pub fn subtask_progress(&self) -> SqlResult<HashMap<Uuid, Progress>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT parent_id, SUM(status IS 'Completed'), SUM(status IS NOT 'Canceled')
+             FROM tasks WHERE parent_id IS NOT NULL AND deleted_at IS NULL
+             GROUP BY parent_id",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, usize>(1)?,
+                row.get::<_, usize>(2)?,
+            ))
+        })?;
+
+        let mut progress = HashMap::new();
+        for row in rows {
+            let (parent_id, done, total) = row?;
+            if let Ok(parent_id) = Uuid::parse_str(&parent_id) {
+                progress.insert(parent_id, Progress { done, total });
+            }
+        }
+        Ok(progress)
+    }
+}
+
+/// Ordonne des tasks en arbre : chaque task est suivie de ses sous-tasks, avec leur
+/// profondeur. L'ordre d'origine est conservé entre tasks sœurs ; une task dont la
+/// parente ne fait pas partie de la liste est placée au premier niveau.
+pub fn tree_order(tasks: &[Task]) -> Vec<(usize, &Task)> {
+    let ids: HashSet<Uuid> = tasks.iter().map(|t| t.id).collect();
+    let mut children: HashMap<Uuid, Vec<&Task>> = HashMap::new();
+    for task in tasks {
+        if let Some(parent_id) = task.parent_id.filter(|id| ids.contains(id)) {
+            children.entry(parent_id).or_default().push(task);
+        }
+    }
+
+    let mut ordered = Vec::with_capacity(tasks.len());
+    let mut visited = HashSet::new();
+    let roots = tasks
+        .iter()
+        .filter(|t| t.parent_id.is_none_or(|id| !ids.contains(&id)));
+    // Les tasks restantes appartiennent à un cycle (données importées) : premier niveau
+    for root in roots.chain(tasks) {
+        let mut stack = vec![(0, root)];
+        while let Some((depth, task)) = stack.pop() {
+            if !visited.insert(task.id) {
+                continue;
+            }
+            ordered.push((depth, task));
+            if let Some(subtasks) = children.get(&task.id) {
+                stack.extend(subtasks.iter().rev().map(|t| (depth + 1, *t)));
+            }
+        }
+    }
+    ordered
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(description: &str, parent: Option<&Task>) -> Task {
+        let mut task = Task::new(description, None, None, None, None);
+        task.parent_id = parent.map(|p| p.id);
+        task
+    }
+
+    fn tree(tasks: &[Task]) -> Vec<(usize, &str)> {
+        tree_order(tasks)
+            .into_iter()
+            .map(|(depth, t)| (depth, t.description.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn test_tree_order() {
+        let projet = task("Projet", None);
+        let etape1 = task("Étape 1", Some(&projet));
+        let detail = task("Détail", Some(&etape1));
+        let etape2 = task("Étape 2", Some(&projet));
+        let autre = task("Autre", None);
+        let orpheline = task(
+            "Orpheline",
+            Some(&Task::new("Absente", None, None, None, None)),
+        );
+
+        let tasks = vec![
+            detail.clone(),
+            autre.clone(),
+            etape2.clone(),
+            projet.clone(),
+            orpheline,
+            etape1.clone(),
+        ];
+        assert_eq!(
+            tree(&tasks),
+            vec![
+                (0, "Autre"),
+                (0, "Projet"),
+                (1, "Étape 2"),
+                (1, "Étape 1"),
+                (2, "Détail"),
+                (0, "Orpheline"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tree_order_with_cycle() {
+        let mut a = task("A", None);
+        let b = task("B", Some(&a));
+        a.parent_id = Some(b.id);
+
+        let tasks = vec![a, b];
+        assert_eq!(tree(&tasks), vec![(0, "A"), (1, "B")]);
+    }
+}
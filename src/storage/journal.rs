@@ -0,0 +1,341 @@
+use super::TaskStorage;
+use super::notes::Note;
+use super::time_tracking::TimeEntry;
+use crate::task::Task;
+use chrono::{DateTime, Utc};
+use rusqlite::{Result as SqlResult, params};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Type d'opération enregistrée dans le journal
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum OperationKind {
+    Add,
+    Update,
+    Delete,
+    Restore,
+    /// Ajout ou modification d'une note
+    Note,
+    /// Démarrage ou arrêt du suivi du temps
+    TimeTracking,
+}
+
+/// Opération du journal, avec l'état de la task avant et après
+#[derive(Debug, Clone, Serialize)]
+pub struct Operation {
+    pub id: i64,
+    pub batch: i64,
+    pub kind: OperationKind,
+    pub task_id: Uuid,
+    pub before: Option<Task>,
+    pub after: Option<Task>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Operation {
+    /// État de la task le plus récent connu (après, sinon avant)
+    pub fn task(&self) -> Option<&Task> {
+        self.after.as_ref().or(self.before.as_ref())
+    }
+}
+
+/// État d'une task enregistré dans le journal.
+/// Le projet est identifié par son id : un projet renommé depuis l'opération est retrouvé.
+/// `project_id` est absent des états enregistrés avant son ajout ; le nom du projet fait alors foi.
+/// Les notes et les intervalles de suivi du temps ne sont enregistrés que par les opérations
+/// qui les modifient (`None` : inchangés).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(super) struct Snapshot {
+    #[serde(flatten)]
+    task: Task,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    project_id: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    notes: Option<Vec<Note>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    time_entries: Option<Vec<TimeEntry>>,
+}
+
+/// Opération relue du journal, avec les états complets à rétablir
+struct JournalEntry {
+    operation: Operation,
+    before: Option<Snapshot>,
+    after: Option<Snapshot>,
+}
+
+impl TaskStorage {
+    /// Ouvre un nouveau lot d'opérations et retourne son numéro.
+    /// Toute nouvelle modification invalide les opérations annulées (pile de redo).
+    pub(super) fn begin_batch(&self) -> SqlResult<i64> {
+        self.conn
+            .execute("DELETE FROM operations WHERE undone = 1", [])?;
+        self.conn.query_row(
+            "SELECT COALESCE(MAX(batch), 0) + 1 FROM operations",
+            [],
+            |row| row.get(0),
+        )
+    }
+
+    /// Enregistre une opération dans le journal
+    pub(super) fn record_operation(
+        &self,
+        batch: i64,
+        kind: OperationKind,
+        before: Option<&Task>,
+        after: Option<&Task>,
+    ) -> SqlResult<()> {
+        let before = before.map(|task| self.snapshot(task)).transpose()?;
+        let after = after.map(|task| self.snapshot(task)).transpose()?;
+        self.record_snapshots(batch, kind, before.as_ref(), after.as_ref())
+    }
+
+    /// État actuel d'une task, à enregistrer avant et après une opération `kind`.
+    /// Les notes sont comprises pour `Note`, les intervalles pour `TimeTracking`.
+    pub(super) fn capture(
+        &self,
+        task_id: &Uuid,
+        kind: OperationKind,
+    ) -> SqlResult<Option<Snapshot>> {
+        let Some(task) = self.get_task(task_id)? else {
+            return Ok(None);
+        };
+        let mut state = self.snapshot(&task)?;
+        match kind {
+            OperationKind::Note => state.notes = Some(self.get_notes(task_id)?),
+            OperationKind::TimeTracking => {
+                state.time_entries = Some(self.get_time_entries(task_id)?)
+            }
+            _ => {}
+        }
+        Ok(Some(state))
+    }
+
+    /// Enregistre une opération dont les états ont été capturés par l'appelant
+    pub(super) fn record_snapshots(
+        &self,
+        batch: i64,
+        kind: OperationKind,
+        before: Option<&Snapshot>,
+        after: Option<&Snapshot>,
+    ) -> SqlResult<()> {
+        let Some(task_id) = after.or(before).map(|state| state.task.id.to_string()) else {
+            return Ok(());
+        };
+        self.conn.execute(
+            "INSERT INTO operations (batch, kind, task_id, before, after, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                batch,
+                kind_to_string(kind),
+                task_id,
+                before.map(to_json).transpose()?,
+                after.map(to_json).transpose()?,
+                Utc::now().to_rfc3339(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Annule le dernier lot d'opérations et retourne les opérations annulées
+    /// (vide s'il n'y a rien à annuler).
+    pub fn undo(&mut self) -> SqlResult<Vec<Operation>> {
+        let tx = self.conn.unchecked_transaction()?;
+        let batch: Option<i64> = self.conn.query_row(
+            "SELECT MAX(batch) FROM operations WHERE undone = 0",
+            [],
+            |row| row.get(0),
+        )?;
+        let Some(batch) = batch else {
+            return Ok(Vec::new());
+        };
+
+        // Les opérations d'un lot sont annulées dans l'ordre inverse
+        let mut entries = self.batch_operations(batch)?;
+        entries.reverse();
+        for entry in &entries {
+            self.restore(&entry.operation.task_id, entry.before.as_ref())?;
+        }
+        self.conn.execute(
+            "UPDATE operations SET undone = 1 WHERE batch = ?1",
+            params![batch],
+        )?;
+
+        tx.commit()?;
+        Ok(entries.into_iter().map(|entry| entry.operation).collect())
+    }
+
+    /// Rejoue le dernier lot annulé et retourne les opérations rejouées
+    /// (vide s'il n'y a rien à rejouer).
+    pub fn redo(&mut self) -> SqlResult<Vec<Operation>> {
+        let tx = self.conn.unchecked_transaction()?;
+        let batch: Option<i64> = self.conn.query_row(
+            "SELECT MIN(batch) FROM operations WHERE undone = 1",
+            [],
+            |row| row.get(0),
+        )?;
+        let Some(batch) = batch else {
+            return Ok(Vec::new());
+        };
+
+        let entries = self.batch_operations(batch)?;
+        for entry in &entries {
+            self.restore(&entry.operation.task_id, entry.after.as_ref())?;
+        }
+        self.conn.execute(
+            "UPDATE operations SET undone = 0 WHERE batch = ?1",
+            params![batch],
+        )?;
+
+        tx.commit()?;
+        Ok(entries.into_iter().map(|entry| entry.operation).collect())
+    }
+
+    /// Remet une task (et ses notes ou ses intervalles si l'état les contient) dans l'état donné, sans
+    /// journalisation, et enregistre dans son historique les champs rétablis. Sans état (annulation d'un ajout), la task est placée
+    /// dans la corbeille plutôt que supprimée : ses notes, son suivi du temps et son
+    /// historique sont conservés pour `redo`.
+    fn restore(&self, task_id: &Uuid, state: Option<&Snapshot>) -> SqlResult<()> {
+        let current = self.get_task(task_id)?;
+        let (target, project_id) = match (state, &current) {
+            (Some(state), _) => (state.task.clone(), self.snapshot_project_id(state)?),
+            (None, Some(current)) if current.deleted_at.is_none() => {
+                let mut trashed = current.clone();
+                trashed.deleted_at = Some(Utc::now());
+                let project_id = self.project_id_for(&trashed)?;
+                (trashed, project_id)
+            }
+            (None, _) => return Ok(()),
+        };
+
+        match current {
+            Some(current) => {
+                self.write_task_in(&target, project_id)?;
+                if let Some(restored) = self.get_task(task_id)? {
+                    self.record_changes_at(&current, &restored, Utc::now())?;
+                }
+            }
+            // Task purgée depuis l'opération
+            None => {
+                self.insert_task_in(&target, project_id)?;
+            }
+        }
+
+        if let Some(notes) = state.and_then(|state| state.notes.as_ref()) {
+            self.replace_notes(task_id, notes)?;
+        }
+        if let Some(entries) = state.and_then(|state| state.time_entries.as_ref()) {
+            self.replace_time_entries(task_id, entries)?;
+        }
+        Ok(())
+    }
+
+    /// État d'une task pour le journal, avec l'id de son projet
+    fn snapshot(&self, task: &Task) -> SqlResult<Snapshot> {
+        // L'état avant une opération est enregistré après l'écriture : le projet est
+        // retrouvé par son nom, qui ne change pas au sein d'un lot
+        let project_id = match task.project.as_deref() {
+            Some(name) => self.get_project(name)?.map(|project| project.id),
+            None => None,
+        };
+        Ok(Snapshot {
+            task: task.clone(),
+            project_id,
+            notes: None,
+            time_entries: None,
+        })
+    }
+
+    /// Projet d'un état du journal : par son id s'il existe toujours, sinon par son nom
+    fn snapshot_project_id(&self, state: &Snapshot) -> SqlResult<Option<i64>> {
+        if state.task.project.is_none() {
+            return Ok(None);
+        }
+        if let Some(project_id) = state.project_id {
+            let exists: bool = self.conn.query_row(
+                "SELECT COUNT(*) > 0 FROM projects WHERE id = ?1",
+                params![project_id],
+                |row| row.get(0),
+            )?;
+            if exists {
+                return Ok(Some(project_id));
+            }
+        }
+        self.project_id_for(&state.task)
+    }
+
+    /// Récupère les opérations d'un lot, dans l'ordre chronologique
+    fn batch_operations(&self, batch: i64) -> SqlResult<Vec<JournalEntry>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, batch, kind, task_id, before, after, created_at
+             FROM operations WHERE batch = ?1 ORDER BY id",
+        )?;
+        let rows = stmt.query_map(params![batch], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, Option<String>>(4)?,
+                row.get::<_, Option<String>>(5)?,
+                row.get::<_, String>(6)?,
+            ))
+        })?;
+
+        let mut entries = Vec::new();
+        for row in rows {
+            let (id, batch, kind_str, task_id_str, before, after, created_at_str) = row?;
+            let before = before.as_deref().map(restore_snapshot).transpose()?;
+            let after = after.as_deref().map(restore_snapshot).transpose()?;
+            entries.push(JournalEntry {
+                operation: Operation {
+                    id,
+                    batch,
+                    kind: string_to_kind(&kind_str),
+                    task_id: Uuid::parse_str(&task_id_str).unwrap_or_else(|_| Uuid::nil()),
+                    before: before.as_ref().map(|state| state.task.clone()),
+                    after: after.as_ref().map(|state| state.task.clone()),
+                    created_at: DateTime::parse_from_rfc3339(&created_at_str)
+                        .map(|dt| dt.with_timezone(&Utc))
+                        .unwrap_or_else(|_| Utc::now()),
+                },
+                before,
+                after,
+            });
+        }
+        Ok(entries)
+    }
+}
+
+/// Sérialise l'état d'une task en JSON
+fn to_json(state: &Snapshot) -> SqlResult<String> {
+    serde_json::to_string(state).map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))
+}
+
+/// Relit l'état JSON d'une task
+fn restore_snapshot(json: &str) -> SqlResult<Snapshot> {
+    serde_json::from_str(json).map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Text, Box::new(e))
+    })
+}
+
+fn kind_to_string(kind: OperationKind) -> &'static str {
+    match kind {
+        OperationKind::Add => "Add",
+        OperationKind::Update => "Update",
+        OperationKind::Delete => "Delete",
+        OperationKind::Restore => "Restore",
+        OperationKind::Note => "Note",
+        OperationKind::TimeTracking => "TimeTracking",
+    }
+}
+
+fn string_to_kind(s: &str) -> OperationKind {
+    match s {
+        "Add" => OperationKind::Add,
+        "Delete" => OperationKind::Delete,
+        "Restore" => OperationKind::Restore,
+        "Note" => OperationKind::Note,
+        "TimeTracking" => OperationKind::TimeTracking,
+        _ => OperationKind::Update,
+    }
+}
@@ -0,0 +1,422 @@
+use rusqlite::{Connection, Result as SqlResult, Transaction, params};
+
+/// Migration du schéma de la base de données
+pub struct Migration {
+    /// Version du schéma atteinte après application
+    pub version: u32,
+    /// Fonction d'application, exécutée dans une transaction
+    pub up: fn(&Transaction) -> SqlResult<()>,
+}
+
+/// Liste ordonnée des migrations. Ne jamais modifier une migration publiée :
+/// toute évolution du schéma doit faire l'objet d'une nouvelle entrée.
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        up: initial_schema,
+    },
+    Migration {
+        version: 2,
+        up: short_ids,
+    },
+    Migration {
+        version: 3,
+        up: operations_journal,
+    },
+    Migration {
+        version: 4,
+        up: task_events,
+    },
+    Migration {
+        version: 5,
+        up: soft_delete,
+    },
+    Migration {
+        version: 6,
+        up: query_indexes,
+    },
+    Migration {
+        version: 7,
+        up: full_text_search,
+    },
+    Migration {
+        version: 8,
+        up: optional_due_date,
+    },
+    Migration {
+        version: 9,
+        up: recurrence,
+    },
+    Migration {
+        version: 10,
+        up: subtasks,
+    },
+    Migration {
+        version: 11,
+        up: task_dependencies,
+    },
+    Migration {
+        version: 12,
+        up: projects,
+    },
+    Migration {
+        version: 13,
+        up: task_notes,
+    },
+    Migration {
+        version: 14,
+        up: time_entries,
+    },
+    Migration {
+        version: 15,
+        up: estimates,
+    },
+    Migration {
+        version: 16,
+        up: sequences,
+    },
+];
+
+/// Version du schéma attendue par cette version de l'application
+pub fn latest_version() -> u32 {
+    MIGRATIONS.last().map(|m| m.version).unwrap_or(0)
+}
+
+/// Lit la version du schéma (`PRAGMA user_version`)
+pub fn current_version(conn: &Connection) -> SqlResult<u32> {
+    conn.query_row("PRAGMA user_version", [], |row| row.get(0))
+}
+
+/// Applique les migrations en attente de `MIGRATIONS`
+pub fn run(conn: &mut Connection) -> SqlResult<()> {
+    apply(conn, MIGRATIONS)
+}
+
+/// Applique les migrations dont la version est supérieure à la version courante.
+/// Chaque migration est exécutée dans sa propre transaction avec la mise à jour de `user_version`.
+///
+/// Les clés étrangères sont désactivées pendant les migrations : une reconstruction de
+/// table supprimerait sinon les tags en cascade. Le réglage ne peut pas être modifié
+/// dans une transaction, il est donc rétabli une fois toutes les migrations appliquées.
+pub fn apply(conn: &mut Connection, migrations: &[Migration]) -> SqlResult<()> {
+    let current = current_version(conn)?;
+    let foreign_keys: bool = conn.query_row("PRAGMA foreign_keys", [], |row| row.get(0))?;
+    conn.pragma_update(None, "foreign_keys", false)?;
+
+    let result = migrations
+        .iter()
+        .filter(|m| m.version > current)
+        .try_for_each(|migration| {
+            let tx = conn.transaction()?;
+            (migration.up)(&tx)?;
+            tx.pragma_update(None, "user_version", migration.version)?;
+            tx.commit()
+        });
+
+    conn.pragma_update(None, "foreign_keys", foreign_keys)?;
+    result
+}
+
+/// v1 : tables `tasks` et `task_tags` telles que livrées en v0.1.0.
+/// `IF NOT EXISTS` permet d'adopter les bases v0.1.0, créées sans `user_version`.
+fn initial_schema(tx: &Transaction) -> SqlResult<()> {
+    tx.execute_batch(
+        "CREATE TABLE IF NOT EXISTS tasks (
+            id TEXT PRIMARY KEY,
+            description TEXT NOT NULL,
+            priority TEXT NOT NULL,
+            status TEXT,
+            due_date TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            updated_at TEXT NOT NULL
+        );
+        CREATE TABLE IF NOT EXISTS task_tags (
+            task_id TEXT NOT NULL,
+            tag TEXT NOT NULL,
+            PRIMARY KEY (task_id, tag),
+            FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE
+        );",
+    )
+}
+
+/// v2 : colonne `short_id`, attribuée aux tasks existantes par ordre de création
+fn short_ids(tx: &Transaction) -> SqlResult<()> {
+    let has_short_id: bool = tx.query_row(
+        "SELECT COUNT(*) > 0 FROM pragma_table_info('tasks') WHERE name = 'short_id'",
+        [],
+        |row| row.get(0),
+    )?;
+    if !has_short_id {
+        tx.execute_batch("ALTER TABLE tasks ADD COLUMN short_id INTEGER;")?;
+    }
+
+    let missing: Vec<String> = tx
+        .prepare("SELECT id FROM tasks WHERE short_id IS NULL ORDER BY created_at, id")?
+        .query_map([], |row| row.get(0))?
+        .collect::<SqlResult<Vec<String>>>()?;
+    for id in missing {
+        tx.execute(
+            "UPDATE tasks SET short_id = (SELECT COALESCE(MAX(short_id), 0) + 1 FROM tasks)
+             WHERE id = ?1",
+            params![id],
+        )?;
+    }
+
+    tx.execute_batch("CREATE UNIQUE INDEX IF NOT EXISTS idx_tasks_short_id ON tasks(short_id);")
+}
+
+/// v3 : journal des opérations (`add`, `update`, `delete`) pour undo/redo
+fn operations_journal(tx: &Transaction) -> SqlResult<()> {
+    tx.execute_batch(
+        "CREATE TABLE operations (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            batch INTEGER NOT NULL,
+            kind TEXT NOT NULL,
+            task_id TEXT NOT NULL,
+            before TEXT,
+            after TEXT,
+            created_at TEXT NOT NULL,
+            undone INTEGER NOT NULL DEFAULT 0
+        );
+        CREATE INDEX idx_operations_batch ON operations(batch);",
+    )
+}
+
+/// v4 : historique des modifications par champ
+fn task_events(tx: &Transaction) -> SqlResult<()> {
+    tx.execute_batch(
+        "CREATE TABLE task_events (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            task_id TEXT NOT NULL,
+            field TEXT NOT NULL,
+            old_value TEXT,
+            new_value TEXT,
+            author TEXT,
+            changed_at TEXT NOT NULL
+        );
+        CREATE INDEX idx_task_events_task ON task_events(task_id);",
+    )
+}
+
+/// v5 : corbeille (`deleted_at`) et nettoyage des tags orphelins laissés par les
+/// suppressions effectuées sans `PRAGMA foreign_keys`
+fn soft_delete(tx: &Transaction) -> SqlResult<()> {
+    tx.execute_batch(
+        "ALTER TABLE tasks ADD COLUMN deleted_at TEXT;
+        CREATE INDEX idx_tasks_deleted_at ON tasks(deleted_at);
+        DELETE FROM task_tags WHERE task_id NOT IN (SELECT id FROM tasks);",
+    )
+}
+
+/// v6 : index des filtres courants (tag sans tenir compte de la casse, statut, échéance)
+fn query_indexes(tx: &Transaction) -> SqlResult<()> {
+    tx.execute_batch(
+        "CREATE INDEX idx_task_tags_tag ON task_tags(tag COLLATE NOCASE);
+        CREATE INDEX idx_tasks_status ON tasks(status);
+        CREATE INDEX idx_tasks_due_date ON tasks(due_date);",
+    )
+}
+
+/// v7 : index plein texte des descriptions, indexé par ID court
+/// et tenu à jour par des triggers
+fn full_text_search(tx: &Transaction) -> SqlResult<()> {
+    tx.execute_batch(
+        "CREATE VIRTUAL TABLE tasks_fts USING fts5(
+            body,
+            tokenize = 'unicode61 remove_diacritics 2'
+        );
+        INSERT INTO tasks_fts(rowid, body) SELECT short_id, description FROM tasks;
+
+        CREATE TRIGGER tasks_fts_insert AFTER INSERT ON tasks BEGIN
+            INSERT INTO tasks_fts(rowid, body) VALUES (new.short_id, new.description);
+        END;
+        CREATE TRIGGER tasks_fts_update AFTER UPDATE OF description, short_id ON tasks BEGIN
+            DELETE FROM tasks_fts WHERE rowid = old.short_id;
+            INSERT INTO tasks_fts(rowid, body) VALUES (new.short_id, new.description);
+        END;
+        CREATE TRIGGER tasks_fts_delete AFTER DELETE ON tasks BEGIN
+            DELETE FROM tasks_fts WHERE rowid = old.short_id;
+        END;",
+    )
+}
+
+/// v8 : échéance facultative. SQLite ne permet pas de retirer `NOT NULL` d'une colonne :
+/// la table `tasks` est reconstruite, avec ses index et les triggers de `tasks_fts`.
+fn optional_due_date(tx: &Transaction) -> SqlResult<()> {
+    tx.execute_batch(
+        "CREATE TABLE tasks_new (
+            id TEXT PRIMARY KEY,
+            description TEXT NOT NULL,
+            priority TEXT NOT NULL,
+            status TEXT,
+            due_date TEXT,
+            created_at TEXT NOT NULL,
+            updated_at TEXT NOT NULL,
+            short_id INTEGER,
+            deleted_at TEXT
+        );
+        INSERT INTO tasks_new (id, description, priority, status, due_date, created_at,
+                               updated_at, short_id, deleted_at)
+            SELECT id, description, priority, status, due_date, created_at,
+                   updated_at, short_id, deleted_at
+            FROM tasks;
+        DROP TABLE tasks;
+        ALTER TABLE tasks_new RENAME TO tasks;
+
+        CREATE UNIQUE INDEX idx_tasks_short_id ON tasks(short_id);
+        CREATE INDEX idx_tasks_deleted_at ON tasks(deleted_at);
+        CREATE INDEX idx_tasks_status ON tasks(status);
+        CREATE INDEX idx_tasks_due_date ON tasks(due_date);
+
+        CREATE TRIGGER tasks_fts_insert AFTER INSERT ON tasks BEGIN
+            INSERT INTO tasks_fts(rowid, body) VALUES (new.short_id, new.description);
+        END;
+        CREATE TRIGGER tasks_fts_update AFTER UPDATE OF description, short_id ON tasks BEGIN
+            DELETE FROM tasks_fts WHERE rowid = old.short_id;
+            INSERT INTO tasks_fts(rowid, body) VALUES (new.short_id, new.description);
+        END;
+        CREATE TRIGGER tasks_fts_delete AFTER DELETE ON tasks BEGIN
+            DELETE FROM tasks_fts WHERE rowid = old.short_id;
+        END;",
+    )
+}
+
+/// v9 : règle de récurrence (`daily`, `weekly:mon,thu`, `monthly:15`, `after:3d`)
+fn recurrence(tx: &Transaction) -> SqlResult<()> {
+    tx.execute_batch("ALTER TABLE tasks ADD COLUMN recurrence TEXT;")
+}
+
+/// v10 : task parente. Pas de clé étrangère : l'import doit pouvoir ajouter une
+/// sous-task avant sa parente, et une parente purgée laisse ses sous-tasks au premier niveau.
+fn subtasks(tx: &Transaction) -> SqlResult<()> {
+    tx.execute_batch(
+        "ALTER TABLE tasks ADD COLUMN parent_id TEXT;
+        CREATE INDEX idx_tasks_parent_id ON tasks(parent_id);",
+    )
+}
+
+/// v11 : dépendances entre tasks. Comme pour `parent_id`, `depends_on` n'est pas une
+/// clé étrangère : l'import peut ajouter une task avant celles dont elle dépend.
+fn task_dependencies(tx: &Transaction) -> SqlResult<()> {
+    tx.execute_batch(
+        "CREATE TABLE task_dependencies (
+            task_id TEXT NOT NULL,
+            depends_on TEXT NOT NULL,
+            PRIMARY KEY (task_id, depends_on),
+            FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE
+        );
+        CREATE INDEX idx_task_dependencies_depends_on ON task_dependencies(depends_on);",
+    )
+}
+
+/// v12 : projets, référencés par `tasks.project_id`
+fn projects(tx: &Transaction) -> SqlResult<()> {
+    tx.execute_batch(
+        "CREATE TABLE projects (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL UNIQUE COLLATE NOCASE,
+            created_at TEXT NOT NULL,
+            archived_at TEXT
+        );
+        ALTER TABLE tasks ADD COLUMN project_id INTEGER REFERENCES projects(id);
+        CREATE INDEX idx_tasks_project_id ON tasks(project_id);",
+    )
+}
+
+/// v13 : notes des tasks. `tasks_fts` est recréée avec une colonne `notes`
+/// (texte des notes de la task), tenue à jour par des triggers sur `task_notes`.
+fn task_notes(tx: &Transaction) -> SqlResult<()> {
+    tx.execute_batch(
+        "CREATE TABLE task_notes (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            task_id TEXT NOT NULL,
+            text TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            updated_at TEXT NOT NULL,
+            FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE
+        );
+        CREATE INDEX idx_task_notes_task_id ON task_notes(task_id);
+
+        DROP TRIGGER tasks_fts_insert;
+        DROP TRIGGER tasks_fts_update;
+        DROP TRIGGER tasks_fts_delete;
+        DROP TABLE tasks_fts;
+
+        CREATE VIRTUAL TABLE tasks_fts USING fts5(
+            body,
+            notes,
+            tokenize = 'unicode61 remove_diacritics 2'
+        );
+        INSERT INTO tasks_fts(rowid, body) SELECT short_id, description FROM tasks;
+
+        CREATE TRIGGER tasks_fts_insert AFTER INSERT ON tasks BEGIN
+            INSERT INTO tasks_fts(rowid, body) VALUES (new.short_id, new.description);
+        END;
+        CREATE TRIGGER tasks_fts_update AFTER UPDATE OF description, short_id ON tasks BEGIN
+            DELETE FROM tasks_fts WHERE rowid = old.short_id;
+            INSERT INTO tasks_fts(rowid, body, notes) VALUES (new.short_id, new.description,
+                (SELECT group_concat(text, char(10)) FROM task_notes WHERE task_id = new.id));
+        END;
+        CREATE TRIGGER tasks_fts_delete AFTER DELETE ON tasks BEGIN
+            DELETE FROM tasks_fts WHERE rowid = old.short_id;
+        END;
+
+        CREATE TRIGGER task_notes_fts_insert AFTER INSERT ON task_notes BEGIN
+            DELETE FROM tasks_fts WHERE rowid = (SELECT short_id FROM tasks WHERE id = new.task_id);
+            INSERT INTO tasks_fts(rowid, body, notes)
+                SELECT short_id, description,
+                    (SELECT group_concat(text, char(10)) FROM task_notes WHERE task_id = tasks.id)
+                FROM tasks WHERE id = new.task_id;
+        END;
+        CREATE TRIGGER task_notes_fts_update AFTER UPDATE OF text ON task_notes BEGIN
+            DELETE FROM tasks_fts WHERE rowid = (SELECT short_id FROM tasks WHERE id = new.task_id);
+            INSERT INTO tasks_fts(rowid, body, notes)
+                SELECT short_id, description,
+                    (SELECT group_concat(text, char(10)) FROM task_notes WHERE task_id = tasks.id)
+                FROM tasks WHERE id = new.task_id;
+        END;
+        CREATE TRIGGER task_notes_fts_delete AFTER DELETE ON task_notes BEGIN
+            DELETE FROM tasks_fts WHERE rowid = (SELECT short_id FROM tasks WHERE id = old.task_id);
+            INSERT INTO tasks_fts(rowid, body, notes)
+                SELECT short_id, description,
+                    (SELECT group_concat(text, char(10)) FROM task_notes WHERE task_id = tasks.id)
+                FROM tasks WHERE id = old.task_id;
+        END;",
+    )
+}
+
+/// v14 : intervalles de suivi du temps. L'index unique partiel garantit qu'un seul
+/// intervalle est en cours (`ended_at IS NULL`) à la fois.
+fn time_entries(tx: &Transaction) -> SqlResult<()> {
+    tx.execute_batch(
+        "CREATE TABLE time_entries (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            task_id TEXT NOT NULL,
+            started_at TEXT NOT NULL,
+            ended_at TEXT,
+            FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE
+        );
+        CREATE INDEX idx_time_entries_task_id ON time_entries(task_id);
+        CREATE INDEX idx_time_entries_started_at ON time_entries(started_at);
+        CREATE UNIQUE INDEX idx_time_entries_running ON time_entries((ended_at IS NULL))
+            WHERE ended_at IS NULL;",
+    )
+}
+
+/// v15 : effort estimé (`45m`, `1h30`, `3pt`)
+fn estimates(tx: &Transaction) -> SqlResult<()> {
+    tx.execute_batch("ALTER TABLE tasks ADD COLUMN estimate TEXT;")
+}
+
+/// v16 : compteurs monotones. `short_id` mémorise le plus grand ID court attribué, pour
+/// qu'une task purgée ou dont l'ajout a été annulé ne voie jamais son ID court réutilisé.
+fn sequences(tx: &Transaction) -> SqlResult<()> {
+    tx.execute_batch(
+        "CREATE TABLE sequences (
+            name TEXT PRIMARY KEY,
+            value INTEGER NOT NULL
+        );
+        INSERT INTO sequences (name, value)
+            SELECT 'short_id', COALESCE(MAX(short_id), 0) FROM tasks;",
+    )
+}
@@ -0,0 +1,122 @@
+use super::journal::OperationKind;
+use super::{TaskStorage, parse_optional_date};
+use chrono::{DateTime, Utc};
+use rusqlite::{OptionalExtension, Result as SqlResult, Row, params};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Note horodatée attachée à une task, sur une ou plusieurs lignes
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Note {
+    pub id: i64,
+    pub task_id: Uuid,
+    pub text: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+const NOTE_COLUMNS: &str = "id, task_id, text, created_at, updated_at";
+
+fn note_from_row(row: &Row) -> SqlResult<Note> {
+    let task_id: String = row.get(1)?;
+    let created_at: String = row.get(3)?;
+    let updated_at: String = row.get(4)?;
+    Ok(Note {
+        id: row.get(0)?,
+        task_id: Uuid::parse_str(&task_id).unwrap_or_default(),
+        text: row.get(2)?,
+        created_at: parse_optional_date(&created_at).unwrap_or_else(Utc::now),
+        updated_at: parse_optional_date(&updated_at).unwrap_or_else(Utc::now),
+    })
+}
+
+impl TaskStorage {
+    /// Ajoute une note à une task. Le texte est indexé pour la recherche plein texte.
+    /// L'ajout est journalisé (`undo`).
+    pub fn add_note(&self, task_id: &Uuid, text: &str) -> SqlResult<Note> {
+        let tx = self.conn.unchecked_transaction()?;
+        let batch = self.begin_batch()?;
+        let before = self.capture(task_id, OperationKind::Note)?;
+
+        let now = Utc::now().to_rfc3339();
+        self.conn.execute(
+            "INSERT INTO task_notes (task_id, text, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?3)",
+            params![task_id.to_string(), text, now],
+        )?;
+        let note = self.conn.query_row(
+            &format!("SELECT {} FROM task_notes WHERE id = ?1", NOTE_COLUMNS),
+            params![self.conn.last_insert_rowid()],
+            note_from_row,
+        )?;
+
+        let after = self.capture(task_id, OperationKind::Note)?;
+        self.record_snapshots(batch, OperationKind::Note, before.as_ref(), after.as_ref())?;
+        tx.commit()?;
+        Ok(note)
+    }
+
+    /// Récupère une note par son identifiant
+    pub fn get_note(&self, id: i64) -> SqlResult<Option<Note>> {
+        self.conn
+            .query_row(
+                &format!("SELECT {} FROM task_notes WHERE id = ?1", NOTE_COLUMNS),
+                params![id],
+                note_from_row,
+            )
+            .optional()
+    }
+
+    /// Récupère les notes d'une task, dans l'ordre chronologique
+    pub fn get_notes(&self, task_id: &Uuid) -> SqlResult<Vec<Note>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM task_notes WHERE task_id = ?1 ORDER BY id",
+            NOTE_COLUMNS
+        ))?;
+        stmt.query_map(params![task_id.to_string()], note_from_row)?
+            .collect()
+    }
+
+    /// Remplace le texte d'une note ; retourne `None` si elle n'existe pas.
+    /// La modification est journalisée (`undo`).
+    pub fn update_note(&self, id: i64, text: &str) -> SqlResult<Option<Note>> {
+        let tx = self.conn.unchecked_transaction()?;
+        let Some(note) = self.get_note(id)? else {
+            return Ok(None);
+        };
+        let batch = self.begin_batch()?;
+        let before = self.capture(&note.task_id, OperationKind::Note)?;
+
+        self.conn.execute(
+            "UPDATE task_notes SET text = ?1, updated_at = ?2 WHERE id = ?3",
+            params![text, Utc::now().to_rfc3339(), id],
+        )?;
+
+        let after = self.capture(&note.task_id, OperationKind::Note)?;
+        self.record_snapshots(batch, OperationKind::Note, before.as_ref(), after.as_ref())?;
+        tx.commit()?;
+        self.get_note(id)
+    }
+
+    /// Remplace les notes d'une task par `notes`, identifiants compris (`undo`/`redo`)
+    pub(super) fn replace_notes(&self, task_id: &Uuid, notes: &[Note]) -> SqlResult<()> {
+        self.conn.execute(
+            "DELETE FROM task_notes WHERE task_id = ?1",
+            params![task_id.to_string()],
+        )?;
+        for note in notes {
+            self.conn.execute(
+                "INSERT INTO task_notes (id, task_id, text, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    note.id,
+                    task_id.to_string(),
+                    &note.text,
+                    note.created_at.to_rfc3339(),
+                    note.updated_at.to_rfc3339(),
+                ],
+            )?;
+        }
+        Ok(())
+    }
+}
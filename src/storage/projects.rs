@@ -0,0 +1,200 @@
+use super::{Progress, TaskStorage, parse_optional_date};
+use crate::task::Task;
+use chrono::{DateTime, Utc};
+use rusqlite::{OptionalExtension, Result as SqlResult, Row, params};
+use serde::Serialize;
+use std::fmt;
+
+/// Projet regroupant des tasks
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Project {
+    pub id: i64,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+    /// Date d'archivage ; un projet archivé n'accepte plus de nouvelles tasks
+    pub archived_at: Option<DateTime<Utc>>,
+}
+
+impl Project {
+    pub fn is_archived(&self) -> bool {
+        self.archived_at.is_some()
+    }
+}
+
+/// Projet accompagné de l'avancement de ses tasks
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ProjectSummary {
+    #[serde(flatten)]
+    pub project: Project,
+    /// Tasks terminées / tasks du projet, hors annulées et corbeille
+    pub progress: Progress,
+}
+
+/// Erreurs de gestion des projets
+#[derive(Debug)]
+pub enum ProjectError {
+    /// Erreur SQLite
+    Sql(rusqlite::Error),
+    /// Aucun projet ne porte ce nom
+    NotFound(String),
+    /// Un projet porte déjà ce nom
+    AlreadyExists(String),
+    /// Le nom est vide
+    InvalidName,
+}
+
+impl fmt::Display for ProjectError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProjectError::Sql(e) => write!(f, "{}", e),
+            ProjectError::NotFound(name) => write!(f, "Projet introuvable: {}", name),
+            ProjectError::AlreadyExists(name) => write!(f, "Le projet '{}' existe déjà", name),
+            ProjectError::InvalidName => write!(f, "Le nom du projet ne peut pas être vide"),
+        }
+    }
+}
+
+impl std::error::Error for ProjectError {}
+
+impl From<rusqlite::Error> for ProjectError {
+    fn from(e: rusqlite::Error) -> Self {
+        ProjectError::Sql(e)
+    }
+}
+
+const PROJECT_COLUMNS: &str = "id, name, created_at, archived_at";
+
+fn project_from_row(row: &Row) -> SqlResult<Project> {
+    let created_at: String = row.get(2)?;
+    let archived_at: Option<String> = row.get(3)?;
+    Ok(Project {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        created_at: parse_optional_date(&created_at).unwrap_or_else(Utc::now),
+        archived_at: archived_at.as_deref().and_then(parse_optional_date),
+    })
+}
+
+/// Nettoie un nom de projet ; les noms vides sont refusés
+fn normalize_name(name: &str) -> Result<&str, ProjectError> {
+    let name = name.trim();
+    if name.is_empty() {
+        Err(ProjectError::InvalidName)
+    } else {
+        Ok(name)
+    }
+}
+
+impl TaskStorage {
+    /// Crée un projet. Les noms sont uniques sans tenir compte de la casse.
+    pub fn add_project(&self, name: &str) -> Result<Project, ProjectError> {
+        let name = normalize_name(name)?;
+        if self.get_project(name)?.is_some() {
+            return Err(ProjectError::AlreadyExists(name.to_string()));
+        }
+        self.conn.execute(
+            "INSERT INTO projects (name, created_at) VALUES (?1, ?2)",
+            params![name, Utc::now().to_rfc3339()],
+        )?;
+        self.get_project(name)?
+            .ok_or_else(|| ProjectError::NotFound(name.to_string()))
+    }
+
+    /// Récupère un projet par son nom (sans tenir compte de la casse)
+    pub fn get_project(&self, name: &str) -> SqlResult<Option<Project>> {
+        self.conn
+            .query_row(
+                &format!(
+                    "SELECT {} FROM projects WHERE name = ?1 COLLATE NOCASE",
+                    PROJECT_COLUMNS
+                ),
+                params![name.trim()],
+                project_from_row,
+            )
+            .optional()
+    }
+
+    /// Liste les projets par nom avec l'avancement de leurs tasks.
+    /// Les projets archivés ne sont inclus que si `include_archived` est vrai.
+    pub fn list_projects(&self, include_archived: bool) -> SqlResult<Vec<ProjectSummary>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT p.id, p.name, p.created_at, p.archived_at,
+                COUNT(t.id),
+                COALESCE(SUM(t.status = 'Completed'), 0)
+             FROM projects AS p
+             LEFT JOIN tasks AS t ON t.project_id = p.id
+                AND t.deleted_at IS NULL
+                AND t.status IS NOT 'Canceled'
+             WHERE ?1 OR p.archived_at IS NULL
+             GROUP BY p.id
+             ORDER BY p.name COLLATE NOCASE",
+        )?;
+        stmt.query_map(params![include_archived], |row| {
+            let total: i64 = row.get(4)?;
+            let done: i64 = row.get(5)?;
+            Ok(ProjectSummary {
+                project: project_from_row(row)?,
+                progress: Progress {
+                    done: done as usize,
+                    total: total as usize,
+                },
+            })
+        })?
+        .collect()
+    }
+
+    /// Archive un projet ; ses tasks sont conservées
+    pub fn archive_project(&self, name: &str) -> Result<Project, ProjectError> {
+        let project = self
+            .get_project(name)?
+            .ok_or_else(|| ProjectError::NotFound(name.trim().to_string()))?;
+        if project.is_archived() {
+            return Ok(project);
+        }
+        self.conn.execute(
+            "UPDATE projects SET archived_at = ?1 WHERE id = ?2",
+            params![Utc::now().to_rfc3339(), project.id],
+        )?;
+        self.get_project(&project.name)?
+            .ok_or(ProjectError::NotFound(project.name))
+    }
+
+    /// Renomme un projet ; ses tasks suivent puisqu'elles le référencent par identifiant
+    pub fn rename_project(&self, name: &str, new_name: &str) -> Result<Project, ProjectError> {
+        let new_name = normalize_name(new_name)?;
+        let project = self
+            .get_project(name)?
+            .ok_or_else(|| ProjectError::NotFound(name.trim().to_string()))?;
+        if let Some(existing) = self.get_project(new_name)?
+            && existing.id != project.id
+        {
+            return Err(ProjectError::AlreadyExists(new_name.to_string()));
+        }
+        self.conn.execute(
+            "UPDATE projects SET name = ?1 WHERE id = ?2",
+            params![new_name, project.id],
+        )?;
+        self.get_project(new_name)?
+            .ok_or_else(|| ProjectError::NotFound(new_name.to_string()))
+    }
+
+    /// Identifiant du projet d'une task d'après son nom, créé s'il n'existe pas encore (import).
+    /// `undo`/`redo` retrouvent le projet par son id et ne passent ici que pour les états
+    /// enregistrés sans id de projet.
+    pub(super) fn project_id_for(&self, task: &Task) -> SqlResult<Option<i64>> {
+        let Some(name) = task.project.as_deref().map(str::trim) else {
+            return Ok(None);
+        };
+        if name.is_empty() {
+            return Ok(None);
+        }
+        if let Some(project) = self.get_project(name)? {
+            return Ok(Some(project.id));
+        }
+        self.conn.execute(
+            "INSERT INTO projects (name, created_at) VALUES (?1, ?2)",
+            params![name, Utc::now().to_rfc3339()],
+        )?;
+        Ok(Some(self.conn.last_insert_rowid()))
+    }
+}
@@ -0,0 +1,487 @@
+use super::dependencies::OPEN_BLOCKER;
+use super::search::fts_query;
+use super::{TASK_COLUMNS, TaskStorage, priority_to_string, status_to_string};
+use crate::dates::local_day_start;
+use crate::filter::{CompareOp, Condition, Filter};
+use crate::task::{Priority, Status, Task};
+use chrono::{DateTime, Days, NaiveDate, Utc};
+use rusqlite::{Result as SqlResult, params_from_iter, types::Value};
+
+/// Combinaison des tags d'une requête
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TagMatch {
+    /// Au moins un des tags
+    #[default]
+    Any,
+    /// Tous les tags
+    All,
+}
+
+/// Champ de tri des tasks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortField {
+    ShortId,
+    Description,
+    Priority,
+    Status,
+    DueDate,
+    CreatedAt,
+    UpdatedAt,
+}
+
+const SORT_FIELDS: &str = "id, description, priority, status, due, created ou updated, précédé de - pour l'ordre décroissant";
+
+/// Critère de tri : un champ et un sens
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SortKey {
+    pub field: SortField,
+    pub descending: bool,
+}
+
+impl SortKey {
+    pub fn asc(field: SortField) -> Self {
+        SortKey {
+            field,
+            descending: false,
+        }
+    }
+
+    pub fn desc(field: SortField) -> Self {
+        SortKey {
+            field,
+            descending: true,
+        }
+    }
+
+    /// Analyse une liste de critères séparés par des virgules (`due,-priority,description`).
+    /// Un `-` devant le champ inverse l'ordre ; un `+` est accepté pour l'ordre croissant.
+    pub fn parse_list(input: &str) -> Result<Vec<SortKey>, String> {
+        let keys: Vec<&str> = input.split(',').map(str::trim).collect();
+        if keys.iter().all(|key| key.is_empty()) {
+            return Err(format!("Aucun critère de tri. Utilisez: {}", SORT_FIELDS));
+        }
+
+        keys.into_iter()
+            .filter(|key| !key.is_empty())
+            .map(|key| {
+                let (name, descending) = match key.strip_prefix('-') {
+                    Some(name) => (name, true),
+                    None => (key.strip_prefix('+').unwrap_or(key), false),
+                };
+                let field = match name.trim().to_lowercase().as_str() {
+                    "id" | "short_id" => SortField::ShortId,
+                    "description" => SortField::Description,
+                    "priority" => SortField::Priority,
+                    "status" => SortField::Status,
+                    "due" | "due_date" => SortField::DueDate,
+                    "created" | "created_at" => SortField::CreatedAt,
+                    "updated" | "updated_at" => SortField::UpdatedAt,
+                    _ => {
+                        return Err(format!(
+                            "Critère de tri invalide '{}'. Utilisez: {}",
+                            key, SORT_FIELDS
+                        ));
+                    }
+                };
+                Ok(SortKey { field, descending })
+            })
+            .collect()
+    }
+
+    /// Expression SQL du champ. La priorité et le statut suivent leur ordre
+    /// logique (Low < Medium < High) et non l'ordre alphabétique.
+    fn sql(&self) -> &'static str {
+        match self.field {
+            SortField::ShortId => "short_id",
+            SortField::Description => "description COLLATE NOCASE",
+            SortField::Priority => PRIORITY_RANK,
+            SortField::Status => {
+                "CASE status WHEN 'InProgress' THEN 1 WHEN 'Completed' THEN 2
+                    WHEN 'Canceled' THEN 3 ELSE 0 END"
+            }
+            SortField::DueDate => "due_date",
+            SortField::CreatedAt => "created_at",
+            SortField::UpdatedAt => "updated_at",
+        }
+    }
+}
+
+/// Filtre de tasks, compilé en une seule requête SQL par `TaskStorage::query_tasks`.
+/// Les critères renseignés sont combinés par ET ; les tasks de la corbeille sont exclues.
+///
+/// ```
+/// use task_cli::storage::{SortField, SortKey, TaskQuery};
+/// use task_cli::task::{Priority, Status};
+///
+/// let query = TaskQuery::new()
+///     .status(Status::InProgress)
+///     .priority(Priority::High)
+///     .tag("work")
+///     .sort_by(SortKey::asc(SortField::DueDate))
+///     .limit(10);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TaskQuery {
+    /// Statuts acceptés (vide : tous)
+    pub statuses: Vec<Status>,
+    /// Priorités acceptées (vide : toutes)
+    pub priorities: Vec<Priority>,
+    /// Tags recherchés, sans tenir compte de la casse
+    pub tags: Vec<String>,
+    pub tag_match: TagMatch,
+    /// Échéance au plus tôt (incluse) ; les tasks sans échéance sont exclues
+    pub due_after: Option<DateTime<Utc>>,
+    /// Échéance au plus tard (exclue) ; les tasks sans échéance sont exclues
+    pub due_before: Option<DateTime<Utc>>,
+    /// Recherche plein texte dans la description (mots, "phrases", préfixes*,
+    /// AND/OR/NOT) ; les résultats sont classés par pertinence
+    pub text: Option<String>,
+    /// Critères de tri (vide : pertinence, puis date de création décroissante)
+    pub sort: Vec<SortKey>,
+    /// Expression de filtre (voir le module `filter`)
+    pub filter: Option<Filter>,
+    /// Uniquement les tasks à faire (ni complétées ni annulées) sans dépendance ouverte
+    pub ready: bool,
+    /// Nom du projet, sans tenir compte de la casse
+    pub project: Option<String>,
+    pub limit: Option<u32>,
+    pub offset: Option<u32>,
+}
+
+impl TaskQuery {
+    pub fn new() -> Self {
+        TaskQuery::default()
+    }
+
+    pub fn status(mut self, status: Status) -> Self {
+        self.statuses.push(status);
+        self
+    }
+
+    pub fn priority(mut self, priority: Priority) -> Self {
+        self.priorities.push(priority);
+        self
+    }
+
+    pub fn tag(mut self, tag: &str) -> Self {
+        self.tags.push(tag.to_string());
+        self
+    }
+
+    pub fn tag_match(mut self, tag_match: TagMatch) -> Self {
+        self.tag_match = tag_match;
+        self
+    }
+
+    pub fn due_after(mut self, date: DateTime<Utc>) -> Self {
+        self.due_after = Some(date);
+        self
+    }
+
+    pub fn due_before(mut self, date: DateTime<Utc>) -> Self {
+        self.due_before = Some(date);
+        self
+    }
+
+    pub fn text(mut self, text: &str) -> Self {
+        self.text = Some(text.to_string());
+        self
+    }
+
+    pub fn ready(mut self) -> Self {
+        self.ready = true;
+        self
+    }
+
+    pub fn project(mut self, name: &str) -> Self {
+        self.project = Some(name.trim().to_string());
+        self
+    }
+
+    pub fn sort_by(mut self, key: SortKey) -> Self {
+        self.sort.push(key);
+        self
+    }
+
+    pub fn limit(mut self, limit: u32) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    pub fn offset(mut self, offset: u32) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+
+    /// Ajoute une expression de filtre, combinée par ET avec la précédente
+    pub fn filter(mut self, filter: Filter) -> Self {
+        self.filter = Some(match self.filter.take() {
+            Some(previous) => previous.and(filter),
+            None => filter,
+        });
+        self
+    }
+
+    /// Requête FTS5 correspondant à `text`, si elle contient au moins un terme
+    pub(super) fn fts_match(&self) -> Option<String> {
+        self.text.as_deref().and_then(fts_query)
+    }
+
+    /// Construit la requête SQL et ses paramètres. `extra_columns` est ajouté
+    /// après `TASK_COLUMNS` dans la sélection.
+    pub(super) fn to_sql(&self, extra_columns: &str) -> (String, Vec<Value>) {
+        let mut from = "tasks".to_string();
+        let mut conditions = vec!["deleted_at IS NULL".to_string()];
+        let mut values = Vec::new();
+
+        // L'index plein texte est indexé par ID court
+        let fts_match = self.fts_match();
+        if let Some(fts_match) = &fts_match {
+            from.push_str(" JOIN tasks_fts ON tasks_fts.rowid = tasks.short_id");
+            conditions.push("tasks_fts MATCH ?".to_string());
+            values.push(Value::Text(fts_match.clone()));
+        }
+
+        if !self.statuses.is_empty() {
+            conditions.push(format!(
+                "{} IN ({})",
+                EFFECTIVE_STATUS,
+                placeholders(self.statuses.len())
+            ));
+            values.extend(
+                self.statuses
+                    .iter()
+                    .map(|s| Value::Text(status_to_string(s).to_string())),
+            );
+        }
+
+        if !self.priorities.is_empty() {
+            conditions.push(format!(
+                "priority IN ({})",
+                placeholders(self.priorities.len())
+            ));
+            values.extend(
+                self.priorities
+                    .iter()
+                    .map(|p| Value::Text(priority_to_string(p).to_string())),
+            );
+        }
+
+        let mut tags: Vec<String> = self.tags.iter().map(|t| t.to_lowercase()).collect();
+        tags.sort();
+        tags.dedup();
+        if !tags.is_empty() {
+            let condition = match self.tag_match {
+                TagMatch::Any => format!(
+                    "id IN (SELECT task_id FROM task_tags WHERE tag COLLATE NOCASE IN ({}))",
+                    placeholders(tags.len())
+                ),
+                TagMatch::All => format!(
+                    "(SELECT COUNT(DISTINCT lower(tag)) FROM task_tags
+                      WHERE task_id = tasks.id AND tag COLLATE NOCASE IN ({})) = {}",
+                    placeholders(tags.len()),
+                    tags.len()
+                ),
+            };
+            conditions.push(condition);
+            values.extend(tags.into_iter().map(Value::Text));
+        }
+
+        if let Some(filter) = &self.filter {
+            conditions.push(filter_sql(filter, &mut values));
+        }
+
+        if self.ready {
+            conditions.push(format!(
+                "status IS NOT 'Completed' AND status IS NOT 'Canceled'
+                 AND NOT EXISTS (SELECT 1 FROM task_dependencies
+                     JOIN tasks AS blocker ON blocker.id = task_dependencies.depends_on
+                     WHERE task_dependencies.task_id = tasks.id AND {})",
+                OPEN_BLOCKER
+            ));
+        }
+
+        if let Some(project) = &self.project {
+            conditions.push(
+                "project_id = (SELECT id FROM projects WHERE name = ? COLLATE NOCASE)".to_string(),
+            );
+            values.push(Value::Text(project.clone()));
+        }
+
+        if let Some(date) = self.due_after {
+            conditions.push("due_date >= ?".to_string());
+            values.push(Value::Text(date.to_rfc3339()));
+        }
+
+        if let Some(date) = self.due_before {
+            conditions.push("due_date < ?".to_string());
+            values.push(Value::Text(date.to_rfc3339()));
+        }
+
+        let mut order: Vec<String> = self
+            .sort
+            .iter()
+            .map(|key| {
+                let direction = if key.descending { "DESC" } else { "ASC" };
+                match key.field {
+                    // Les tasks sans échéance sont placées en dernier, quel que soit le sens
+                    SortField::DueDate => format!("due_date IS NULL, due_date {}", direction),
+                    _ => format!("{} {}", key.sql(), direction),
+                }
+            })
+            .collect();
+        if fts_match.is_some() {
+            order.push("bm25(tasks_fts) ASC".to_string());
+        }
+        if self.sort.is_empty() {
+            order.push("created_at DESC".to_string());
+        }
+        // Départage stable entre tasks égales
+        order.push("short_id ASC".to_string());
+
+        let columns = match extra_columns {
+            "" => TASK_COLUMNS.to_string(),
+            extra => format!("{}, {}", TASK_COLUMNS, extra),
+        };
+        let mut sql = format!(
+            "SELECT {} FROM {} WHERE {} ORDER BY {}",
+            columns,
+            from,
+            conditions.join(" AND "),
+            order.join(", ")
+        );
+
+        if self.limit.is_some() || self.offset.is_some() {
+            // SQLite n'accepte OFFSET qu'après LIMIT ; -1 signifie sans limite
+            sql.push_str(" LIMIT ? OFFSET ?");
+            values.push(Value::Integer(self.limit.map_or(-1, i64::from)));
+            values.push(Value::Integer(i64::from(self.offset.unwrap_or(0))));
+        }
+
+        (sql, values)
+    }
+}
+
+/// Statut effectif : une task sans statut est NotStarted, comme à l'affichage et dans `report`
+const EFFECTIVE_STATUS: &str = "COALESCE(status, 'NotStarted')";
+
+/// Rang de la priorité, dans l'ordre logique Low < Medium < High
+const PRIORITY_RANK: &str = "CASE priority WHEN 'High' THEN 3 WHEN 'Medium' THEN 2 ELSE 1 END";
+
+fn priority_rank(priority: &Priority) -> i64 {
+    match priority {
+        Priority::High => 3,
+        Priority::Medium => 2,
+        Priority::Low => 1,
+    }
+}
+
+fn compare_sql(op: CompareOp) -> &'static str {
+    match op {
+        CompareOp::Eq => "=",
+        CompareOp::Ne => "!=",
+        CompareOp::Lt => "<",
+        CompareOp::Le => "<=",
+        CompareOp::Gt => ">",
+        CompareOp::Ge => ">=",
+    }
+}
+
+/// Début du jour dans le fuseau local, au format de stockage des dates
+fn day_start(date: NaiveDate) -> Value {
+    Value::Text(local_day_start(date).to_rfc3339())
+}
+
+/// Compile une expression de filtre en condition SQL, en ajoutant ses paramètres à `values`
+fn filter_sql(filter: &Filter, values: &mut Vec<Value>) -> String {
+    match filter {
+        Filter::And(left, right) => format!(
+            "({} AND {})",
+            filter_sql(left, values),
+            filter_sql(right, values)
+        ),
+        Filter::Or(left, right) => format!(
+            "({} OR {})",
+            filter_sql(left, values),
+            filter_sql(right, values)
+        ),
+        Filter::Not(inner) => format!("NOT {}", filter_sql(inner, values)),
+        Filter::Condition(condition) => condition_sql(condition, values),
+    }
+}
+
+fn condition_sql(condition: &Condition, values: &mut Vec<Value>) -> String {
+    match condition {
+        Condition::Status(op, status) => {
+            values.push(Value::Text(status_to_string(status).to_string()));
+            match op {
+                CompareOp::Ne => format!("{} != ?", EFFECTIVE_STATUS),
+                _ => format!("{} = ?", EFFECTIVE_STATUS),
+            }
+        }
+        Condition::Priority(op, priority) => {
+            values.push(Value::Integer(priority_rank(priority)));
+            format!("({}) {} ?", PRIORITY_RANK, compare_sql(*op))
+        }
+        Condition::Tag(op, tag) => {
+            values.push(Value::Text(tag.clone()));
+            format!(
+                "id {}IN (SELECT task_id FROM task_tags WHERE tag = ? COLLATE NOCASE)",
+                if *op == CompareOp::Ne { "NOT " } else { "" }
+            )
+        }
+        Condition::Due(op, None) => match op {
+            CompareOp::Ne => "due_date IS NOT NULL".to_string(),
+            _ => "due_date IS NULL".to_string(),
+        },
+        Condition::Due(op, Some(date)) => {
+            // Comparaison au jour près : [début du jour, début du lendemain[.
+            // Les tasks sans échéance ne sont ni avant ni après une date, mais `!=` les inclut.
+            let next_day = date.checked_add_days(Days::new(1)).unwrap_or(*date);
+            match op {
+                CompareOp::Eq => {
+                    values.push(day_start(*date));
+                    values.push(day_start(next_day));
+                    "(due_date >= ? AND due_date < ?)".to_string()
+                }
+                CompareOp::Ne => {
+                    values.push(day_start(*date));
+                    values.push(day_start(next_day));
+                    "(due_date IS NULL OR NOT (due_date >= ? AND due_date < ?))".to_string()
+                }
+                CompareOp::Lt | CompareOp::Ge => {
+                    values.push(day_start(*date));
+                    format!("(due_date IS NOT NULL AND due_date {} ?)", compare_sql(*op))
+                }
+                CompareOp::Le => {
+                    values.push(day_start(next_day));
+                    "(due_date IS NOT NULL AND due_date < ?)".to_string()
+                }
+                CompareOp::Gt => {
+                    values.push(day_start(next_day));
+                    "(due_date IS NOT NULL AND due_date >= ?)".to_string()
+                }
+            }
+        }
+        Condition::Text(text) => match fts_query(text) {
+            Some(fts_match) => {
+                values.push(Value::Text(fts_match));
+                "short_id IN (SELECT rowid FROM tasks_fts WHERE tasks_fts MATCH ?)".to_string()
+            }
+            None => "1".to_string(),
+        },
+    }
+}
+
+/// Liste de `n` paramètres positionnels : `?, ?, ?`
+fn placeholders(n: usize) -> String {
+    vec!["?"; n].join(", ")
+}
+
+impl TaskStorage {
+    /// Récupère les tasks correspondant à une requête
+    pub fn query_tasks(&self, query: &TaskQuery) -> SqlResult<Vec<Task>> {
+        let (sql, values) = query.to_sql("");
+        self.fetch_tasks(&sql, params_from_iter(values))
+    }
+}
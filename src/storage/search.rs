@@ -0,0 +1,356 @@
+use super::{TASK_COLUMN_COUNT, TaskQuery, TaskStorage, task_from_row};
+use crate::filter::FilterError;
+use crate::task::Task;
+use rusqlite::{Result as SqlResult, params_from_iter};
+use serde::Serialize;
+use std::ops::Range;
+
+/// Marqueurs de début et de fin des passages trouvés, insérés par `highlight()`
+const MATCH_START: char = '\u{2}';
+const MATCH_END: char = '\u{3}';
+
+/// Résultat d'une recherche plein texte
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchHit {
+    pub task: Task,
+    /// Score bm25 : plus il est bas, plus la tâche est pertinente
+    pub score: f64,
+    /// Passages de la description correspondant à la recherche (positions en octets)
+    pub highlights: Vec<Range<usize>>,
+    /// Extrait des notes de la tâche, si la recherche y a trouvé un passage
+    pub note_excerpt: Option<String>,
+    /// Passages de `note_excerpt` correspondant à la recherche (positions en octets)
+    pub note_highlights: Vec<Range<usize>>,
+}
+
+/// Élément d'une recherche saisie par l'utilisateur
+#[derive(Debug, Clone, PartialEq)]
+enum SearchToken {
+    /// Terme FTS5 entre guillemets
+    Term(String),
+    /// `AND`, `OR` ou `NOT`
+    Op(String),
+    LParen,
+    RParen,
+}
+
+/// Découpe la saisie en éléments, avec leur position (en caractères, à partir de 0)
+fn search_tokens(input: &str) -> Vec<(SearchToken, usize)> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let position = i;
+        let token = match chars[i] {
+            c if c.is_whitespace() => {
+                i += 1;
+                continue;
+            }
+            '(' => {
+                i += 1;
+                SearchToken::LParen
+            }
+            ')' => {
+                i += 1;
+                SearchToken::RParen
+            }
+            '"' => {
+                // Une phrase sans guillemet fermant s'étend jusqu'à la fin de la saisie
+                let end = chars[i + 1..]
+                    .iter()
+                    .position(|&c| c == '"')
+                    .map_or(chars.len(), |offset| i + 1 + offset);
+                let phrase: String = chars[i + 1..end].iter().collect();
+                i = (end + 1).min(chars.len());
+                let prefix = chars.get(i) == Some(&'*');
+                if prefix {
+                    i += 1;
+                }
+                SearchToken::Term(quote(&phrase, prefix))
+            }
+            _ => {
+                while i < chars.len()
+                    && !chars[i].is_whitespace()
+                    && !matches!(chars[i], '(' | ')' | '"')
+                {
+                    i += 1;
+                }
+                let word: String = chars[position..i].iter().collect();
+                match word.as_str() {
+                    "AND" | "OR" | "NOT" => SearchToken::Op(word),
+                    _ => SearchToken::Term(match word.strip_suffix('*') {
+                        Some(stem) if !stem.is_empty() => quote(stem, true),
+                        _ => quote(&word, false),
+                    }),
+                }
+            }
+        };
+        tokens.push((token, position));
+    }
+    tokens
+}
+
+fn error(position: usize, message: impl Into<String>) -> Result<(), FilterError> {
+    Err(FilterError {
+        position,
+        message: message.into(),
+    })
+}
+
+/// Vérifie la place des opérateurs et des parenthèses d'une recherche : un opérateur
+/// doit être entouré de termes, et les parenthèses doivent être équilibrées et non vides.
+pub fn check_search(input: &str) -> Result<(), FilterError> {
+    check_tokens(&search_tokens(input))
+}
+
+fn check_tokens(tokens: &[(SearchToken, usize)]) -> Result<(), FilterError> {
+    let mut open = Vec::new();
+    let mut previous: Option<&(SearchToken, usize)> = None;
+
+    for entry in tokens {
+        let (token, position) = entry;
+        match (token, previous) {
+            (SearchToken::Op(op), None | Some((SearchToken::LParen, _))) => {
+                return error(*position, format!("Opérateur '{}' sans terme à gauche", op));
+            }
+            (SearchToken::Op(op), Some((SearchToken::Op(before), _))) => {
+                return error(
+                    *position,
+                    format!("Opérateur '{}' inattendu après '{}'", op, before),
+                );
+            }
+            (SearchToken::LParen, _) => open.push(*position),
+            (SearchToken::RParen, _) if open.pop().is_none() => {
+                return error(*position, "Parenthèse fermante sans parenthèse ouvrante");
+            }
+            (SearchToken::RParen, Some((SearchToken::Op(op), op_position))) => {
+                return error(
+                    *op_position,
+                    format!("Opérateur '{}' sans terme à droite", op),
+                );
+            }
+            (SearchToken::RParen, Some((SearchToken::LParen, lparen_position))) => {
+                return error(*lparen_position, "Parenthèses vides");
+            }
+            _ => {}
+        }
+        previous = Some(entry);
+    }
+
+    if let Some((SearchToken::Op(op), position)) = previous {
+        return error(*position, format!("Opérateur '{}' sans terme à droite", op));
+    }
+    if let Some(position) = open.pop() {
+        return error(position, "Parenthèse fermante manquante");
+    }
+    Ok(())
+}
+
+/// Traduit la saisie de l'utilisateur en requête FTS5.
+///
+/// Les mots et les phrases entre guillemets sont recherchés tels quels, un `*` final
+/// active la recherche par préfixe, et `AND`, `OR`, `NOT` et les parenthèses sont
+/// conservés comme opérateurs. Chaque terme est placé entre guillemets pour que la
+/// ponctuation saisie (`-`, `:`, `+`...) ne soit pas interprétée par FTS5, et un `AND`
+/// est ajouté entre un groupe entre parenthèses et un terme voisin (ET implicite).
+/// Une saisie refusée par `check_search` est recherchée sans ses opérateurs ni ses
+/// parenthèses. Retourne `None` si la saisie ne contient aucun terme.
+pub(super) fn fts_query(input: &str) -> Option<String> {
+    let tokens = search_tokens(input);
+    let valid = check_tokens(&tokens).is_ok();
+
+    let mut terms: Vec<String> = Vec::new();
+    let mut previous: Option<&SearchToken> = None;
+    for (token, _) in &tokens {
+        let implicit_and = matches!(
+            (previous, token),
+            (
+                Some(SearchToken::Term(_) | SearchToken::RParen),
+                SearchToken::LParen
+            ) | (Some(SearchToken::RParen), SearchToken::Term(_))
+        );
+        match token {
+            SearchToken::Term(term) => {
+                if valid && implicit_and {
+                    terms.push("AND".to_string());
+                }
+                terms.push(term.clone());
+            }
+            _ if !valid => continue,
+            SearchToken::Op(op) => terms.push(op.clone()),
+            SearchToken::LParen => {
+                if implicit_and {
+                    terms.push("AND".to_string());
+                }
+                terms.push("(".to_string());
+            }
+            SearchToken::RParen => terms.push(")".to_string()),
+        }
+        previous = Some(token);
+    }
+
+    let has_term = terms.iter().any(|t| t.starts_with('"'));
+    has_term.then(|| terms.join(" "))
+}
+
+/// Chaîne FTS5 entre guillemets, avec `*` pour une recherche par préfixe
+fn quote(term: &str, prefix: bool) -> String {
+    format!(
+        "\"{}\"{}",
+        term.replace('"', "\"\""),
+        if prefix { "*" } else { "" }
+    )
+}
+
+/// Retire les marqueurs de `highlight()` et retourne les positions des passages marqués
+fn parse_highlights(marked: &str) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    let mut len = 0;
+    let mut start = None;
+
+    for c in marked.chars() {
+        match c {
+            MATCH_START => start = Some(len),
+            MATCH_END => {
+                if let Some(start) = start.take() {
+                    ranges.push(start..len);
+                }
+            }
+            _ => len += c.len_utf8(),
+        }
+    }
+    ranges
+}
+
+impl TaskStorage {
+    /// Recherche plein texte : comme `query_tasks`, avec le score bm25 et les passages
+    /// trouvés de chaque tâche. Sans texte recherché, le score vaut 0.
+    pub fn search_tasks(&self, query: &TaskQuery) -> SqlResult<Vec<SearchHit>> {
+        if query.fts_match().is_none() {
+            return Ok(self
+                .query_tasks(query)?
+                .into_iter()
+                .map(|task| SearchHit {
+                    task,
+                    score: 0.0,
+                    highlights: Vec::new(),
+                    note_excerpt: None,
+                    note_highlights: Vec::new(),
+                })
+                .collect());
+        }
+
+        let (sql, values) = query.to_sql(
+            "bm25(tasks_fts), highlight(tasks_fts, 0, char(2), char(3)),
+             snippet(tasks_fts, 1, char(2), char(3), '…', 12)",
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map(params_from_iter(values), |row| {
+            Ok((
+                task_from_row(row)?,
+                row.get::<_, f64>(TASK_COLUMN_COUNT)?,
+                row.get::<_, String>(TASK_COLUMN_COUNT + 1)?,
+                row.get::<_, Option<String>>(TASK_COLUMN_COUNT + 2)?,
+            ))
+        })?;
+
+        let mut tasks = Vec::new();
+        let mut hits = Vec::new();
+        for row in rows {
+            let (task, score, marked, note_snippet) = row?;
+            tasks.push(task);
+
+            // L'extrait des notes n'est conservé que s'il contient un passage trouvé
+            let (note_excerpt, note_highlights) = match note_snippet {
+                Some(snippet) if snippet.contains(MATCH_START) => (
+                    Some(snippet.replace([MATCH_START, MATCH_END], "")),
+                    parse_highlights(&snippet),
+                ),
+                _ => (None, Vec::new()),
+            };
+            hits.push((
+                score,
+                parse_highlights(&marked),
+                note_excerpt,
+                note_highlights,
+            ));
+        }
+        self.load_tags(&mut tasks)?;
+        self.load_dependencies(&mut tasks)?;
+
+        Ok(tasks
+            .into_iter()
+            .zip(hits)
+            .map(
+                |(task, (score, highlights, note_excerpt, note_highlights))| SearchHit {
+                    task,
+                    score,
+                    highlights,
+                    note_excerpt,
+                    note_highlights,
+                },
+            )
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fts_query_translation() {
+        assert_eq!(fts_query("rapport"), Some("\"rapport\"".to_string()));
+        assert_eq!(
+            fts_query("\"revue de code\" OR api*"),
+            Some("\"revue de code\" OR \"api\"*".to_string())
+        );
+        assert_eq!(
+            fts_query("(bug OR fix) NOT c++"),
+            Some("( \"bug\" OR \"fix\" ) NOT \"c++\"".to_string())
+        );
+        assert_eq!(fts_query("   "), None);
+        assert_eq!(fts_query("AND"), None);
+        assert_eq!(
+            fts_query("api (bug OR fix) doc"),
+            Some("\"api\" AND ( \"bug\" OR \"fix\" ) AND \"doc\"".to_string())
+        );
+        // Les opérateurs d'une saisie invalide ne sont pas transmis à FTS5
+        assert_eq!(fts_query("NOT T1"), Some("\"T1\"".to_string()));
+    }
+
+    #[test]
+    fn test_check_search_operators() {
+        assert!(check_search("(bug OR fix) NOT \"c++\"").is_ok());
+        assert!(check_search("").is_ok());
+
+        let position = |input: &str| check_search(input).unwrap_err().position;
+        assert_eq!(position("NOT T1"), 0);
+        assert_eq!(position("bug OR"), 4);
+        assert_eq!(position("bug AND OR fix"), 8);
+        assert_eq!(position("(OR fix)"), 1);
+        assert_eq!(position("(bug OR) fix"), 5);
+        assert_eq!(position("bug ()"), 4);
+        assert_eq!(position("bug) fix"), 3);
+        assert_eq!(position("(bug OR fix"), 0);
+        assert_eq!(
+            check_search("bug AND").unwrap_err().message,
+            "Opérateur 'AND' sans terme à droite"
+        );
+    }
+
+    #[test]
+    fn test_parse_highlights() {
+        let marked = format!(
+            "Revue {}API{} à {}faire{}",
+            MATCH_START, MATCH_END, MATCH_START, MATCH_END
+        );
+        let ranges = parse_highlights(&marked);
+        let text = "Revue API à faire";
+        assert_eq!(
+            ranges.iter().map(|r| &text[r.clone()]).collect::<Vec<_>>(),
+            vec!["API", "faire"]
+        );
+    }
+}
@@ -0,0 +1,335 @@
+use super::journal::OperationKind;
+use super::{TASK_COLUMNS, TaskStorage, parse_optional_date};
+use crate::task::{Status, Task};
+use chrono::{DateTime, Duration, Utc};
+use rusqlite::{OptionalExtension, Result as SqlResult, Row, params};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// Intervalle de travail sur une task
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimeEntry {
+    pub id: i64,
+    pub task_id: Uuid,
+    pub started_at: DateTime<Utc>,
+    /// Fin de l'intervalle, `None` tant qu'il est en cours
+    pub ended_at: Option<DateTime<Utc>>,
+}
+
+impl TimeEntry {
+    pub fn is_running(&self) -> bool {
+        self.ended_at.is_none()
+    }
+
+    /// Durée de l'intervalle ; un intervalle en cours est compté jusqu'à `now`
+    pub fn duration(&self, now: DateTime<Utc>) -> Duration {
+        self.ended_at.unwrap_or(now) - self.started_at
+    }
+
+    /// Durée de la partie de l'intervalle comprise dans `[since, until[`
+    fn duration_within(
+        &self,
+        since: Option<DateTime<Utc>>,
+        until: DateTime<Utc>,
+        now: DateTime<Utc>,
+    ) -> Duration {
+        let start = since.map_or(self.started_at, |since| self.started_at.max(since));
+        let end = self.ended_at.unwrap_or(now).min(until);
+        (end - start).max(Duration::zero())
+    }
+}
+
+/// Regroupement des durées d'un rapport
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportGroup {
+    Task,
+    Tag,
+    Project,
+}
+
+impl ReportGroup {
+    /// Convertit une chaîne (`task`, `tag` ou `project`) en regroupement
+    pub fn parse(input: &str) -> Result<ReportGroup, String> {
+        match input.trim().to_lowercase().as_str() {
+            "task" => Ok(ReportGroup::Task),
+            "tag" => Ok(ReportGroup::Tag),
+            "project" => Ok(ReportGroup::Project),
+            _ => Err(format!(
+                "Regroupement invalide '{}'. Utilisez: task, tag ou project",
+                input.trim()
+            )),
+        }
+    }
+}
+
+/// Ligne d'un rapport de temps
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TimeReportLine {
+    /// Task (`[3] Description`), tag ou projet ; `None` pour les tasks sans tag ou sans projet
+    pub label: Option<String>,
+    pub seconds: i64,
+}
+
+/// Temps passé sur une période, par task, tag ou projet
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TimeReport {
+    pub since: Option<DateTime<Utc>>,
+    pub until: DateTime<Utc>,
+    /// Lignes par durée décroissante
+    pub lines: Vec<TimeReportLine>,
+    /// Temps total ; une task à plusieurs tags n'y est comptée qu'une fois
+    pub total_seconds: i64,
+}
+
+const ENTRY_COLUMNS: &str = "id, task_id, started_at, ended_at";
+
+fn entry_from_row(row: &Row) -> SqlResult<TimeEntry> {
+    let task_id: String = row.get(1)?;
+    let started_at: String = row.get(2)?;
+    let ended_at: Option<String> = row.get(3)?;
+    Ok(TimeEntry {
+        id: row.get(0)?,
+        task_id: Uuid::parse_str(&task_id).unwrap_or_default(),
+        started_at: parse_optional_date(&started_at).unwrap_or_else(Utc::now),
+        ended_at: ended_at.as_deref().and_then(parse_optional_date),
+    })
+}
+
+impl TaskStorage {
+    /// Intervalle en cours, s'il y en a un
+    pub fn running_entry(&self) -> SqlResult<Option<TimeEntry>> {
+        self.conn
+            .query_row(
+                &format!(
+                    "SELECT {} FROM time_entries WHERE ended_at IS NULL",
+                    ENTRY_COLUMNS
+                ),
+                [],
+                entry_from_row,
+            )
+            .optional()
+    }
+
+    /// Intervalles d'une task, dans l'ordre chronologique
+    pub fn get_time_entries(&self, task_id: &Uuid) -> SqlResult<Vec<TimeEntry>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM time_entries WHERE task_id = ?1 ORDER BY started_at",
+            ENTRY_COLUMNS
+        ))?;
+        stmt.query_map(params![task_id.to_string()], entry_from_row)?
+            .collect()
+    }
+
+    /// Démarre le suivi du temps d'une task et la passe au statut `InProgress`.
+    /// Une seule task est suivie à la fois : l'intervalle en cours d'une autre task est
+    /// arrêté et retourné. Sans effet si la task est déjà suivie.
+    /// L'arrêt, le démarrage et le changement de statut forment une seule opération (`undo`).
+    pub fn start_task(&mut self, task: &Task) -> SqlResult<Option<TimeEntry>> {
+        let tx = self.conn.unchecked_transaction()?;
+        let now = Utc::now();
+
+        let running = self.running_entry()?;
+        if running.as_ref().is_some_and(|e| e.task_id == task.id) {
+            return Ok(None);
+        }
+        let batch = self.begin_batch()?;
+        let stopped = match running {
+            Some(entry) => self.stop_running(batch, Some(&entry.task_id), now)?,
+            None => None,
+        };
+
+        let before = self.capture(&task.id, OperationKind::TimeTracking)?;
+        self.conn.execute(
+            "INSERT INTO time_entries (task_id, started_at) VALUES (?1, ?2)",
+            params![task.id.to_string(), now.to_rfc3339()],
+        )?;
+        let after = self.capture(&task.id, OperationKind::TimeTracking)?;
+        self.record_snapshots(
+            batch,
+            OperationKind::TimeTracking,
+            before.as_ref(),
+            after.as_ref(),
+        )?;
+
+        if let Some(before) = self.get_task(&task.id)?
+            && before.status != Some(Status::InProgress)
+        {
+            let mut after = before.clone();
+            after.set_status(Some(Status::InProgress));
+            self.rewrite_task(batch, &before, &after)?;
+        }
+
+        tx.commit()?;
+        Ok(stopped)
+    }
+
+    /// Arrête l'intervalle en cours et le retourne ; `None` si aucune task n'est suivie
+    pub fn stop_task(&mut self) -> SqlResult<Option<TimeEntry>> {
+        let tx = self.conn.unchecked_transaction()?;
+        if self.running_entry()?.is_none() {
+            return Ok(None);
+        }
+        let batch = self.begin_batch()?;
+        let stopped = self.stop_running(batch, None, Utc::now())?;
+        tx.commit()?;
+        Ok(stopped)
+    }
+
+    /// Arrête l'intervalle en cours à `at`, s'il concerne `task_id` (ou n'importe quelle
+    /// task si `task_id` est `None`), dans l'opération `batch`, sans transaction
+    pub(super) fn stop_running(
+        &self,
+        batch: i64,
+        task_id: Option<&Uuid>,
+        at: DateTime<Utc>,
+    ) -> SqlResult<Option<TimeEntry>> {
+        let Some(entry) = self.running_entry()? else {
+            return Ok(None);
+        };
+        if task_id.is_some_and(|id| *id != entry.task_id) {
+            return Ok(None);
+        }
+
+        let before = self.capture(&entry.task_id, OperationKind::TimeTracking)?;
+        // Un intervalle ne se termine jamais avant d'avoir commencé
+        let ended_at = at.max(entry.started_at);
+        self.conn.execute(
+            "UPDATE time_entries SET ended_at = ?1 WHERE id = ?2",
+            params![ended_at.to_rfc3339(), entry.id],
+        )?;
+        let after = self.capture(&entry.task_id, OperationKind::TimeTracking)?;
+        self.record_snapshots(
+            batch,
+            OperationKind::TimeTracking,
+            before.as_ref(),
+            after.as_ref(),
+        )?;
+
+        Ok(Some(TimeEntry {
+            ended_at: Some(ended_at),
+            ..entry
+        }))
+    }
+
+    /// Remplace les intervalles d'une task par `entries`, identifiants compris (`undo`/`redo`)
+    pub(super) fn replace_time_entries(
+        &self,
+        task_id: &Uuid,
+        entries: &[TimeEntry],
+    ) -> SqlResult<()> {
+        self.conn.execute(
+            "DELETE FROM time_entries WHERE task_id = ?1",
+            params![task_id.to_string()],
+        )?;
+        for entry in entries {
+            self.conn.execute(
+                "INSERT INTO time_entries (id, task_id, started_at, ended_at)
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![
+                    entry.id,
+                    task_id.to_string(),
+                    entry.started_at.to_rfc3339(),
+                    entry.ended_at.map(|d| d.to_rfc3339()),
+                ],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Temps passé sur chaque task suivie, intervalle en cours compris (jusqu'à `now`)
+    pub fn tracked_time(&self, now: DateTime<Utc>) -> SqlResult<HashMap<Uuid, Duration>> {
+        let mut stmt = self
+            .conn
+            .prepare(&format!("SELECT {} FROM time_entries", ENTRY_COLUMNS))?;
+        let mut tracked = HashMap::new();
+        for entry in stmt.query_map([], entry_from_row)? {
+            let entry = entry?;
+            *tracked.entry(entry.task_id).or_insert_with(Duration::zero) += entry.duration(now);
+        }
+        Ok(tracked)
+    }
+
+    /// Temps passé dans `[since, until[`, regroupé par task, tag ou projet. Les intervalles
+    /// à cheval sur les bornes sont tronqués ; l'intervalle en cours est compté jusqu'à
+    /// `now`. Les tasks de la corbeille sont comprises.
+    pub fn time_report(
+        &self,
+        since: Option<DateTime<Utc>>,
+        until: Option<DateTime<Utc>>,
+        group: ReportGroup,
+        now: DateTime<Utc>,
+    ) -> SqlResult<TimeReport> {
+        let until = until.unwrap_or(now);
+        let since_str = since.map(|d| d.to_rfc3339());
+        let until_str = until.to_rfc3339();
+
+        let overlapping = "started_at < ?2 AND (?1 IS NULL OR ended_at IS NULL OR ended_at > ?1)";
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM time_entries WHERE {}",
+            ENTRY_COLUMNS, overlapping
+        ))?;
+        let entries = stmt
+            .query_map(params![since_str, until_str], entry_from_row)?
+            .collect::<SqlResult<Vec<TimeEntry>>>()?;
+
+        let tasks: HashMap<Uuid, Task> = self
+            .fetch_tasks(
+                &format!(
+                    "SELECT {} FROM tasks WHERE id IN (SELECT task_id FROM time_entries WHERE {})",
+                    TASK_COLUMNS, overlapping
+                ),
+                params![since_str, until_str],
+            )?
+            .into_iter()
+            .map(|task| (task.id, task))
+            .collect();
+
+        let mut totals: HashMap<Option<String>, i64> = HashMap::new();
+        let mut total_seconds = 0;
+        for entry in &entries {
+            let duration = entry.duration_within(since, until, now);
+            if duration <= Duration::zero() {
+                continue;
+            }
+            let seconds = duration.num_seconds();
+            total_seconds += seconds;
+
+            let task = tasks.get(&entry.task_id);
+            let labels: Vec<Option<String>> = match group {
+                ReportGroup::Task => vec![task.map(|t| match t.short_id {
+                    Some(short_id) => format!("[{}] {}", short_id, t.description),
+                    None => t.description.clone(),
+                })],
+                ReportGroup::Project => vec![task.and_then(|t| t.project.clone())],
+                ReportGroup::Tag => match task.and_then(|t| t.tags.as_ref()) {
+                    Some(tags) if !tags.is_empty() => tags.iter().cloned().map(Some).collect(),
+                    _ => vec![None],
+                },
+            };
+            for label in labels {
+                *totals.entry(label).or_insert(0) += seconds;
+            }
+        }
+
+        // Par durée décroissante, puis par libellé ; les tasks sans tag ou projet en dernier
+        let mut lines: Vec<TimeReportLine> = totals
+            .into_iter()
+            .map(|(label, seconds)| TimeReportLine { label, seconds })
+            .collect();
+        lines.sort_by(|a, b| {
+            a.label
+                .is_none()
+                .cmp(&b.label.is_none())
+                .then(b.seconds.cmp(&a.seconds))
+                .then(a.label.cmp(&b.label))
+        });
+
+        Ok(TimeReport {
+            since,
+            until,
+            lines,
+            total_seconds,
+        })
+    }
+}
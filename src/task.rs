@@ -1,20 +1,18 @@
-use chrono::{DateTime, Duration, Utc};
+use crate::estimate::Estimate;
+use crate::recurrence::Recurrence;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub enum Priority {
     High,
+    #[default]
     Medium,
     Low,
 }
 
-impl Default for Priority {
-    fn default() -> Self {
-        Priority::Medium
-    }
-}
-
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Status {
     Completed,
     NotStarted,
@@ -22,36 +20,58 @@ pub enum Status {
     Canceled,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Task {
     pub id: Uuid,
+    /// ID court numérique, attribué par le stockage lors de l'ajout
+    pub short_id: Option<u32>,
     pub description: String,
     pub tags: Option<Vec<String>>,
     pub status: Option<Status>,
     pub priority: Priority,
-    pub due_date: DateTime<Utc>,
+    /// Échéance (`None` si la task n'en a pas)
+    #[serde(default)]
+    pub due_date: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// Date de mise à la corbeille (`None` si la task n'est pas supprimée)
+    #[serde(default)]
+    pub deleted_at: Option<DateTime<Utc>>,
+    /// Règle de récurrence : une nouvelle occurrence est créée à la complétion
+    #[serde(default)]
+    pub recurrence: Option<Recurrence>,
+    /// Task parente (`None` pour une task de premier niveau)
+    #[serde(default)]
+    pub parent_id: Option<Uuid>,
+    /// Tasks à terminer avant de pouvoir commencer celle-ci
+    #[serde(default)]
+    pub depends_on: Vec<Uuid>,
+    /// Nom du projet de la task
+    #[serde(default)]
+    pub project: Option<String>,
+    /// Effort estimé, en minutes ou en points
+    #[serde(default)]
+    pub estimate: Option<Estimate>,
 }
 
 impl Task {
     /// Crée une nouvelle task.
     /// - `description` obligatoire.
-    /// - `tags`, `status`, `priority`, `due_date_opt` sont optionnels.
+    /// - `tags`, `status`, `priority`, `due_date` sont optionnels.
     ///   - priorité par défaut = `Medium`
-    ///   - date de fin par défaut = now + 1 jour
+    ///   - pas d'échéance par défaut (None)
     ///   - pas de tags par défaut (None)
     pub fn new<D: Into<String>>(
         description: D,
         tags: Option<Vec<String>>,
         status: Option<Status>,
         priority: Option<Priority>,
-        due_date_opt: Option<DateTime<Utc>>,
+        due_date: Option<DateTime<Utc>>,
     ) -> Self {
         let now = Utc::now();
-        let due_date = due_date_opt.unwrap_or_else(|| now + Duration::days(1));
         Task {
             id: Uuid::new_v4(),
+            short_id: None,
             description: description.into(),
             tags,
             status,
@@ -59,9 +79,42 @@ impl Task {
             due_date,
             created_at: now,
             updated_at: now,
+            deleted_at: None,
+            recurrence: None,
+            parent_id: None,
+            depends_on: Vec::new(),
+            project: None,
+            estimate: None,
         }
     }
 
+    /// Occurrence suivante d'une task récurrente complétée à `completed_at` :
+    /// mêmes description, tags, priorité, règle, parent, projet et estimation, non commencée.
+    /// Retourne `None` si la task n'est pas récurrente.
+    pub fn next_occurrence(&self, completed_at: DateTime<Utc>) -> Option<Task> {
+        let recurrence = self.recurrence.as_ref()?;
+        let mut next = Task::new(
+            self.description.clone(),
+            self.tags.clone(),
+            None,
+            Some(self.priority.clone()),
+            Some(recurrence.next_due(self.due_date, completed_at)),
+        );
+        next.recurrence = Some(recurrence.clone());
+        next.parent_id = self.parent_id;
+        next.project = self.project.clone();
+        next.estimate = self.estimate;
+        Some(next)
+    }
+
+    /// Indique si la task reste à faire (ni complétée, ni annulée)
+    pub fn is_open(&self) -> bool {
+        !matches!(
+            self.status,
+            Some(Status::Completed) | Some(Status::Canceled)
+        )
+    }
+
     fn touch(&mut self) {
         self.updated_at = Utc::now();
     }
@@ -81,11 +134,52 @@ impl Task {
         self.touch();
     }
 
-    pub fn set_due_date(&mut self, due_date: DateTime<Utc>) {
+    pub fn set_due_date(&mut self, due_date: Option<DateTime<Utc>>) {
         self.due_date = due_date;
         self.touch();
     }
 
+    pub fn set_recurrence(&mut self, recurrence: Option<Recurrence>) {
+        self.recurrence = recurrence;
+        self.touch();
+    }
+
+    pub fn set_estimate(&mut self, estimate: Option<Estimate>) {
+        self.estimate = estimate;
+        self.touch();
+    }
+
+    pub fn set_parent(&mut self, parent_id: Option<Uuid>) {
+        self.parent_id = parent_id;
+        self.touch();
+    }
+
+    pub fn set_project(&mut self, project: Option<String>) {
+        self.project = project;
+        self.touch();
+    }
+
+    /// Ajoute une dépendance ; retourne `false` si elle existait déjà
+    pub fn add_dependency(&mut self, task_id: Uuid) -> bool {
+        if self.depends_on.contains(&task_id) {
+            return false;
+        }
+        self.depends_on.push(task_id);
+        self.touch();
+        true
+    }
+
+    /// Retire une dépendance ; retourne `false` si elle n'existait pas
+    pub fn remove_dependency(&mut self, task_id: &Uuid) -> bool {
+        let count = self.depends_on.len();
+        self.depends_on.retain(|id| id != task_id);
+        if self.depends_on.len() == count {
+            return false;
+        }
+        self.touch();
+        true
+    }
+
     pub fn add_tag<S: Into<String>>(&mut self, tag: S) {
         match &mut self.tags {
             Some(vec) => {
@@ -108,3 +202,47 @@ impl Task {
         }
     }
 }
+
+/// Référence vers une task telle que saisie par l'utilisateur
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TaskRef {
+    /// ID court numérique (ex: `3`)
+    Short(u32),
+    /// UUID complet
+    Full(Uuid),
+    /// Préfixe d'UUID (ex: `671bc1`)
+    Prefix(String),
+}
+
+impl TaskRef {
+    /// Analyse un identifiant saisi par l'utilisateur.
+    /// - uniquement des chiffres => ID court, résolu comme préfixe d'UUID si aucune task
+    ///   ne porte cet ID court ; avec un zéro initial ou au-delà de `u32`, préfixe d'UUID
+    /// - UUID valide => UUID complet
+    /// - caractères hexadécimaux et tirets => préfixe d'UUID
+    ///
+    /// Retourne `None` si la saisie ne correspond à aucun de ces formats.
+    pub fn parse(input: &str) -> Option<TaskRef> {
+        let input = input.trim();
+        if input.is_empty() {
+            return None;
+        }
+
+        if input.chars().all(|c| c.is_ascii_digit())
+            && (input == "0" || !input.starts_with('0'))
+            && let Ok(short_id) = input.parse()
+        {
+            return Some(TaskRef::Short(short_id));
+        }
+
+        if let Ok(uuid) = Uuid::parse_str(input) {
+            return Some(TaskRef::Full(uuid));
+        }
+
+        if input.chars().all(|c| c.is_ascii_hexdigit() || c == '-') {
+            return Some(TaskRef::Prefix(input.to_lowercase()));
+        }
+
+        None
+    }
+}
@@ -0,0 +1,238 @@
+//! Score d'urgence des tasks, pour savoir quoi faire ensuite.
+//!
+//! Le score est la somme de termes pondérés par des coefficients configurables :
+//!
+//! | Clé | Défaut | Terme |
+//! |-----|--------|-------|
+//! | `priority.high` | 6.0 | Priorité haute |
+//! | `priority.medium` | 3.9 | Priorité moyenne |
+//! | `priority.low` | 1.8 | Priorité basse |
+//! | `due` | 12.0 | Proximité de l'échéance : 0.2 à 14 jours ou plus, 1.0 à l'échéance |
+//! | `overdue` | 3.0 | Échéance dépassée |
+//! | `age` | 2.0 | Ancienneté, jusqu'à 1.0 à un an |
+//! | `tags` | 1.0 | Tags : 0.8 pour un tag, 0.9 pour deux, 1.0 au-delà |
+//! | `blocked` | -5.0 | Dépendance ouverte |
+//! | `inprogress` | 4.0 | Statut `InProgress` |
+//!
+//! Une task complétée ou annulée a un score nul.
+
+use crate::task::{Priority, Status, Task};
+use chrono::{DateTime, Utc};
+use std::cmp::Ordering;
+
+/// Coefficients du score d'urgence
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UrgencyCoefficients {
+    pub priority_high: f64,
+    pub priority_medium: f64,
+    pub priority_low: f64,
+    pub due: f64,
+    pub overdue: f64,
+    pub age: f64,
+    pub tags: f64,
+    pub blocked: f64,
+    pub in_progress: f64,
+}
+
+impl Default for UrgencyCoefficients {
+    fn default() -> Self {
+        UrgencyCoefficients {
+            priority_high: 6.0,
+            priority_medium: 3.9,
+            priority_low: 1.8,
+            due: 12.0,
+            overdue: 3.0,
+            age: 2.0,
+            tags: 1.0,
+            blocked: -5.0,
+            in_progress: 4.0,
+        }
+    }
+}
+
+const KEYS: &str =
+    "priority.high, priority.medium, priority.low, due, overdue, age, tags, blocked ou inprogress";
+
+/// Nombre de jours avant l'échéance au-delà duquel sa proximité est minimale (0.2)
+const DUE_HORIZON_DAYS: f64 = 14.0;
+
+/// Âge en jours auquel le terme d'ancienneté atteint son maximum
+const AGE_MAX_DAYS: f64 = 365.0;
+
+impl UrgencyCoefficients {
+    /// Applique aux coefficients par défaut des valeurs `clé=valeur` séparées par des
+    /// virgules (ex: `due=15,blocked=-8`)
+    pub fn parse(input: &str) -> Result<UrgencyCoefficients, String> {
+        let mut coefficients = UrgencyCoefficients::default();
+        for item in input.split(',').map(str::trim).filter(|i| !i.is_empty()) {
+            let invalid = || {
+                format!(
+                    "Coefficient d'urgence invalide '{}'. Utilisez clé=valeur avec: {}",
+                    item, KEYS
+                )
+            };
+            let (key, value) = item.split_once('=').ok_or_else(invalid)?;
+            let value: f64 = match value.trim().parse() {
+                Ok(value) if f64::is_finite(value) => value,
+                _ => return Err(invalid()),
+            };
+            let field = match key.trim().to_lowercase().as_str() {
+                "priority.high" => &mut coefficients.priority_high,
+                "priority.medium" => &mut coefficients.priority_medium,
+                "priority.low" => &mut coefficients.priority_low,
+                "due" => &mut coefficients.due,
+                "overdue" => &mut coefficients.overdue,
+                "age" => &mut coefficients.age,
+                "tags" => &mut coefficients.tags,
+                "blocked" => &mut coefficients.blocked,
+                "inprogress" => &mut coefficients.in_progress,
+                _ => return Err(invalid()),
+            };
+            *field = value;
+        }
+        Ok(coefficients)
+    }
+
+    /// Score d'urgence d'une task à l'instant `now` ; `blocked` indique une dépendance ouverte
+    pub fn score(&self, task: &Task, blocked: bool, now: DateTime<Utc>) -> f64 {
+        if !task.is_open() {
+            return 0.0;
+        }
+
+        let mut score = match task.priority {
+            Priority::High => self.priority_high,
+            Priority::Medium => self.priority_medium,
+            Priority::Low => self.priority_low,
+        };
+
+        if let Some(due) = task.due_date {
+            let days = (due - now).num_seconds() as f64 / 86_400.0;
+            let proximity = if days <= 0.0 {
+                1.0
+            } else if days >= DUE_HORIZON_DAYS {
+                0.2
+            } else {
+                0.2 + 0.8 * (DUE_HORIZON_DAYS - days) / DUE_HORIZON_DAYS
+            };
+            score += self.due * proximity;
+            if due < now {
+                score += self.overdue;
+            }
+        }
+
+        let age_days = (now - task.created_at).num_seconds().max(0) as f64 / 86_400.0;
+        score += self.age * (age_days / AGE_MAX_DAYS).min(1.0);
+
+        score += self.tags
+            * match task.tags.as_ref().map_or(0, Vec::len) {
+                0 => 0.0,
+                1 => 0.8,
+                2 => 0.9,
+                _ => 1.0,
+            };
+
+        if blocked {
+            score += self.blocked;
+        }
+        if task.status == Some(Status::InProgress) {
+            score += self.in_progress;
+        }
+        score
+    }
+
+    /// Trie des tasks par urgence décroissante, avec leur score. À score égal, l'ordre
+    /// d'origine est conservé.
+    pub fn rank(
+        &self,
+        tasks: Vec<Task>,
+        is_blocked: impl Fn(&Task) -> bool,
+        now: DateTime<Utc>,
+    ) -> Vec<(Task, f64)> {
+        let mut ranked: Vec<(Task, f64)> = tasks
+            .into_iter()
+            .map(|task| {
+                let score = self.score(&task, is_blocked(&task), now);
+                (task, score)
+            })
+            .collect();
+        ranked.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(Ordering::Equal));
+        ranked
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn task(priority: Priority, now: DateTime<Utc>) -> Task {
+        let mut task = Task::new("Task", None, None, Some(priority), None);
+        task.created_at = now;
+        task
+    }
+
+    #[test]
+    fn test_score_terms() {
+        let now = Utc::now();
+        let c = UrgencyCoefficients::default();
+        let close = |a: f64, b: f64| (a - b).abs() < 1e-9;
+
+        assert!(close(c.score(&task(Priority::High, now), false, now), 6.0));
+        assert!(close(c.score(&task(Priority::Low, now), false, now), 1.8));
+
+        // Proximité de l'échéance et retard
+        let mut due = task(Priority::Medium, now);
+        due.due_date = Some(now + Duration::days(30));
+        assert!(close(c.score(&due, false, now), 3.9 + 12.0 * 0.2));
+        due.due_date = Some(now + Duration::days(7));
+        assert!(close(c.score(&due, false, now), 3.9 + 12.0 * 0.6));
+        due.due_date = Some(now - Duration::days(2));
+        assert!(close(c.score(&due, false, now), 3.9 + 12.0 + 3.0));
+
+        // Ancienneté, tags, blocage et statut
+        let mut other = task(Priority::Medium, now - Duration::days(730));
+        other.tags = Some(vec!["a".to_string(), "b".to_string()]);
+        other.status = Some(Status::InProgress);
+        assert!(close(
+            c.score(&other, true, now),
+            3.9 + 2.0 + 0.9 - 5.0 + 4.0
+        ));
+
+        other.status = Some(Status::Completed);
+        assert_eq!(c.score(&other, false, now), 0.0);
+    }
+
+    #[test]
+    fn test_parse_and_rank() {
+        let c = UrgencyCoefficients::parse(" due=20, Blocked=-10 ,").unwrap();
+        assert_eq!(c.due, 20.0);
+        assert_eq!(c.blocked, -10.0);
+        assert_eq!(c.age, UrgencyCoefficients::default().age);
+        assert_eq!(
+            UrgencyCoefficients::parse("").unwrap(),
+            UrgencyCoefficients::default()
+        );
+        for input in ["due", "due=abc", "speed=2", "due=inf"] {
+            assert!(
+                UrgencyCoefficients::parse(input)
+                    .unwrap_err()
+                    .starts_with("Coefficient d'urgence invalide"),
+                "{}",
+                input
+            );
+        }
+
+        let now = Utc::now();
+        let low = task(Priority::Low, now);
+        let high = task(Priority::High, now);
+        let blocked = task(Priority::High, now);
+        let blocked_id = blocked.id;
+        let ranked = UrgencyCoefficients::default().rank(
+            vec![low.clone(), blocked, high.clone()],
+            |t| t.id == blocked_id,
+            now,
+        );
+        let ids: Vec<_> = ranked.iter().map(|(t, _)| t.id).collect();
+        assert_eq!(ids, vec![high.id, low.id, blocked_id]);
+    }
+}
@@ -0,0 +1,31 @@
+-- Base de données telle que créée par task-cli v0.1.0 (sans user_version)
+CREATE TABLE IF NOT EXISTS tasks (
+    id TEXT PRIMARY KEY,
+    description TEXT NOT NULL,
+    priority TEXT NOT NULL,
+    status TEXT,
+    due_date TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS task_tags (
+    task_id TEXT NOT NULL,
+    tag TEXT NOT NULL,
+    PRIMARY KEY (task_id, tag),
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE
+);
+
+INSERT INTO tasks VALUES ('5c5052fc-1b2a-4c3d-8e4f-000000000002', 'Implémenter API REST', 'High', 'InProgress',
+    '2026-02-15T00:00:00+00:00', '2026-01-20T09:00:00+00:00', '2026-01-21T10:30:00+00:00');
+INSERT INTO tasks VALUES ('671bc182-7f18-4f8d-a0c3-b29a7e506742', 'Apprendre Rust', 'Medium', NULL,
+    '2026-02-01T00:00:00+00:00', '2026-01-10T08:00:00+00:00', '2026-01-10T08:00:00+00:00');
+INSERT INTO tasks VALUES ('85416604-0071-46ab-b76c-601a3df0f308', 'Faire les courses', 'Low', 'Completed',
+    '2026-01-30T00:00:00+00:00', '2026-01-25T18:00:00+00:00', '2026-01-26T12:00:00+00:00');
+
+INSERT INTO task_tags VALUES ('5c5052fc-1b2a-4c3d-8e4f-000000000002', 'backend');
+INSERT INTO task_tags VALUES ('5c5052fc-1b2a-4c3d-8e4f-000000000002', 'work');
+INSERT INTO task_tags VALUES ('671bc182-7f18-4f8d-a0c3-b29a7e506742', 'dev');
+INSERT INTO task_tags VALUES ('85416604-0071-46ab-b76c-601a3df0f308', 'personnel');
+-- Tag orphelin laissé par une suppression v0.1.0 (foreign_keys désactivé)
+PRAGMA foreign_keys = OFF;
+INSERT INTO task_tags VALUES ('00000000-dead-4000-8000-000000000000', 'supprimée');
@@ -184,6 +184,33 @@ fn test_workflow_add_list_delete() {
     }
 }
 
+#[test]
+fn test_complete_command_with_short_id() {
+    setup_test_env();
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.arg("add").arg("Tâche avec ID court");
+
+    let output = cmd.assert().success();
+    let stdout = String::from_utf8_lossy(&output.get_output().stdout);
+
+    // Extraire l'ID court (format: "ID: <n>")
+    let short_id = stdout
+        .lines()
+        .find(|l| l.trim_start().starts_with("ID:"))
+        .and_then(|l| l.split_whitespace().last())
+        .unwrap()
+        .to_string();
+    assert!(short_id.chars().all(|c| c.is_ascii_digit()));
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.arg("complete").arg(&short_id);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Tâche marquée comme complétée"))
+        .stdout(predicate::str::contains("Tâche avec ID court"));
+}
+
 #[test]
 fn test_add_command_with_medium_priority() {
     setup_test_env();
@@ -200,8 +227,1572 @@ fn test_add_command_with_medium_priority() {
         .stdout(predicate::str::contains("Medium"));
 }
 
+/// Chemin unique vers une base de données temporaire, dans un répertoire inexistant
+fn temp_db_path() -> String {
+    format!("/tmp/task-cli-test-db/{}/tasks.db", uuid::Uuid::new_v4())
+}
+
+#[test]
+fn test_db_option_isolates_database() {
+    let db = temp_db_path();
+
+    // list fonctionne sur une base neuve dont le répertoire n'existe pas encore
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.arg("--db").arg(&db).arg("list");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Aucune tâche trouvée"));
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.arg("add").arg("Tâche isolée").arg("--db").arg(&db);
+    cmd.assert().success();
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.arg("list").arg("--db").arg(&db);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("1 tâche(s) trouvée(s)"))
+        .stdout(predicate::str::contains("Tâche isolée"));
+}
+
+#[test]
+fn test_db_env_var_and_option_precedence() {
+    let env_db = temp_db_path();
+    let option_db = temp_db_path();
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.env("TASK_CLI_DB", &env_db)
+        .arg("add")
+        .arg("Tâche via env");
+    cmd.assert().success();
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.env("TASK_CLI_DB", &env_db).arg("list");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Tâche via env"));
+
+    // --db est prioritaire sur TASK_CLI_DB
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.env("TASK_CLI_DB", &env_db)
+        .arg("--db")
+        .arg(&option_db)
+        .arg("list");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Aucune tâche trouvée"));
+}
+
+#[test]
+fn test_json_output_format() {
+    let db = temp_db_path();
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "--format", "json"]).args([
+        "add",
+        "Tâche JSON",
+        "-t",
+        "work",
+        "-p",
+        "high",
+    ]);
+    let output = cmd.assert().success();
+    let added: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(added["description"], "Tâche JSON");
+    assert_eq!(added["priority"], "High");
+    assert_eq!(added["short_id"], 1);
+    assert_eq!(added["tags"], serde_json::json!(["work"]));
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "--format", "json", "complete", "1"]);
+    let output = cmd.assert().success();
+    let completed: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(completed["status"], "Completed");
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "list", "--format", "json"]);
+    let output = cmd.assert().success();
+    let listed: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(listed.as_array().unwrap().len(), 1);
+    assert_eq!(listed[0]["id"], added["id"]);
+
+    // Sans --force, la suppression est refusée en JSON
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "--format", "json", "delete", "1"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::is_empty())
+        .stderr(predicate::str::contains("--force"));
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "--format", "json", "delete", "1", "--force"]);
+    let output = cmd.assert().success();
+    let deleted: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(deleted["id"], added["id"]);
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "--format", "json", "search", "JSON"]);
+    cmd.assert().success().stdout(predicate::str::diff("[]\n"));
+}
+
+#[test]
+fn test_export_import_round_trip() {
+    let source_db = temp_db_path();
+    let target_db = temp_db_path();
+    let export_dir = format!("/tmp/task-cli-test-export/{}", uuid::Uuid::new_v4());
+    std::fs::create_dir_all(&export_dir).unwrap();
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &source_db, "add", "Tâche exportée", "-t", "a,b"]);
+    cmd.assert().success();
+
+    for ext in ["json", "csv"] {
+        let file = format!("{}/tasks.{}", export_dir, ext);
+
+        let mut cmd = Command::cargo_bin("task-cli").unwrap();
+        cmd.args(["--db", &source_db, "export", &file]);
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::contains("1 tâche(s) exportée(s)"));
+
+        let mut cmd = Command::cargo_bin("task-cli").unwrap();
+        cmd.args(["--db", &target_db, "import", &file]);
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::contains("Import terminé"));
+    }
+
+    // Le second import (CSV) retrouve l'UUID déjà importé et l'ignore
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &target_db, "--format", "json", "list"]);
+    let output = cmd.assert().success();
+    let listed: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(listed.as_array().unwrap().len(), 1);
+    assert_eq!(listed[0]["description"], "Tâche exportée");
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &target_db, "--format", "json"]).args([
+        "import",
+        &format!("{}/tasks.csv", export_dir),
+        "--on-conflict",
+        "overwrite",
+    ]);
+    let output = cmd.assert().success();
+    let report: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(report["updated"], 1);
+}
+
+/// Écrit un fichier JSON de deux tâches X et Y liées par `field` dans les deux sens
+fn write_cyclic_import(field: &str, value: impl Fn(&str) -> serde_json::Value) -> String {
+    let dir = format!("/tmp/task-cli-test-export/{}", uuid::Uuid::new_v4());
+    std::fs::create_dir_all(&dir).unwrap();
+    let x = "11111111-5ff1-4f8d-a0c3-b29a7e506742";
+    let y = "22222222-5ff1-4f8d-a0c3-b29a7e506742";
+    let task = |id: &str, description: &str, other: &str| {
+        let mut task = serde_json::json!({
+            "id": id,
+            "short_id": null,
+            "description": description,
+            "tags": null,
+            "status": null,
+            "priority": "Medium",
+            "created_at": "2026-10-01T09:00:00Z",
+            "updated_at": "2026-10-01T09:00:00Z",
+        });
+        task[field] = value(other);
+        task
+    };
+    let file = format!("{}/cycle.json", dir);
+    let tasks = serde_json::json!([task(x, "X", y), task(y, "Y", x)]);
+    std::fs::write(&file, tasks.to_string()).unwrap();
+    file
+}
+
+#[test]
+fn test_import_rejects_parent_cycle() {
+    let db = temp_db_path();
+    let file = write_cyclic_import("parent_id", |other| serde_json::json!(other));
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "import", &file]);
+    cmd.assert().success().stderr(predicate::str::contains(
+        "Rattachement circulaire: la tâche 'X'",
+    ));
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "--format", "json", "list"]);
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let tasks: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert!(tasks.as_array().unwrap().is_empty());
+}
+
+#[test]
+fn test_import_rejects_dependency_cycle() {
+    let db = temp_db_path();
+    let file = write_cyclic_import("depends_on", |other| serde_json::json!([other]));
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "import", &file]);
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("Import annulé"))
+        .stderr(predicate::str::contains(
+            "Dépendance circulaire: la tâche 'X'",
+        ));
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "--format", "json", "list"]);
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let tasks: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert!(tasks.as_array().unwrap().is_empty());
+}
+
+#[test]
+fn test_undo_redo_delete() {
+    let db = temp_db_path();
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "add", "Tâche à restaurer"]);
+    cmd.assert().success();
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "delete", "1", "--force"]);
+    cmd.assert().success();
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "undo"]);
+    cmd.assert().success().stdout(predicate::str::contains(
+        "Annulé: Suppression de la tâche [1] Tâche à restaurer",
+    ));
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "list"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Tâche à restaurer"));
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "redo"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Rejoué: Suppression"));
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "redo"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Rien à rétablir"));
+}
+
+#[test]
+fn test_history_shows_status_changes() {
+    let db = temp_db_path();
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "add", "Tâche suivie"]);
+    cmd.assert().success();
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "edit", "1", "--status", "inprogress"]);
+    cmd.assert().success();
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "history", "1"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Historique de la tâche [1] Tâche suivie",
+        ))
+        .stdout(predicate::str::contains("Création"))
+        .stdout(predicate::str::contains("Statut: - → InProgress"));
+
+    // L'annulation est inscrite dans l'historique
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "undo"]);
+    cmd.assert().success();
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "history", "1"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Statut: InProgress → -"));
+}
+
 #[test]
 fn test_cli_requires_subcommand() {
     let mut cmd = Command::cargo_bin("task-cli").unwrap();
     cmd.assert().failure();
 }
+
+#[test]
+fn test_trash_restore_and_purge() {
+    let db = temp_db_path();
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "add", "Tâche jetée", "--tags", "corbeille"]);
+    cmd.assert().success();
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "delete", "1", "--force"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("placée dans la corbeille"));
+
+    // La tâche n'apparaît plus dans la liste, mais dans la corbeille
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "list"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Tâche jetée").not());
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "trash"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("[1] Tâche jetée"));
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "restore", "1"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Tâche restaurée avec succès"));
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "list"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Tâche jetée"));
+
+    // Une purge récente ne touche pas aux tâches supprimées à l'instant
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "delete", "1", "--force"]);
+    cmd.assert().success();
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "purge", "--older-than", "30d", "--force"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("0 tâche(s) supprimée(s)"));
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "purge", "--force"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("1 tâche(s) supprimée(s)"));
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "trash"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("La corbeille est vide"));
+}
+
+#[test]
+fn test_purge_invalid_age() {
+    let db = temp_db_path();
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "purge", "--older-than", "soon", "--force"]);
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("Durée invalide"));
+}
+
+#[test]
+fn test_search_full_text_ranking() {
+    let db = temp_db_path();
+
+    for description in ["Rapport mensuel", "Rapport du rapport annuel", "Courses"] {
+        let mut cmd = Command::cargo_bin("task-cli").unwrap();
+        cmd.args(["--db", &db, "add", description]);
+        cmd.assert().success();
+    }
+
+    // Le résultat le plus pertinent est affiché en premier
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "--format", "json", "search", "rapp*"]);
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let tasks: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    let descriptions: Vec<&str> = tasks
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|t| t["description"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        descriptions,
+        vec!["Rapport du rapport annuel", "Rapport mensuel"]
+    );
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "search", "mensuel OR courses"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("2 tâche(s) trouvée(s)"));
+
+    // Un opérateur mal placé est signalé avec sa position, sans erreur fts5
+    for (pattern, message) in [
+        (
+            "NOT rapport",
+            "Opérateur 'NOT' sans terme à gauche (position 1)",
+        ),
+        (
+            "rapport OR",
+            "Opérateur 'OR' sans terme à droite (position 9)",
+        ),
+        (
+            "rapport AND OR courses",
+            "Opérateur 'OR' inattendu après 'AND'",
+        ),
+        ("(rapport", "Parenthèse fermante manquante"),
+    ] {
+        let mut cmd = Command::cargo_bin("task-cli").unwrap();
+        cmd.args(["--db", &db, "search", pattern]);
+        cmd.assert()
+            .success()
+            .stderr(predicate::str::contains("Recherche invalide"))
+            .stderr(predicate::str::contains(message))
+            .stderr(predicate::str::contains("fts5").not());
+    }
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "search", "annuel (mensuel OR rapport)"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("1 tâche(s) trouvée(s)"));
+}
+
+#[test]
+fn test_list_with_filter_expression() {
+    let db = temp_db_path();
+
+    for args in [
+        vec![
+            "add",
+            "Tâche travail",
+            "--tags",
+            "work",
+            "--due",
+            "2026-10-25",
+        ],
+        vec![
+            "add",
+            "Tâche urgente",
+            "--priority",
+            "high",
+            "--due",
+            "2026-12-01",
+        ],
+        vec![
+            "add",
+            "Tâche perso",
+            "--tags",
+            "perso",
+            "--due",
+            "2026-10-25",
+        ],
+    ] {
+        let mut cmd = Command::cargo_bin("task-cli").unwrap();
+        cmd.args(["--db", &db]).args(args);
+        cmd.assert().success();
+    }
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args([
+        "--db",
+        &db,
+        "list",
+        "--filter",
+        "(tag:work or priority:high) and due<2026-11-01",
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("1 tâche(s) trouvée(s)"))
+        .stdout(predicate::str::contains("Tâche travail"));
+
+    // L'option --due de search filtre sur le jour d'échéance
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "search", "tâche", "--due", "2026-10-25"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("2 tâche(s) trouvée(s)"));
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "search", "tâche", "--filter", "not tag:perso"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("2 tâche(s) trouvée(s)"));
+}
+
+#[test]
+fn test_list_with_invalid_filter_shows_position() {
+    let db = temp_db_path();
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "list", "--filter", "tag:work and stat:done"]);
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("Champ inconnu 'stat'"))
+        .stderr(predicate::str::contains("(position 14)"))
+        .stderr(predicate::str::contains("\n               ^"));
+}
+
+#[test]
+fn test_add_and_edit_with_natural_due_dates() {
+    let db = temp_db_path();
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "add", "Réunion", "--due", "2026-11-02 14:00"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("2026-11-02 14:00"));
+
+    let tomorrow = (chrono::Local::now().date_naive() + chrono::Days::new(1)).to_string();
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "edit", "1", "--due", "tomorrow"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains(format!("Échéance: {}", tomorrow)));
+
+    // Filtre relatif sur l'échéance
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "list", "--filter", "due:tomorrow"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Réunion"));
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "edit", "1", "--due", "bientôt"]);
+    cmd.assert().success().stderr(predicate::str::contains(
+        "Format de date invalide 'bientôt'",
+    ));
+}
+
+#[test]
+fn test_tasks_without_due_date() {
+    let db = temp_db_path();
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "add", "Un jour peut-être"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Date de fin").not());
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "add", "Rapport", "--due", "2026-11-02"]);
+    cmd.assert().success();
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "edit", "2", "--due", "none"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Échéance: aucune"));
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args([
+        "--db", &db, "--format", "json", "list", "--filter", "due:none",
+    ]);
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let tasks: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(tasks.as_array().unwrap().len(), 2);
+    assert!(tasks[0]["due_date"].is_null());
+}
+
+#[test]
+fn test_timezone_option_for_parsing_and_display() {
+    let db = temp_db_path();
+
+    // 14:00 à New York (heure d'hiver depuis le 1er novembre) = 19:00 UTC
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args([
+        "--db",
+        &db,
+        "--tz",
+        "America/New_York",
+        "--format",
+        "json",
+        "add",
+        "Appel",
+        "--due",
+        "2026-11-02 14:00",
+    ]);
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let task: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(task["due_date"], "2026-11-02T19:00:00Z");
+
+    // Affichée à Paris (UTC+1) via la variable d'environnement
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.env("TASK_CLI_TZ", "Europe/Paris")
+        .args(["--db", &db, "list"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Échéance: 2026-11-02 20:00"));
+
+    // Une date sans heure reste un jour entier dans le fuseau choisi
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args([
+        "--db",
+        &db,
+        "edit",
+        "1",
+        "--due",
+        "2026-11-03",
+        "--tz",
+        "Asia/Tokyo",
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Échéance: 2026-11-03\n"));
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "--tz", "Mars/Olympus", "list"]);
+    cmd.assert().success().stderr(predicate::str::contains(
+        "Fuseau horaire inconnu 'Mars/Olympus'",
+    ));
+}
+
+#[test]
+fn test_recurring_task_completion() {
+    let db = temp_db_path();
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args([
+        "--db",
+        &db,
+        "--tz",
+        "Europe/Paris",
+        "add",
+        "Réunion d'équipe",
+        "--due",
+        "2026-11-02",
+        "--recur",
+        "weekly:mon,thu",
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Récurrence: weekly:mon,thu"));
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "--tz", "Europe/Paris", "complete", "1"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Prochaine occurrence créée: [2]"));
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args([
+        "--db",
+        &db,
+        "--format",
+        "json",
+        "list",
+        "--filter",
+        "status!=completed",
+    ]);
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let tasks: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(tasks.as_array().unwrap().len(), 1);
+    assert_eq!(tasks[0]["recurrence"], "weekly:mon,thu");
+
+    // Terminer par `edit` crée aussi l'occurrence suivante
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "edit", "2", "--status", "completed"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Prochaine occurrence créée: [3]"));
+
+    // Une règle invalide est refusée, 'none' arrête la récurrence
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "edit", "2", "--recur", "hourly"]);
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("Récurrence invalide"));
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "edit", "2", "--recur", "none"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Récurrence").not());
+}
+
+#[test]
+fn test_subtasks_tree_and_completion() {
+    let db = temp_db_path();
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "add", "Déménagement"]);
+    cmd.assert().success();
+
+    for step in ["Cartons", "Camion"] {
+        let mut cmd = Command::cargo_bin("task-cli").unwrap();
+        cmd.args(["--db", &db, "add", step, "--parent", "1"]);
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::contains("Parente: [1] Déménagement"));
+    }
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "complete", "2"]);
+    cmd.assert().success();
+
+    // Les sous-tâches sont indentées sous leur parente, avec l'avancement
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "list"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Sous-tâches: 1/2 terminée(s)"))
+        .stdout(predicate::str::contains("    ✓  [2] Cartons"));
+
+    // Une parente ne peut pas devenir la sous-tâche de sa sous-tâche
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "edit", "1", "--parent", "3"]);
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("ne peut pas être rattachée"));
+
+    // Une sous-tâche reste ouverte : --force est nécessaire
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "complete", "1"]);
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("1 sous-tâche(s) non terminée(s)"));
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "edit", "1", "--status", "completed"]);
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("1 sous-tâche(s) non terminée(s)"));
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "complete", "1", "--force"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Tâche marquée comme complétée"));
+}
+
+#[test]
+fn test_dependencies_block_completion() {
+    let db = temp_db_path();
+
+    for description in ["Acheter les cartons", "Emballer"] {
+        let mut cmd = Command::cargo_bin("task-cli").unwrap();
+        cmd.args(["--db", &db, "add", description]);
+        cmd.assert().success();
+    }
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "depend", "2", "--on", "1"]);
+    cmd.assert().success().stdout(predicate::str::contains(
+        "[2] Emballer dépend de [1] Acheter les cartons",
+    ));
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "depend", "1", "--on", "2"]);
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("Dépendance circulaire"));
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "list"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("BLOQUÉE"))
+        .stdout(predicate::str::contains("Bloquée par: [1]"));
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "--format", "json", "list"]);
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let tasks: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    let blocked_by = |short_id: u32| {
+        let task = tasks
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|t| t["short_id"] == short_id)
+            .unwrap();
+        assert!(task.get("urgency").is_none());
+        task["blocked_by"].clone()
+    };
+    assert_eq!(blocked_by(1), serde_json::json!([]));
+    assert_eq!(blocked_by(2), serde_json::json!([1]));
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "complete", "2"]);
+    cmd.assert().success().stderr(predicate::str::contains(
+        "bloquée par: [1] Acheter les cartons",
+    ));
+
+    // `edit --status completed` applique la même règle, contournable avec --force
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "edit", "2", "--status", "completed"]);
+    cmd.assert().success().stderr(predicate::str::contains(
+        "bloquée par: [1] Acheter les cartons",
+    ));
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "edit", "2", "--status", "completed", "--force"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Tâche mise à jour"));
+
+    // Une tâche terminée n'est plus affichée comme bloquée
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "list"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("BLOQUÉE").not());
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "undo"]);
+    cmd.assert().success();
+
+    // Seules les tâches non bloquées sont prêtes
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "--format", "json", "ready"]);
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let tasks: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(tasks.as_array().unwrap().len(), 1);
+    assert_eq!(tasks[0]["short_id"], 1);
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "complete", "1"]);
+    cmd.assert().success();
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "complete", "2"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Tâche marquée comme complétée"));
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "undepend", "2", "--on", "1"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("ne dépend plus de"));
+}
+
+#[test]
+fn test_projects_commands_and_filter() {
+    let db = temp_db_path();
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "project", "add", "Maison"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Projet créé"));
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "add", "Sans projet", "--project", "Jardin"]);
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("Projet introuvable: Jardin"));
+
+    for description in ["Peindre", "Ranger"] {
+        let mut cmd = Command::cargo_bin("task-cli").unwrap();
+        cmd.args(["--db", &db, "add", description, "--project", "maison"]);
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::contains("Projet: Maison"));
+    }
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "add", "Hors projet"]);
+    cmd.assert().success();
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "complete", "1"]);
+    cmd.assert().success();
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "list", "--project", "Maison"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("2 tâche(s) trouvée(s)"))
+        .stdout(predicate::str::contains("Hors projet").not());
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "search", "Peindre", "--project", "Maison"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Peindre"));
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "project", "list"]);
+    cmd.assert().success().stdout(predicate::str::contains(
+        "Maison — 1/2 tâche(s) terminée(s) (50%)",
+    ));
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "project", "rename", "Maison", "Appartement"]);
+    cmd.assert().success();
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "project", "archive", "Appartement"]);
+    cmd.assert().success();
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "edit", "3", "--project", "Appartement"]);
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("est archivé"));
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "project", "list"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Aucun projet trouvé"));
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "--format", "json", "project", "list", "--all"]);
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let projects: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(projects[0]["name"], "Appartement");
+    assert_eq!(projects[0]["progress"]["done"], 1);
+    assert!(projects[0]["archived_at"].is_string());
+}
+
+#[test]
+fn test_notes_show_and_search() {
+    let db = temp_db_path();
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "add", "Préparer la réunion"]);
+    cmd.assert().success();
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "show", "1"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Notes").not());
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "note", "1", "Réserver la salle B12"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Note ajoutée"));
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "note", "1", "   "]);
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("Note vide"));
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "show", "1"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Notes (1)"))
+        .stdout(predicate::str::contains("Réserver la salle B12"));
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "search", "salle"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("1 tâche(s) trouvée(s)"))
+        .stdout(predicate::str::contains("Notes:"));
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "note", "1", "--edit", "2", "Texte"]);
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("n'a pas de note n°2"));
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "--format", "json", "show", "1"]);
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let task: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(task["description"], "Préparer la réunion");
+    assert_eq!(task["notes"][0]["text"], "Réserver la salle B12");
+}
+
+#[test]
+fn test_undo_redo_note() {
+    let db = temp_db_path();
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "add", "Préparer la réunion"]);
+    cmd.assert().success();
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "note", "1", "Réserver la salle B12"]);
+    cmd.assert().success();
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args([
+        "--db",
+        &db,
+        "note",
+        "1",
+        "--edit",
+        "1",
+        "Réserver la salle C3",
+    ]);
+    cmd.assert().success();
+
+    // L'annulation rétablit le texte précédent, puis retire la note sans toucher à la tâche
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "undo"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Note de la tâche [1]"));
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "show", "1"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Réserver la salle B12"));
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "undo"]);
+    cmd.assert().success();
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "show", "1"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Préparer la réunion"))
+        .stdout(predicate::str::contains("Notes").not());
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "search", "salle"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Aucune tâche"));
+
+    // Le rétablissement rejoue l'ajout puis la modification
+    for _ in 0..2 {
+        let mut cmd = Command::cargo_bin("task-cli").unwrap();
+        cmd.args(["--db", &db, "redo"]);
+        cmd.assert().success();
+    }
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "show", "1"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Notes (1)"))
+        .stdout(predicate::str::contains("Réserver la salle C3"));
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "search", "C3"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("1 tâche(s) trouvée(s)"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_note_edited_in_editor() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let db = temp_db_path();
+    let dir = std::path::Path::new(&db).parent().unwrap().to_path_buf();
+    std::fs::create_dir_all(&dir).unwrap();
+
+    // Éditeur de test : ajoute une ligne au fichier reçu
+    let editor = dir.join("editor.sh");
+    std::fs::write(&editor, "#!/bin/sh\necho \"Ligne ajoutée\" >> \"$1\"\n").unwrap();
+    std::fs::set_permissions(&editor, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "add", "Rédiger le compte rendu"]);
+    cmd.assert().success();
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "note", "1", "Première ligne"]);
+    cmd.assert().success();
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.env("EDITOR", &editor).env_remove("VISUAL");
+    cmd.args(["--db", &db, "note", "1", "--edit", "1"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Note modifiée"))
+        .stdout(predicate::str::contains("Première ligne\n  Ligne ajoutée"));
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.env("EDITOR", "false").env_remove("VISUAL");
+    cmd.args(["--db", &db, "note", "1"]);
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("L'éditeur 'false' a échoué"));
+}
+
+#[test]
+fn test_show_task_details() {
+    let db = temp_db_path();
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "add", "Déménagement", "-t", "maison,perso"]);
+    cmd.assert().success();
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args([
+        "--db",
+        &db,
+        "add",
+        "Cartons",
+        "--parent",
+        "1",
+        "--due",
+        "yesterday",
+    ]);
+    cmd.assert().success();
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "add", "Réserver le camion", "--due", "+3d"]);
+    cmd.assert().success();
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "depend", "1", "--on", "3"]);
+    cmd.assert().success();
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "edit", "1", "-s", "inprogress"]);
+    cmd.assert().success();
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "show", "1"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Déménagement ⛔ BLOQUÉE"))
+        .stdout(predicate::str::contains("Statut: ⚙ InProgress"))
+        .stdout(predicate::str::contains("Priorité: Medium"))
+        .stdout(predicate::str::contains("Échéance: aucune"))
+        .stdout(predicate::str::contains("Créée:"))
+        .stdout(predicate::str::contains("il y a moins d'une minute"))
+        .stdout(predicate::str::contains("Tags: maison, perso"))
+        .stdout(predicate::str::contains("Sous-tâches (0/1 terminée(s)):"))
+        .stdout(predicate::str::contains("○ [2] Cartons"))
+        .stdout(predicate::str::contains("Dépend de:"))
+        .stdout(predicate::str::contains("○ [3] Réserver le camion"))
+        .stdout(predicate::str::contains("Historique:"))
+        .stdout(predicate::str::contains("Statut: - → InProgress"));
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "show", "2"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("(en retard de"))
+        .stdout(predicate::str::contains("Parente: ⚙ [1] Déménagement"))
+        .stdout(predicate::str::contains("Historique").not());
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "show", "3"]);
+    cmd.assert().success().stdout(
+        predicate::str::contains("(dans 2 jours)").or(predicate::str::contains("(dans 3 jours)")),
+    );
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "--format", "json", "show", "1"]);
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let details: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(details["short_id"], 1);
+    assert_eq!(details["blocked"], true);
+    assert_eq!(details["subtasks"][0]["description"], "Cartons");
+    assert_eq!(details["dependencies"][0]["short_id"], 3);
+    assert_eq!(details["history"][0]["field"], "Dependencies");
+    assert_eq!(details["history"][1]["field"], "Status");
+    assert!(details["notes"].as_array().unwrap().is_empty());
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "show", "42"]);
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("Tâche introuvable"));
+}
+
+#[test]
+fn test_undo_redo_time_tracking() {
+    let db = temp_db_path();
+    let run = |args: &[&str]| {
+        let mut cmd = Command::cargo_bin("task-cli").unwrap();
+        cmd.args(["--db", &db]).args(args);
+        cmd.assert().success()
+    };
+
+    run(&["add", "Rédiger"]);
+    run(&["add", "Relire"]);
+    run(&["start", "1"]);
+    run(&["start", "2"]);
+
+    // Annuler le démarrage de [2] relance le suivi de [1]
+    run(&["undo"]).stdout(predicate::str::contains(
+        "Suivi du temps de la tâche [2] Relire",
+    ));
+    run(&["show", "1"]).stdout(predicate::str::contains("(en cours)"));
+    run(&["show", "2"])
+        .stdout(predicate::str::contains("(en cours)").not())
+        .stdout(predicate::str::contains("Statut: ○ NotStarted"));
+
+    // Annuler le premier démarrage ne laisse aucun suivi en cours
+    run(&["undo"]);
+    run(&["stop"]).stderr(predicate::str::contains("Aucune tâche n'est démarrée"));
+
+    run(&["redo"]);
+    run(&["show", "1"]).stdout(predicate::str::contains("(en cours)"));
+
+    run(&["stop"]);
+    run(&["undo"]);
+    run(&["show", "1"]).stdout(predicate::str::contains("(en cours)"));
+
+    // Terminer la tâche par `edit` arrête aussi son suivi, dans la même opération
+    run(&["edit", "1", "--status", "completed"]);
+    run(&["stop"]).stderr(predicate::str::contains("Aucune tâche n'est démarrée"));
+    run(&["undo"]);
+    run(&["show", "1"]).stdout(predicate::str::contains("(en cours)"));
+}
+
+#[test]
+fn test_start_stop_and_time_report() {
+    let db = temp_db_path();
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "add", "Rédiger", "-t", "doc"]);
+    cmd.assert().success();
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "project", "add", "Site"]);
+    cmd.assert().success();
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "add", "Relire", "--project", "Site"]);
+    cmd.assert().success();
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "stop"]);
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("Aucune tâche n'est démarrée"));
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "start", "1"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("▶ Suivi démarré: [1] Rédiger"));
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "start", "1"]);
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("La tâche [1] est déjà démarrée"));
+
+    // Démarrer une autre tâche arrête la première
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "start", "2"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "⏹ Suivi arrêté: [1] Rédiger (0 min)",
+        ))
+        .stdout(predicate::str::contains("▶ Suivi démarré: [2] Relire"));
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "show", "2"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Statut: ⚙ InProgress"))
+        .stdout(predicate::str::contains("Temps passé: 0 min (en cours)"));
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "stop", "1"]);
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("La tâche [1] n'est pas démarrée"));
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "stop", "2"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("⏹ Suivi arrêté: [2] Relire"));
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "complete", "1"]);
+    cmd.assert().success();
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "start", "1"]);
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("La tâche [1] est Completed"));
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args([
+        "--db", &db, "report", "time", "--since", "today", "--by", "project",
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Temps passé du"))
+        .stdout(predicate::str::contains("Site"))
+        .stdout(predicate::str::contains("(sans projet)"))
+        .stdout(predicate::str::contains("Total"));
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args([
+        "--db", &db, "--format", "json", "report", "time", "--by", "tag",
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("\"label\": \"doc\""))
+        .stdout(predicate::str::contains("\"total_seconds\""));
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "report", "time", "--until", "2000-01-01"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Aucun temps enregistré"));
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args([
+        "--db",
+        &db,
+        "report",
+        "time",
+        "--since",
+        "2024-03-02",
+        "--until",
+        "2024-03-01",
+    ]);
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("La date de début doit précéder"));
+
+    let _ = std::fs::remove_file(&db);
+}
+
+#[test]
+fn test_estimates_and_reports() {
+    let db = temp_db_path();
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args([
+        "--db",
+        &db,
+        "add",
+        "Rédiger",
+        "-t",
+        "doc",
+        "--estimate",
+        "1h30",
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Estimation: 1 h 30 min"));
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "add", "Relire", "-t", "doc,qa", "-e", "3pt"]);
+    cmd.assert().success();
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "add", "Tester", "-t", "qa", "-e", "5"]);
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("Estimation invalide '5'"));
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "add", "Tester", "-t", "qa"]);
+    cmd.assert().success();
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "edit", "3", "-e", "45m"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Estimation: 45 min"));
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "report", "estimate"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Estimations de 3 tâche(s)"))
+        .stdout(predicate::str::contains("-1 h 30 min (0%)"))
+        .stdout(predicate::str::contains("3 pt"))
+        .stdout(predicate::str::contains("Total (estimations en durée)"));
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "report", "load", "--by", "tag"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("par tag"))
+        .stdout(predicate::str::contains("1 h 30 min + 3 pt"))
+        .stdout(predicate::str::contains("45 min + 3 pt"));
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "edit", "3", "--estimate", "none"]);
+    cmd.assert().success();
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "report", "load", "--by", "week"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("(sans échéance)"))
+        .stdout(predicate::str::contains("(1 non estimée(s))"));
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args([
+        "--db", &db, "--format", "json", "report", "load", "--tag", "qa",
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("\"points\": 3"))
+        .stdout(predicate::str::contains("\"unestimated\": 1"));
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "history", "3"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Estimation: 45m → -"));
+
+    let _ = std::fs::remove_file(&db);
+}
+
+#[test]
+fn test_list_sorted_by_urgency() {
+    let db = temp_db_path();
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "add", "Basse", "-p", "low"]);
+    cmd.assert().success();
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "add", "En retard", "--due", "yesterday"]);
+    cmd.assert().success();
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "add", "Haute", "-p", "high"]);
+    cmd.assert().success();
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "depend", "3", "--on", "1"]);
+    cmd.assert().success();
+
+    // En retard (3.9 + 12 + 3), puis basse (1.8), puis haute mais bloquée (6 - 5)
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "list", "--sort", "urgency"]);
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let stdout = String::from_utf8(output).unwrap();
+    let late = stdout.find("[2] En retard").unwrap();
+    let low = stdout.find("[1] Basse").unwrap();
+    let high = stdout.find("[3] Haute").unwrap();
+    assert!(late < low && low < high, "{}", stdout);
+    assert!(stdout.contains("Urgence: 18.9"));
+    assert!(stdout.contains("Urgence: 1.0"));
+
+    // Coefficients redéfinis : le blocage ne pénalise plus
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.env("TASK_CLI_URGENCY", "blocked=0")
+        .args(["--db", &db, "--format", "json", "list", "--sort", "urgency"]);
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let ranked: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(ranked[0]["short_id"], 2);
+    assert_eq!(ranked[1]["short_id"], 3);
+    assert_eq!(ranked[1]["urgency"], 6.0);
+    assert_eq!(ranked[1]["blocked_by"], serde_json::json!([1]));
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.env("TASK_CLI_URGENCY", "vitesse=2")
+        .args(["--db", &db, "list", "--sort", "urgency"]);
+    cmd.assert().success().stderr(predicate::str::contains(
+        "Coefficient d'urgence invalide 'vitesse=2'",
+    ));
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "show", "2"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Urgence: 18.9"));
+
+    let _ = std::fs::remove_file(&db);
+}
+
+#[test]
+fn test_list_and_search_sort_and_pagination() {
+    let db = temp_db_path();
+
+    for (description, priority, due) in [
+        ("Rapport mensuel", "low", Some("2026-11-02")),
+        ("Rapport annuel", "high", Some("2026-11-02")),
+        ("Rapport client", "medium", None),
+        ("Archiver le rapport", "high", Some("2026-10-30")),
+    ] {
+        let mut cmd = Command::cargo_bin("task-cli").unwrap();
+        cmd.args(["--db", &db, "add", description, "-p", priority]);
+        if let Some(due) = due {
+            cmd.args(["--due", due]);
+        }
+        cmd.assert().success();
+    }
+
+    let short_ids = |args: &[&str]| -> Vec<i64> {
+        let mut cmd = Command::cargo_bin("task-cli").unwrap();
+        cmd.args(["--db", &db, "--format", "json"]).args(args);
+        let output = cmd.assert().success().get_output().stdout.clone();
+        let tasks: serde_json::Value = serde_json::from_slice(&output).unwrap();
+        tasks
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|t| t["short_id"].as_i64().unwrap())
+            .collect()
+    };
+
+    // Échéance, sans échéance en dernier ; priorité dans l'ordre logique à échéance égale
+    assert_eq!(
+        short_ids(&["list", "--sort", "due,-priority"]),
+        vec![4, 2, 1, 3]
+    );
+    assert_eq!(
+        short_ids(&["list", "--sort", "priority,description"]),
+        vec![1, 3, 4, 2]
+    );
+    assert_eq!(
+        short_ids(&[
+            "list",
+            "--sort",
+            "due,-priority",
+            "--limit",
+            "2",
+            "--offset",
+            "1"
+        ]),
+        vec![2, 1]
+    );
+    assert_eq!(short_ids(&["list", "--offset", "3"]), vec![1]);
+    assert_eq!(
+        short_ids(&[
+            "search",
+            "rapport",
+            "--sort",
+            "-priority,id",
+            "--limit",
+            "3"
+        ]),
+        vec![2, 4, 3]
+    );
+
+    // Le texte suit l'ordre demandé, à plat
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "list", "--sort", "description", "--limit", "2"]);
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let stdout = String::from_utf8(output).unwrap();
+    assert!(stdout.contains("2 tâche(s) trouvée(s)"));
+    assert!(stdout.find("[4] Archiver").unwrap() < stdout.find("[2] Rapport annuel").unwrap());
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "list", "--sort", "due,taille"]);
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("Critère de tri invalide 'taille'"));
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "list", "--sort", "urgency,due"]);
+    cmd.assert().success().stderr(predicate::str::contains(
+        "Le tri par urgence ne se combine pas",
+    ));
+
+    let mut cmd = Command::cargo_bin("task-cli").unwrap();
+    cmd.args(["--db", &db, "search", "rapport", "--limit", "dix"]);
+    cmd.assert().success().stderr(predicate::str::contains(
+        "Nombre invalide 'dix' pour --limit",
+    ));
+
+    let _ = std::fs::remove_file(&db);
+}